@@ -2838,6 +2838,27 @@ impl<'env> FieldEnv<'env> {
         }
     }
 
+    /// Get the location of this field's declaration, if it is declared in Move.
+    pub fn get_loc(&self) -> Option<Loc> {
+        if let FieldInfo::Declared { def_idx } = &self.data.info {
+            let smap = self
+                .struct_env
+                .module_env
+                .data
+                .source_map
+                .get_struct_source_map(*def_idx)
+                .ok()?;
+            Some(
+                self.struct_env
+                    .module_env
+                    .env
+                    .to_loc(&smap.fields[self.data.offset]),
+            )
+        } else {
+            None
+        }
+    }
+
     /// Get documentation associated with this field.
     pub fn get_doc(&self) -> &str {
         if let FieldInfo::Declared { def_idx } = &self.data.info {
@@ -2,8 +2,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{rt_types::*, vector::TypedMoveBorrowedRustVec};
-use core::mem;
+use crate::{rt_types::*, target_defs, vector::TypedMoveBorrowedRustVec};
+use alloc::alloc::Layout;
+use core::{mem, ptr};
 
 /// This is a placeholder for the unstable `ptr::invalid_mut`.
 ///
@@ -119,6 +120,169 @@ pub unsafe fn raw_borrow_move_value_as_rust_value(
     }
 }
 
+/// A Move value that has been moved out of Move storage, e.g. removed
+/// from a vector.
+///
+/// Like `MoveUntypedVector` this has no destructor. The value must either
+/// be moved back into Move storage with `write_to` or freed with `destroy`,
+/// otherwise anything it owns is leaked.
+///
+/// As in `BorrowedTypedMoveValue`, the type of `Vector` and `Reference` is
+/// the element type, and the type of `Struct` is the struct's own type.
+/// A struct is held in a heap buffer with the struct's size and alignment.
+pub enum OwnedMoveValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(MoveAddress),
+    Signer(MoveSigner),
+    Vector(MoveType, MoveUntypedVector),
+    Struct(MoveType, *mut AnyValue),
+    Reference(MoveType, MoveUntypedReference),
+}
+
+impl OwnedMoveValue {
+    /// Moves a value of type `type_` out of `src`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must hold an initialized value of type `type_`. Ownership
+    /// passes to the returned value, so `src` must be treated as
+    /// uninitialized afterwards.
+    pub unsafe fn read_from(type_: &MoveType, src: *const AnyValue) -> OwnedMoveValue {
+        match type_.type_desc {
+            TypeDesc::Bool => OwnedMoveValue::Bool(ptr::read(src as *const bool)),
+            TypeDesc::U8 => OwnedMoveValue::U8(ptr::read(src as *const u8)),
+            TypeDesc::U16 => OwnedMoveValue::U16(ptr::read(src as *const u16)),
+            TypeDesc::U32 => OwnedMoveValue::U32(ptr::read(src as *const u32)),
+            TypeDesc::U64 => OwnedMoveValue::U64(ptr::read(src as *const u64)),
+            TypeDesc::U128 => OwnedMoveValue::U128(ptr::read(src as *const u128)),
+            TypeDesc::U256 => OwnedMoveValue::U256(ptr::read(src as *const U256)),
+            TypeDesc::Address => OwnedMoveValue::Address(ptr::read(src as *const MoveAddress)),
+            TypeDesc::Signer => OwnedMoveValue::Signer(ptr::read(src as *const MoveSigner)),
+            TypeDesc::Vector => {
+                let element_type = *(*type_.type_info).vector.element_type;
+                let v = ptr::read(src as *const MoveUntypedVector);
+                OwnedMoveValue::Vector(element_type, v)
+            }
+            TypeDesc::Struct => {
+                let layout = struct_layout(type_);
+                let buf = alloc::alloc::alloc(layout);
+                if buf.is_null() {
+                    alloc::alloc::handle_alloc_error(layout);
+                }
+                ptr::copy_nonoverlapping(src as *const u8, buf, layout.size());
+                OwnedMoveValue::Struct(*type_, buf as *mut AnyValue)
+            }
+            TypeDesc::Reference => {
+                let element_type = *(*type_.type_info).reference.element_type;
+                let r = ptr::read(src as *const MoveUntypedReference);
+                OwnedMoveValue::Reference(element_type, r)
+            }
+        }
+    }
+
+    /// Copies a value of type `type_` out of `src`, deeply, so the copy
+    /// owns its own vectors and `src` is left as it is.
+    ///
+    /// # Safety
+    ///
+    /// `src` must hold an initialized value of type `type_`.
+    pub unsafe fn copy_from(type_: &MoveType, src: &AnyValue) -> OwnedMoveValue {
+        match type_.type_desc {
+            TypeDesc::Vector => {
+                let element_type = *(*type_.type_info).vector.element_type;
+                let v = &*(src as *const AnyValue as *const MoveUntypedVector);
+                OwnedMoveValue::Vector(element_type, v.copy(&element_type))
+            }
+            TypeDesc::Struct => {
+                let layout = struct_layout(type_);
+                let buf = alloc::alloc::alloc(layout);
+                if buf.is_null() {
+                    alloc::alloc::handle_alloc_error(layout);
+                }
+                crate::structs::copy(&(*type_.type_info).struct_, src, buf as *mut AnyValue);
+                OwnedMoveValue::Struct(*type_, buf as *mut AnyValue)
+            }
+            // Plain data.
+            _ => OwnedMoveValue::read_from(type_, src),
+        }
+    }
+
+    /// Moves the value into `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must be valid for writes of the value's type. Any value
+    /// already in `dst` is overwritten without being destroyed.
+    pub unsafe fn write_to(self, dst: *mut AnyValue) {
+        match self {
+            OwnedMoveValue::Bool(v) => ptr::write(dst as *mut bool, v),
+            OwnedMoveValue::U8(v) => ptr::write(dst as *mut u8, v),
+            OwnedMoveValue::U16(v) => ptr::write(dst as *mut u16, v),
+            OwnedMoveValue::U32(v) => ptr::write(dst as *mut u32, v),
+            OwnedMoveValue::U64(v) => ptr::write(dst as *mut u64, v),
+            OwnedMoveValue::U128(v) => ptr::write(dst as *mut u128, v),
+            OwnedMoveValue::U256(v) => ptr::write(dst as *mut U256, v),
+            OwnedMoveValue::Address(v) => ptr::write(dst as *mut MoveAddress, v),
+            OwnedMoveValue::Signer(v) => ptr::write(dst as *mut MoveSigner, v),
+            OwnedMoveValue::Vector(_t, v) => ptr::write(dst as *mut MoveUntypedVector, v),
+            OwnedMoveValue::Struct(t, buf) => {
+                let layout = struct_layout(&t);
+                ptr::copy_nonoverlapping(buf as *const u8, dst as *mut u8, layout.size());
+                alloc::alloc::dealloc(buf as *mut u8, layout);
+            }
+            OwnedMoveValue::Reference(_t, v) => ptr::write(dst as *mut MoveUntypedReference, v),
+        }
+    }
+
+    /// Destroys the value, freeing anything it owns.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe because the stored types must be correct.
+    pub unsafe fn destroy(self) {
+        match self {
+            OwnedMoveValue::Vector(t, v) => v.destroy(&t),
+            OwnedMoveValue::Struct(t, buf) => {
+                crate::structs::destroy(&(*t.type_info).struct_, buf);
+                alloc::alloc::dealloc(buf as *mut u8, struct_layout(&t));
+            }
+            _ => { /* nop */ }
+        }
+    }
+}
+
+unsafe fn struct_layout(type_: &MoveType) -> Layout {
+    let st_info = &(*type_.type_info).struct_;
+    let size = usize::try_from(st_info.size).expect("overflow");
+    let alignment = usize::try_from(st_info.alignment).expect("overflow");
+    assert!(size != 0); // can't handle ZSTs
+    Layout::from_size_align(size, alignment).expect("bad size or alignment")
+}
+
+/// Converts a `vector<u8>` to an address.
+///
+/// Returns `None` if the length is not that of an address.
+pub fn bytes_to_address(v: &TypedMoveBorrowedRustVec) -> Option<MoveAddress> {
+    match v {
+        TypedMoveBorrowedRustVec::U8(v) => {
+            let bytes = <[u8; target_defs::ACCOUNT_ADDRESS_LENGTH]>::try_from(&v[..]).ok()?;
+            Some(MoveAddress(bytes))
+        }
+        _ => panic!("expected a vector<u8>"),
+    }
+}
+
+/// Converts an address to a `vector<u8>` of its bytes.
+pub fn address_to_bytes(addr: &MoveAddress) -> MoveUntypedVector {
+    MoveUntypedVector::from_rust_vec(addr.0.to_vec())
+}
+
 impl<'mv> core::fmt::Debug for BorrowedTypedMoveValue<'mv> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -136,12 +300,9 @@ impl<'mv> core::fmt::Debug for BorrowedTypedMoveValue<'mv> {
                 rv.fmt(f)
             },
             BorrowedTypedMoveValue::Struct(t, v) => unsafe {
-                let st = (*(t.type_info)).struct_;
                 write!(f, "{} {{ ", t.name.as_ascii_str())?;
-                let fields = crate::structs::walk_fields(&st, v);
-                for (type_, ref_, fld_name) in fields {
-                    let rv = borrow_move_value_as_rust_value(type_, ref_);
-                    write!(f, "{}: ", fld_name.as_ascii_str())?;
+                for (fld_name, rv) in crate::structs::struct_to_pairs(t, v) {
+                    write!(f, "{}: ", fld_name)?;
                     rv.fmt(f)?;
                     f.write_str(", ")?;
                 }
@@ -247,10 +247,23 @@ pub struct MoveAsciiString {
 #[derive(Debug)]
 pub struct MoveUntypedReference(pub *const AnyValue);
 
-#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Copy, Clone, PartialEq)]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct U256(pub [u128; 2]);
 
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // The word order is ethnum's, so compare as ethnum::U256.
+        ethnum::U256(self.0).cmp(&ethnum::U256(other.0))
+    }
+}
+
 impl core::fmt::Debug for U256 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Printing is not trivial. Defer to ethnum::U256.
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{conv::*, rt_types::*, vector::*};
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use borsh::{BorshDeserialize, BorshSerialize};
-use core::ptr;
+use core::{hash::Hasher, mem, ptr};
+use sha2::{Digest, Sha256};
 
 fn borsh_to_buf<T: BorshSerialize>(v: &T, buf: &mut Vec<u8>) {
     borsh::to_writer(buf, v).expect("serialization failure")
@@ -15,6 +16,56 @@ fn borsh_from_slice<T: BorshDeserialize>(buf: &mut &[u8]) -> T {
     BorshDeserialize::deserialize(buf).expect("deserialization failure")
 }
 
+/// Encodes a vector length as BCS does, in ULEB128, returning the buffer
+/// and the number of bytes of it used.
+fn encode_len(len: usize) -> ([u8; 5], usize) {
+    let mut len: u32 = len.try_into().expect("overlong vector");
+    let mut buf = [0; 5];
+    let mut n = 0;
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            buf[n] = byte;
+            return (buf, n + 1);
+        }
+        buf[n] = byte | 0x80;
+        n += 1;
+    }
+}
+
+fn write_len(len: usize, buf: &mut Vec<u8>) {
+    let (bytes, n) = encode_len(len);
+    buf.extend_from_slice(&bytes[..n]);
+}
+
+/// The number of bytes of the length prefix of a vector of `len` elements.
+fn len_size(len: usize) -> usize {
+    encode_len(len).1
+}
+
+/// Reads a vector length prefix from the front of `bytes`.
+///
+/// As in BCS, the prefix must be the shortest encoding of a `u32`, so
+/// each length has exactly one encoding.
+fn read_len(bytes: &mut &[u8]) -> Result<usize, DecodeError> {
+    let mut len: u64 = 0;
+    for shift in (0..35).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(DecodeError::Truncated)?;
+        *bytes = rest;
+        len |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                // A redundant trailing zero group.
+                return Err(DecodeError::Invalid);
+            }
+            let len = u32::try_from(len).map_err(|_| DecodeError::Invalid)?;
+            return Ok(usize::try_from(len).expect("overflow"));
+        }
+    }
+    Err(DecodeError::Invalid)
+}
+
 pub unsafe fn serialize(type_v: &MoveType, v: &AnyValue) -> MoveByteVector {
     let mut buf = Vec::new();
     serialize_to_buf(type_v, v, &mut buf);
@@ -70,6 +121,196 @@ pub unsafe fn deserialize(type_v: &MoveType, bytes: &MoveByteVector, v: *mut Any
     assert!(bytes.is_empty());
 }
 
+/// Whether `bytes` is exactly the serialization of the struct at `s`.
+///
+/// The struct is compared field by field as it would be serialized,
+/// stopping at the first mismatch, so no serialized copy is built. The
+/// result is that of comparing `bytes` with the output of `serialize`.
+pub unsafe fn struct_eq_bytes(type_ve: &MoveType, s: &AnyValue, bytes: &[u8]) -> bool {
+    assert_eq!(type_ve.type_desc, TypeDesc::Struct);
+    let mut rest = bytes;
+    struct_eq_prefix(type_ve, s, &mut rest) && rest.is_empty()
+}
+
+/// Computes the CRC-32 (IEEE) of the struct's serialization.
+///
+/// Being over the serialization, the checksum does not depend on padding
+/// or on where owned data lives. It detects accidental changes only, use
+/// a cryptographic hash against deliberate ones.
+pub unsafe fn struct_checksum(type_ve: &MoveType, s: &AnyValue) -> u32 {
+    assert_eq!(type_ve.type_desc, TypeDesc::Struct);
+    let mut buf = Vec::new();
+    serialize_struct(type_ve, s, &mut buf);
+    crc32(&buf)
+}
+
+/// Bitwise CRC-32 with the reflected IEEE polynomial, as in zlib.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Strips `expected` from the front of `bytes`, if it is there.
+fn eat(bytes: &mut &[u8], expected: &[u8]) -> bool {
+    match bytes.strip_prefix(expected) {
+        Some(rest) => {
+            *bytes = rest;
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe fn value_eq_prefix(type_v: &MoveType, v: &AnyValue, bytes: &mut &[u8]) -> bool {
+    // These are the encodings `serialize_to_buf` writes.
+    match borrow_move_value_as_rust_value(type_v, v) {
+        BorrowedTypedMoveValue::Bool(v) => eat(bytes, &[u8::from(*v)]),
+        BorrowedTypedMoveValue::U8(v) => eat(bytes, &v.to_le_bytes()),
+        BorrowedTypedMoveValue::U16(v) => eat(bytes, &v.to_le_bytes()),
+        BorrowedTypedMoveValue::U32(v) => eat(bytes, &v.to_le_bytes()),
+        BorrowedTypedMoveValue::U64(v) => eat(bytes, &v.to_le_bytes()),
+        BorrowedTypedMoveValue::U128(v) => eat(bytes, &v.to_le_bytes()),
+        BorrowedTypedMoveValue::U256(v) => v.0.iter().all(|w| eat(bytes, &w.to_le_bytes())),
+        BorrowedTypedMoveValue::Address(v) => eat(bytes, &v.0),
+        BorrowedTypedMoveValue::Signer(v) => eat(bytes, &v.0 .0),
+        BorrowedTypedMoveValue::Vector(t, v) => {
+            let rv = TypedMoveBorrowedRustVec::new(&t, v);
+            let (len, n) = encode_len(usize::try_from(rv.len()).expect("overflow"));
+            if !eat(bytes, &len[..n]) {
+                return false;
+            }
+            match rv {
+                TypedMoveBorrowedRustVec::U8(v) => eat(bytes, &v),
+                _ => (0..rv.len()).all(|i| value_eq_prefix(&t, rv.borrow(i), bytes)),
+            }
+        }
+        BorrowedTypedMoveValue::Struct(t, v) => struct_eq_prefix(&t, v, bytes),
+        BorrowedTypedMoveValue::Reference(_, _) => {
+            todo!("impossible case?");
+        }
+    }
+}
+
+unsafe fn struct_eq_prefix(t: &MoveType, v: &AnyValue, bytes: &mut &[u8]) -> bool {
+    let structinfo = &(*(t.type_info)).struct_;
+    crate::structs::walk_fields(structinfo, v).all(|(ft, fv, _)| value_eq_prefix(ft, fv, bytes))
+}
+
+/// Why a serialized field could not be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The struct has no field of the given name.
+    NoSuchField,
+    /// The input ends before the value does.
+    Truncated,
+    /// The input is not a valid encoding of the value.
+    Invalid,
+}
+
+/// Decodes the BCS serialized field `field_name` of the struct at `s` from
+/// the front of `bytes`, in place, and returns the number of bytes decoded.
+///
+/// The previous field value is destroyed. Nothing is changed if the
+/// input does not decode.
+///
+/// # Safety
+///
+/// `s` must point to a valid struct of type `type_ve`.
+pub unsafe fn update_field_bcs(
+    type_ve: &MoveType,
+    s: *mut AnyValue,
+    field_name: &str,
+    bytes: &[u8],
+) -> Result<usize, DecodeError> {
+    let st_info = &(*type_ve.type_info).struct_;
+    let (fld_ty, fld_ptr) =
+        crate::structs::find_field_mut(st_info, s, field_name).ok_or(DecodeError::NoSuchField)?;
+    // Checked up front, so that the decoding below can't fail halfway.
+    let len = validate(fld_ty, &mut &bytes[..])?;
+
+    OwnedMoveValue::read_from(fld_ty, fld_ptr).destroy();
+    deserialize_from_slice(fld_ty, &mut &bytes[..len], fld_ptr);
+    Ok(len)
+}
+
+/// Checks that `bytes` starts with a BCS serialized value of `type_v`,
+/// advancing past it, and returns its length.
+///
+/// Vector length prefixes must be canonical ULEB128, as for `deserialize`.
+unsafe fn validate(type_v: &MoveType, bytes: &mut &[u8]) -> Result<usize, DecodeError> {
+    fn take<'b>(bytes: &mut &'b [u8], n: usize) -> Result<&'b [u8], DecodeError> {
+        if bytes.len() < n {
+            return Err(DecodeError::Truncated);
+        }
+        let (taken, rest) = bytes.split_at(n);
+        *bytes = rest;
+        Ok(taken)
+    }
+
+    let start_len = bytes.len();
+    match type_v.type_desc {
+        TypeDesc::Bool => {
+            if take(bytes, 1)?[0] > 1 {
+                return Err(DecodeError::Invalid);
+            }
+        }
+        TypeDesc::U8 => {
+            take(bytes, mem::size_of::<u8>())?;
+        }
+        TypeDesc::U16 => {
+            take(bytes, mem::size_of::<u16>())?;
+        }
+        TypeDesc::U32 => {
+            take(bytes, mem::size_of::<u32>())?;
+        }
+        TypeDesc::U64 => {
+            take(bytes, mem::size_of::<u64>())?;
+        }
+        TypeDesc::U128 => {
+            take(bytes, mem::size_of::<u128>())?;
+        }
+        TypeDesc::U256 => {
+            take(bytes, mem::size_of::<U256>())?;
+        }
+        TypeDesc::Address => {
+            take(bytes, mem::size_of::<MoveAddress>())?;
+        }
+        TypeDesc::Signer => {
+            take(bytes, mem::size_of::<MoveSigner>())?;
+        }
+        TypeDesc::Vector => {
+            let len = read_len(bytes)?;
+            // Every element takes at least one byte.
+            if len > bytes.len() {
+                return Err(DecodeError::Truncated);
+            }
+            let elt_type = (*type_v.type_info).vector.element_type;
+            for _ in 0..len {
+                validate(elt_type, bytes)?;
+            }
+        }
+        TypeDesc::Struct => {
+            let structinfo = &(*type_v.type_info).struct_;
+            let field_len = usize::try_from(structinfo.field_array_len).expect("overflow");
+            let fields = core::slice::from_raw_parts(structinfo.field_array_ptr, field_len);
+            for field in fields {
+                validate(&field.type_, bytes)?;
+            }
+        }
+        TypeDesc::Reference => {
+            todo!("impossible case?");
+        }
+    }
+    Ok(start_len - bytes.len())
+}
+
 unsafe fn deserialize_from_slice(type_v: &MoveType, bytes: &mut &[u8], v: *mut AnyValue) {
     // These writes are to uninitialized memory.
     // Using `ptr::write` guarantees that the destination is never read,
@@ -126,95 +367,333 @@ unsafe fn deserialize_from_slice(type_v: &MoveType, bytes: &mut &[u8], v: *mut A
 }
 
 unsafe fn serialize_vector(type_elt: &MoveType, v: &MoveUntypedVector, buf: &mut Vec<u8>) {
-    let v = TypedMoveBorrowedRustVec::new(type_elt, v);
-    match v {
-        TypedMoveBorrowedRustVec::Bool(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U8(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U16(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U32(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U64(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U128(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::U256(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::Address(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::Signer(v) => borsh_to_buf(&*v, buf),
-        TypedMoveBorrowedRustVec::Vector(t, v) => {
-            let len: u32 = v.len().try_into().expect("overlong vector");
-            borsh_to_buf(&len, buf);
-            for elt in v.iter() {
-                serialize_vector(&t, elt, buf);
-            }
-        }
-        TypedMoveBorrowedRustVec::Struct(v) => {
-            let len: u32 = v.len().try_into().expect("overlong vector");
-            borsh_to_buf(&len, buf);
-            for elt in v.iter() {
-                serialize_struct(v.type_(), elt, buf);
-            }
-        }
-        TypedMoveBorrowedRustVec::Reference(_, _) => {
-            todo!("impossible case?");
-        }
+    TypedMoveBorrowedRustVec::new(type_elt, v).serialize_unreserved(buf);
+}
+
+fn primitives_to_buf<T: BorshSerialize>(v: &[T], buf: &mut Vec<u8>) {
+    write_len(v.len(), buf);
+    for elt in v {
+        borsh_to_buf(elt, buf);
     }
 }
 
-unsafe fn deserialize_vector(type_elt: &MoveType, bytes: &mut &[u8]) -> MoveUntypedVector {
-    let mut mv = MoveUntypedVector::empty(type_elt);
-    let mut rv = TypedMoveBorrowedRustVecMut::new(type_elt, &mut mv);
-    match &mut rv {
-        TypedMoveBorrowedRustVecMut::Bool(v) => {
-            **v = borsh_from_slice(bytes);
-        }
-        TypedMoveBorrowedRustVecMut::U8(v) => {
-            **v = borsh_from_slice(bytes);
-        }
-        TypedMoveBorrowedRustVecMut::U16(v) => {
-            **v = borsh_from_slice(bytes);
-        }
-        TypedMoveBorrowedRustVecMut::U32(v) => {
-            **v = borsh_from_slice(bytes);
-        }
-        TypedMoveBorrowedRustVecMut::U64(v) => {
-            **v = borsh_from_slice(bytes);
+fn primitives_from_slice<T: BorshDeserialize>(bytes: &mut &[u8], len: usize) -> Vec<T> {
+    (0..len).map(|_| borsh_from_slice(bytes)).collect()
+}
+
+impl<'mv> TypedMoveBorrowedRustVec<'mv> {
+    /// Appends the BCS serialization of the vector to `out`: a ULEB128
+    /// length prefix, followed by each element in order.
+    ///
+    /// This is the vector encoding of `serialize`, which `deserialize` reads back.
+    pub unsafe fn serialize(&self, out: &mut Vec<u8>) {
+        // Nested vectors are written into the space reserved here.
+        out.reserve(self.serialized_size());
+        self.serialize_unreserved(out);
+    }
+
+    unsafe fn serialize_unreserved(&self, out: &mut Vec<u8>) {
+        match self {
+            TypedMoveBorrowedRustVec::Bool(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::U8(v) => {
+                // Bytes need no per-element encoding.
+                write_len(v.len(), out);
+                out.extend_from_slice(v);
+            }
+            TypedMoveBorrowedRustVec::U16(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::U32(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::U64(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::U128(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::U256(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::Address(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::Signer(v) => primitives_to_buf(v, out),
+            TypedMoveBorrowedRustVec::Vector(t, v) => {
+                write_len(v.len(), out);
+                for elt in v.iter() {
+                    serialize_vector(t, elt, out);
+                }
+            }
+            TypedMoveBorrowedRustVec::Struct(v) => {
+                write_len(v.len(), out);
+                for elt in v.iter() {
+                    serialize_struct(v.type_(), elt, out);
+                }
+            }
+            TypedMoveBorrowedRustVec::Reference(_, _) => {
+                todo!("impossible case?");
+            }
         }
-        TypedMoveBorrowedRustVecMut::U128(v) => {
-            **v = borsh_from_slice(bytes);
+    }
+
+    /// Feeds the serialized vector to `hasher`, without building it in memory.
+    ///
+    /// The hasher sees exactly the bytes `serialize` writes, so the result
+    /// is that of hashing the serialized vector.
+    pub unsafe fn hash_into(&self, hasher: &mut impl Hasher) {
+        fn hash_len(len: usize, hasher: &mut impl Hasher) {
+            let (len, n) = encode_len(len);
+            hasher.write(&len[..n]);
         }
-        TypedMoveBorrowedRustVecMut::U256(v) => {
-            **v = borsh_from_slice(bytes);
+
+        match self {
+            TypedMoveBorrowedRustVec::U8(v) => {
+                hash_len(v.len(), hasher);
+                hasher.write(v);
+            }
+            TypedMoveBorrowedRustVec::Vector(t, v) => {
+                hash_len(v.len(), hasher);
+                for elt in v.iter() {
+                    TypedMoveBorrowedRustVec::new(t, elt).hash_into(hasher);
+                }
+            }
+            TypedMoveBorrowedRustVec::Struct(v) => {
+                hash_len(v.len(), hasher);
+                for elt in v.iter() {
+                    hash_struct_into(v.type_(), elt, hasher);
+                }
+            }
+            TypedMoveBorrowedRustVec::Reference(_, _) => {
+                todo!("impossible case?");
+            }
+            _ => {
+                // Vectors of other primitives are contiguous in their encoding too.
+                let mut buf = Vec::new();
+                self.serialize(&mut buf);
+                hasher.write(&buf);
+            }
         }
-        TypedMoveBorrowedRustVecMut::Address(v) => {
-            **v = borsh_from_slice(bytes);
+    }
+
+    /// Folds the serialized elements, in order, into a running SHA-256 hash,
+    /// `hash(prev || element)`, starting from 32 zero bytes.
+    ///
+    /// Appending an element to the vector takes the hash one
+    /// `rolling_hash_step` further.
+    pub unsafe fn rolling_hash(&self) -> [u8; 32] {
+        let mut acc = [0; 32];
+        let mut buf = Vec::new();
+        for i in 0..self.len() {
+            let i = usize::try_from(i).expect("usize");
+            buf.clear();
+            self.serialize_element(i, &mut buf);
+            acc = rolling_hash_step(&acc, &buf);
+        }
+        acc
+    }
+
+    unsafe fn serialize_element(&self, i: usize, buf: &mut Vec<u8>) {
+        match self {
+            TypedMoveBorrowedRustVec::Bool(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U8(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U16(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U32(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U64(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U128(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::U256(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::Address(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::Signer(v) => borsh_to_buf(&v[i], buf),
+            TypedMoveBorrowedRustVec::Vector(t, v) => serialize_vector(t, &v[i], buf),
+            TypedMoveBorrowedRustVec::Struct(v) => serialize_struct(v.type_(), v.get(i), buf),
+            TypedMoveBorrowedRustVec::Reference(_, _) => {
+                todo!("impossible case?");
+            }
         }
-        TypedMoveBorrowedRustVecMut::Signer(v) => {
-            **v = borsh_from_slice(bytes);
+    }
+
+    /// The number of bytes `serialize` writes.
+    pub unsafe fn serialized_size(&self) -> usize {
+        // Every primitive is encoded as its in-memory bytes.
+        fn primitives_size<T>(v: &[T]) -> usize {
+            let elts_size = v.len().checked_mul(mem::size_of::<T>()).expect("overflow");
+            elts_size.checked_add(len_size(v.len())).expect("overflow")
         }
-        TypedMoveBorrowedRustVecMut::Vector(inner_elt_type, v) => {
-            let len: u32 = borsh_from_slice(bytes);
-            let len: usize = len as usize;
-            v.reserve_exact(len);
-            for _ in 0..len {
-                let eltv = deserialize_vector(inner_elt_type, bytes);
-                v.push(eltv);
+
+        match self {
+            TypedMoveBorrowedRustVec::Bool(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U8(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U16(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U32(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U64(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U128(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::U256(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::Address(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::Signer(v) => primitives_size(v),
+            TypedMoveBorrowedRustVec::Vector(t, v) => {
+                v.iter().fold(len_size(v.len()), |size, elt| {
+                    let elt_size = TypedMoveBorrowedRustVec::new(t, elt).serialized_size();
+                    size.checked_add(elt_size).expect("overflow")
+                })
+            }
+            TypedMoveBorrowedRustVec::Struct(v) => v.iter().fold(len_size(v.len()), |size, elt| {
+                let elt_size = serialized_struct_size(v.type_(), elt);
+                size.checked_add(elt_size).expect("overflow")
+            }),
+            TypedMoveBorrowedRustVec::Reference(_, _) => {
+                todo!("impossible case?");
             }
         }
-        TypedMoveBorrowedRustVecMut::Struct(vs) => {
-            let len: u32 = borsh_from_slice(bytes);
-            let len: usize = len as usize;
-            vs.reserve_exact(len);
-            for i in 0..len {
-                let eltptr = vs.get_mut_unchecked_raw(i);
-                deserialize_struct(type_elt, bytes, eltptr);
+    }
+}
+
+/// Extends the rolling hash `prev` with the serialized element `element`.
+pub fn rolling_hash_step(prev: &[u8; 32], element: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(prev);
+    hasher.update(element);
+    let mut next = [0; 32];
+    next.copy_from_slice(&hasher.finalize());
+    next
+}
+
+unsafe fn hash_struct_into(t: &MoveType, v: &AnyValue, hasher: &mut impl Hasher) {
+    assert_eq!(t.type_desc, TypeDesc::Struct);
+    let structinfo = &(*(t.type_info)).struct_;
+    let mut buf = Vec::new();
+    for (ft, fv, _) in crate::structs::walk_fields(structinfo, v) {
+        match ft.type_desc {
+            TypeDesc::Vector => {
+                let elt_type = (*ft.type_info).vector.element_type;
+                let fv = &*(fv as *const AnyValue as *const MoveUntypedVector);
+                TypedMoveBorrowedRustVec::new(elt_type, fv).hash_into(hasher);
+            }
+            TypeDesc::Struct => hash_struct_into(ft, fv, hasher),
+            _ => {
+                buf.clear();
+                serialize_to_buf(ft, fv, &mut buf);
+                hasher.write(&buf);
             }
-            vs.set_length(len);
         }
-        TypedMoveBorrowedRustVecMut::Reference(..) => {
+    }
+}
+
+unsafe fn serialized_struct_size(t: &MoveType, v: &AnyValue) -> usize {
+    assert_eq!(t.type_desc, TypeDesc::Struct);
+    let structinfo = &(*(t.type_info)).struct_;
+    let mut size: usize = 0;
+    for (ft, fv, _) in crate::structs::walk_fields(structinfo, v) {
+        let field_size = serialized_value_size(ft, fv);
+        size = size.checked_add(field_size).expect("overflow");
+    }
+    size
+}
+
+/// Pairs the name of each top-level field of the struct with the number
+/// of bytes of its serialization, in declaration order.
+///
+/// The sizes add up to the size of the serialized struct.
+pub unsafe fn serialized_field_sizes(type_ve: &MoveType, s: &AnyValue) -> Vec<(String, usize)> {
+    assert_eq!(type_ve.type_desc, TypeDesc::Struct);
+    let structinfo = &(*(type_ve.type_info)).struct_;
+    crate::structs::walk_fields(structinfo, s)
+        .map(|(ft, fv, fname)| {
+            (
+                String::from(fname.as_ascii_str()),
+                serialized_value_size(ft, fv),
+            )
+        })
+        .collect()
+}
+
+unsafe fn serialized_value_size(t: &MoveType, v: &AnyValue) -> usize {
+    match borrow_move_value_as_rust_value(t, v) {
+        BorrowedTypedMoveValue::Bool(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U8(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U16(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U32(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U64(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U128(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::U256(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::Address(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::Signer(v) => mem::size_of_val(v),
+        BorrowedTypedMoveValue::Vector(t, v) => {
+            TypedMoveBorrowedRustVec::new(&t, v).serialized_size()
+        }
+        BorrowedTypedMoveValue::Struct(t, v) => serialized_struct_size(&t, v),
+        BorrowedTypedMoveValue::Reference(_, _) => {
             todo!("impossible case?");
         }
     }
-    drop(rv);
+}
+
+unsafe fn deserialize_vector(type_elt: &MoveType, bytes: &mut &[u8]) -> MoveUntypedVector {
+    let (mv, consumed) = MoveUntypedVector::deserialize(type_elt, bytes);
+    *bytes = &bytes[consumed..];
     mv
 }
 
+impl MoveUntypedVector {
+    /// Reads a BCS serialized vector of `type_elt` elements, a ULEB128
+    /// length prefix and then the elements, from the front of `input`,
+    /// returning it and the number of bytes consumed.
+    ///
+    /// This is the inverse of `TypedMoveBorrowedRustVec::serialize`.
+    /// Panics if the input is truncated, if the length prefix is not
+    /// canonical, or if it is more than the remaining input could hold.
+    pub unsafe fn deserialize(type_elt: &MoveType, input: &[u8]) -> (MoveUntypedVector, usize) {
+        let mut rest = input;
+        let bytes = &mut rest;
+        let len = read_len(bytes).expect("deserialization failure");
+        // Every element takes at least one byte, so a longer prefix is
+        // corrupt, and must be rejected before anything is allocated for it.
+        assert!(len <= bytes.len(), "oversized vector length");
+
+        let mut mv = MoveUntypedVector::empty(type_elt);
+        let mut rv = TypedMoveBorrowedRustVecMut::new(type_elt, &mut mv);
+        match &mut rv {
+            TypedMoveBorrowedRustVecMut::Bool(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::U8(v) => {
+                let (elts, tail) = bytes.split_at(len);
+                **v = elts.to_vec();
+                *bytes = tail;
+            }
+            TypedMoveBorrowedRustVecMut::U16(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::U32(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::U64(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::U128(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::U256(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::Address(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::Signer(v) => {
+                **v = primitives_from_slice(bytes, len);
+            }
+            TypedMoveBorrowedRustVecMut::Vector(inner_elt_type, v) => {
+                v.reserve_exact(len);
+                for _ in 0..len {
+                    let eltv = deserialize_vector(inner_elt_type, bytes);
+                    v.push(eltv);
+                }
+            }
+            TypedMoveBorrowedRustVecMut::Struct(vs) => {
+                vs.reserve_exact(len);
+                for i in 0..len {
+                    let eltptr = vs.get_mut_unchecked_raw(i);
+                    deserialize_struct(type_elt, bytes, eltptr);
+                }
+                vs.set_length(len);
+            }
+            TypedMoveBorrowedRustVecMut::Reference(..) => {
+                todo!("impossible case?");
+            }
+        }
+        drop(rv);
+        let consumed = input.len() - rest.len();
+        (mv, consumed)
+    }
+}
+
 unsafe fn serialize_struct(t: &MoveType, v: &AnyValue, buf: &mut Vec<u8>) {
     assert_eq!(t.type_desc, TypeDesc::Struct);
     let structinfo = &(*(t.type_info)).struct_;
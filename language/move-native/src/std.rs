@@ -4,6 +4,7 @@
 
 mod bcs {
     use crate::rt_types::*;
+    use alloc::vec::Vec;
 
     /// Serialize any value.
     ///
@@ -31,6 +32,53 @@ mod bcs {
     ) {
         crate::serialization::deserialize(type_v, bytes, v)
     }
+
+    /// Decodes the serialized field `field_name` of the struct at `s` from
+    /// the front of `bytes`, in place, and returns the number of bytes read.
+    #[export_name = "move_native_bcs_update_field"]
+    unsafe extern "C" fn update_field(
+        type_v: &MoveType,
+        s: *mut AnyValue,
+        field_name: &MoveAsciiString,
+        bytes: &MoveByteVector,
+    ) -> u64 {
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        let bytes = bytes.as_rust_vec();
+        let len = crate::serialization::update_field_bcs(type_v, s, field_name, &bytes)
+            .expect("field does not decode");
+        u64::try_from(len).expect("u64")
+    }
+
+    /// Whether `bytes` is exactly the serialization of the struct `s`.
+    #[export_name = "move_native_bcs_struct_eq_bytes"]
+    unsafe extern "C" fn struct_eq_bytes(
+        type_v: &MoveType,
+        s: &AnyValue,
+        bytes: &MoveByteVector,
+    ) -> bool {
+        let bytes = bytes.as_rust_vec();
+        crate::serialization::struct_eq_bytes(type_v, s, &bytes)
+    }
+
+    /// Returns a `vector<u64>` of the serialized sizes of the fields of the
+    /// struct `s`, in declaration order.
+    #[export_name = "move_native_bcs_serialized_field_sizes"]
+    unsafe extern "C" fn serialized_field_sizes(
+        type_v: &MoveType,
+        s: &AnyValue,
+    ) -> MoveUntypedVector {
+        let sizes: Vec<u64> = crate::serialization::serialized_field_sizes(type_v, s)
+            .into_iter()
+            .map(|(_name, size)| u64::try_from(size).expect("u64"))
+            .collect();
+        MoveUntypedVector::from_rust_vec(sizes)
+    }
+
+    #[export_name = "move_native_bcs_struct_checksum"]
+    unsafe extern "C" fn struct_checksum(type_v: &MoveType, s: &AnyValue) -> u32 {
+        crate::serialization::struct_checksum(type_v, s)
+    }
 }
 
 // nursery
@@ -150,6 +198,21 @@ pub(crate) mod string {
     }
 }
 
+mod structs {
+    use crate::rt_types::*;
+
+    #[export_name = "move_native_structs_swap"]
+    unsafe extern "C" fn swap(type_ve: &MoveType, a: *mut AnyValue, b: *mut AnyValue) {
+        crate::structs::swap_struct(type_ve, a, b)
+    }
+
+    #[export_name = "move_native_structs_layout_fingerprint"]
+    unsafe extern "C" fn layout_fingerprint(type_ve: &MoveType) -> u64 {
+        assert_eq!(type_ve.type_desc, TypeDesc::Struct);
+        crate::structs::layout_fingerprint(&(*type_ve.type_info).struct_)
+    }
+}
+
 mod type_name {
     use crate::{rt_types::*, vector::MoveBorrowedRustVecMut};
 
@@ -190,8 +253,14 @@ mod unit_test {
 mod vector {
     use crate::{
         rt_types::*,
-        vector::{TypedMoveBorrowedRustVec, TypedMoveBorrowedRustVecMut},
+        vector::{
+            MoveBorrowedRustVec, MoveBorrowedRustVecMut, TypedMoveBorrowedRustVec,
+            TypedMoveBorrowedRustVecMut,
+        },
     };
+    use alloc::vec::Vec;
+    use core::hash::Hasher;
+    use sha2::{Digest, Sha256};
 
     // Safety: Even empty Rust vectors have non-null buffer pointers,
     // which must be correctly aligned. This function crates empty Rust vecs
@@ -247,4 +316,581 @@ mod vector {
     unsafe extern "C" fn swap(type_ve: &MoveType, v: &mut MoveUntypedVector, i: u64, j: u64) {
         TypedMoveBorrowedRustVecMut::new(type_ve, v).swap(i, j)
     }
+
+    #[export_name = "move_native_vector_split_off"]
+    unsafe extern "C" fn split_off(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        at: u64,
+    ) -> MoveUntypedVector {
+        let at = usize::try_from(at).expect("usize");
+        TypedMoveBorrowedRustVecMut::new(type_ve, v).split_off(at)
+    }
+
+    #[export_name = "move_native_vector_set_eq"]
+    unsafe extern "C" fn set_eq(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> bool {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        v1.set_eq(&v2)
+    }
+
+    #[export_name = "move_native_vector_remove"]
+    unsafe extern "C" fn remove(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        i: u64,
+        r: *mut AnyValue,
+    ) {
+        let i = usize::try_from(i).expect("usize");
+        TypedMoveBorrowedRustVecMut::new(type_ve, v)
+            .remove(i)
+            .write_to(r)
+    }
+
+    /// Copies the elements `start..end` into a new vector.
+    ///
+    /// Move has no borrowed slice type to return, but the window is
+    /// borrowed in place, so only its own elements are copied.
+    #[export_name = "move_native_vector_slice"]
+    unsafe extern "C" fn slice(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        start: u64,
+        end: u64,
+    ) -> MoveUntypedVector {
+        let start = usize::try_from(start).expect("usize");
+        let end = usize::try_from(end).expect("usize");
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        rust_vec.slice(start..end).copy_elements()
+    }
+
+    #[export_name = "move_native_vector_dedup"]
+    unsafe extern "C" fn dedup(type_ve: &MoveType, v: &mut MoveUntypedVector) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.dedup();
+    }
+
+    #[export_name = "move_native_vector_fill"]
+    unsafe extern "C" fn fill(type_ve: &MoveType, v: &mut MoveUntypedVector, value: &AnyValue) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.fill(value);
+    }
+
+    #[export_name = "move_native_vector_rotate_left"]
+    unsafe extern "C" fn rotate_left(type_ve: &MoveType, v: &mut MoveUntypedVector, n: u64) {
+        let n = usize::try_from(n).expect("usize");
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.rotate_left(n);
+    }
+
+    #[export_name = "move_native_vector_rotate_right"]
+    unsafe extern "C" fn rotate_right(type_ve: &MoveType, v: &mut MoveUntypedVector, n: u64) {
+        let n = usize::try_from(n).expect("usize");
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.rotate_right(n);
+    }
+
+    #[export_name = "move_native_vector_is_sorted"]
+    unsafe extern "C" fn is_sorted(type_ve: &MoveType, v: &MoveUntypedVector) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        rust_vec.is_sorted()
+    }
+
+    /// Returns whether `needle` was found, and writes its index, or the
+    /// index where it would be inserted, to `index`.
+    #[export_name = "move_native_vector_binary_search"]
+    unsafe extern "C" fn binary_search(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        needle: &AnyValue,
+        index: *mut u64,
+    ) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let (found, i) = match rust_vec.binary_search(needle) {
+            Ok(i) => (true, i),
+            Err(i) => (false, i),
+        };
+        *index = u64::try_from(i).expect("u64");
+        found
+    }
+
+    #[export_name = "move_native_vector_add_assign"]
+    unsafe extern "C" fn add_assign(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        other: &MoveUntypedVector,
+    ) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        let other = TypedMoveBorrowedRustVec::new(type_ve, other);
+        rust_vec.add_assign(&other);
+    }
+
+    /// Returns whether a struct element has field `field_name` equal to
+    /// `expected`, and writes the index of the first such element to `index`.
+    #[export_name = "move_native_vector_find_by_field"]
+    unsafe extern "C" fn find_by_field(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        field_name: &MoveAsciiString,
+        expected: &AnyValue,
+        index: *mut u64,
+    ) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        match rust_vec.find_by_field(field_name, expected) {
+            Some(i) => {
+                *index = u64::try_from(i).expect("u64");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves struct elements whose field `field_name` is less than `pivot`
+    /// to the front, and returns how many there are.
+    #[export_name = "move_native_vector_partition_by_field"]
+    unsafe extern "C" fn partition_by_field(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        field_name: &MoveAsciiString,
+        pivot: &AnyValue,
+    ) -> u64 {
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        let split =
+            TypedMoveBorrowedRustVecMut::new(type_ve, v).partition_by_field(field_name, pivot);
+        u64::try_from(split).expect("u64")
+    }
+
+    /// Serializes the vector, as `bcs::to_bytes` would a `vector<T>`.
+    #[export_name = "move_native_vector_serialize"]
+    unsafe extern "C" fn serialize(type_ve: &MoveType, v: &MoveUntypedVector) -> MoveByteVector {
+        let mut buf = Vec::new();
+        TypedMoveBorrowedRustVec::new(type_ve, v).serialize(&mut buf);
+        MoveByteVector::from_rust_vec(buf)
+    }
+
+    #[export_name = "move_native_vector_serialized_size"]
+    unsafe extern "C" fn serialized_size(type_ve: &MoveType, v: &MoveUntypedVector) -> u64 {
+        let size = TypedMoveBorrowedRustVec::new(type_ve, v).serialized_size();
+        u64::try_from(size).expect("u64")
+    }
+
+    /// Deserializes a vector of `type_elt` from the front of `bytes`, and
+    /// writes the number of bytes read to `consumed`.
+    #[export_name = "move_native_vector_deserialize"]
+    unsafe extern "C" fn deserialize(
+        type_elt: &MoveType,
+        bytes: &MoveByteVector,
+        consumed: *mut u64,
+    ) -> MoveUntypedVector {
+        let bytes = bytes.as_rust_vec();
+        let (v, n) = MoveUntypedVector::deserialize(type_elt, &bytes);
+        *consumed = u64::try_from(n).expect("u64");
+        v
+    }
+
+    /// Hashes the serialized vector with SHA2-256, feeding the hasher as
+    /// the vector is walked rather than serializing it first.
+    #[export_name = "move_native_vector_sha2_256"]
+    unsafe extern "C" fn sha2_256(type_ve: &MoveType, v: &MoveUntypedVector) -> MoveByteVector {
+        let mut hasher = DigestHasher(Sha256::new());
+        TypedMoveBorrowedRustVec::new(type_ve, v).hash_into(&mut hasher);
+        MoveByteVector::from_rust_vec(hasher.0.finalize().to_vec())
+    }
+
+    /// Feeds the bytes `hash_into` writes to a digest.
+    struct DigestHasher<D>(D);
+
+    impl<D: Digest + Clone> Hasher for DigestHasher<D> {
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.update(bytes);
+        }
+
+        /// The first eight bytes of the digest so far, little-endian.
+        fn finish(&self) -> u64 {
+            let digest = self.0.clone().finalize();
+            u64::from_le_bytes(digest[..8].try_into().expect("short digest"))
+        }
+    }
+
+    /// Copies an integer vector into a new vector of the wider integer
+    /// type `type_to`, zero-extending each element.
+    #[export_name = "move_native_vector_clone_widen"]
+    unsafe extern "C" fn clone_widen(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        type_to: &MoveType,
+    ) -> MoveUntypedVector {
+        TypedMoveBorrowedRustVec::new(type_ve, v).clone_widen(type_to)
+    }
+
+    #[export_name = "move_native_vector_clone_widen_u32_to_u64"]
+    unsafe extern "C" fn clone_widen_u32_to_u64(v: &MoveUntypedVector) -> MoveUntypedVector {
+        TypedMoveBorrowedRustVec::U32(MoveBorrowedRustVec::new(v)).clone_widen_u32_to_u64()
+    }
+
+    #[export_name = "move_native_vector_subbytes"]
+    unsafe extern "C" fn subbytes(v: &MoveByteVector, start: u64, len: u64) -> MoveUntypedVector {
+        let start = usize::try_from(start).expect("usize");
+        let len = usize::try_from(len).expect("usize");
+        bytes(v).subbytes(start, len)
+    }
+
+    /// Views a `vector<u8>`, which non-generic natives get without a type,
+    /// as a typed vector.
+    unsafe fn bytes(v: &MoveByteVector) -> TypedMoveBorrowedRustVec<'_> {
+        TypedMoveBorrowedRustVec::U8(v.as_rust_vec())
+    }
+
+    #[export_name = "move_native_vector_rolling_hash"]
+    unsafe extern "C" fn rolling_hash(type_ve: &MoveType, v: &MoveUntypedVector) -> MoveByteVector {
+        let hash = TypedMoveBorrowedRustVec::new(type_ve, v).rolling_hash();
+        MoveByteVector::from_rust_vec(hash.to_vec())
+    }
+
+    /// Extends the 32-byte rolling hash `prev` with the serialized `element`.
+    #[export_name = "move_native_vector_rolling_hash_step"]
+    unsafe extern "C" fn rolling_hash_step(
+        prev: &MoveByteVector,
+        element: &MoveByteVector,
+    ) -> MoveByteVector {
+        let prev = prev.as_rust_vec();
+        let prev = <&[u8; 32]>::try_from(&prev[..]).expect("hash of wrong length");
+        let hash = crate::serialization::rolling_hash_step(prev, &element.as_rust_vec());
+        MoveByteVector::from_rust_vec(hash.to_vec())
+    }
+
+    #[export_name = "move_native_vector_zip"]
+    unsafe extern "C" fn zip(
+        type_ve1: &MoveType,
+        type_ve2: &MoveType,
+        pair_type: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve1, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve2, v2);
+        v1.zip(&v2, pair_type)
+    }
+
+    #[export_name = "move_native_vector_count_ones"]
+    unsafe extern "C" fn count_ones(v: &MoveByteVector) -> u64 {
+        bytes(v).count_ones()
+    }
+
+    #[export_name = "move_native_vector_get_bit"]
+    unsafe extern "C" fn get_bit(v: &MoveByteVector, index: u64) -> bool {
+        let index = usize::try_from(index).expect("usize");
+        bytes(v).get_bit(index)
+    }
+
+    #[export_name = "move_native_vector_mask_with"]
+    unsafe extern "C" fn mask_with(v: &mut MoveByteVector, mask: &MoveByteVector) {
+        bytes_mut(v).mask_with(&mask.as_rust_vec());
+    }
+
+    #[export_name = "move_native_vector_and_mask_with"]
+    unsafe extern "C" fn and_mask_with(v: &mut MoveByteVector, mask: &MoveByteVector) {
+        bytes_mut(v).and_mask_with(&mask.as_rust_vec());
+    }
+
+    unsafe fn bytes_mut(v: &mut MoveByteVector) -> TypedMoveBorrowedRustVecMut<'_> {
+        // Safety: MoveUntypedVector and MoveByteVector have the same representation.
+        let v = &mut *(v as *mut MoveByteVector as *mut MoveUntypedVector);
+        TypedMoveBorrowedRustVecMut::U8(MoveBorrowedRustVecMut::new(v))
+    }
+
+    /// Consumes the vector of `type_ve` records `v`, moving their fields
+    /// into the columns of the `type_out` struct written to `out`.
+    #[export_name = "move_native_vector_transpose_to_columns"]
+    unsafe extern "C" fn transpose_to_columns(
+        type_ve: &MoveType,
+        v: MoveUntypedVector,
+        type_out: &MoveType,
+        out: *mut AnyValue,
+    ) {
+        assert_eq!(type_out.type_desc, TypeDesc::Struct);
+        v.transpose_to_columns(type_ve, &(*type_out.type_info).struct_, out)
+    }
+
+    #[export_name = "move_native_vector_prefix_sum"]
+    unsafe extern "C" fn prefix_sum(type_ve: &MoveType, v: &mut MoveUntypedVector) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.prefix_sum();
+    }
+
+    /// Returns a `vector<u64>` of the indices of the elements equal to an
+    /// earlier element.
+    #[export_name = "move_native_vector_find_duplicates"]
+    unsafe extern "C" fn find_duplicates(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let indices: Vec<u64> = rust_vec
+            .find_duplicates()
+            .into_iter()
+            .map(|i| u64::try_from(i).expect("u64"))
+            .collect();
+        MoveUntypedVector::from_rust_vec(indices)
+    }
+
+    #[export_name = "move_native_vector_merge_sorted"]
+    unsafe extern "C" fn merge_sorted(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        v1.merge_sorted(&v2)
+    }
+
+    #[export_name = "move_native_vector_intersect"]
+    unsafe extern "C" fn intersect(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        v1.intersect(&v2)
+    }
+
+    #[export_name = "move_native_vector_difference"]
+    unsafe extern "C" fn difference(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        v1.difference(&v2)
+    }
+
+    /// Returns a vector of the groups of struct elements with equal field
+    /// `field_name`, in order of first appearance. Each group's key is the
+    /// field of any of its elements.
+    #[export_name = "move_native_vector_group_by_field"]
+    unsafe extern "C" fn group_by_field(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        field_name: &MoveAsciiString,
+    ) -> MoveUntypedVector {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        let groups: Vec<MoveUntypedVector> = rust_vec
+            .group_by_field(field_name)
+            .into_iter()
+            .map(|(key, group)| {
+                key.destroy();
+                group
+            })
+            .collect();
+        MoveUntypedVector::from_rust_vec(groups)
+    }
+
+    #[export_name = "move_native_vector_flatten"]
+    unsafe extern "C" fn flatten(type_ve: &MoveType, v: &MoveUntypedVector) -> MoveUntypedVector {
+        TypedMoveBorrowedRustVec::new(type_ve, v).flatten()
+    }
+
+    /// Returns whether `v` has the length of an address, and if so writes
+    /// the address to `addr`.
+    #[export_name = "move_native_vector_bytes_to_address"]
+    unsafe extern "C" fn bytes_to_address(v: &MoveByteVector, addr: *mut MoveAddress) -> bool {
+        match crate::conv::bytes_to_address(&bytes(v)) {
+            Some(a) => {
+                *addr = a;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[export_name = "move_native_vector_address_to_bytes"]
+    unsafe extern "C" fn address_to_bytes(addr: &MoveAddress) -> MoveUntypedVector {
+        crate::conv::address_to_bytes(addr)
+    }
+
+    #[export_name = "move_native_vector_eq_mask"]
+    unsafe extern "C" fn eq_mask(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+    ) -> MoveUntypedVector {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        v1.eq_mask(&v2)
+    }
+
+    /// Reorders the elements so that the element at `perm[i]` comes to
+    /// `i`, with `perm` a `vector<u64>`.
+    #[export_name = "move_native_vector_apply_permutation"]
+    unsafe extern "C" fn apply_permutation(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        perm: &MoveUntypedVector,
+    ) {
+        let perm: Vec<usize> = MoveBorrowedRustVec::<u64>::new(perm)
+            .iter()
+            .map(|&i| usize::try_from(i).expect("usize"))
+            .collect();
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.apply_permutation(&perm);
+    }
+
+    #[export_name = "move_native_vector_chunks"]
+    unsafe extern "C" fn chunks(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        chunk_size: u64,
+    ) -> MoveUntypedVector {
+        let chunk_size = usize::try_from(chunk_size).expect("usize");
+        TypedMoveBorrowedRustVec::new(type_ve, v).chunks(chunk_size)
+    }
+
+    #[export_name = "move_native_vector_all_field_eq"]
+    unsafe extern "C" fn all_field_eq(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        field_name: &MoveAsciiString,
+        expected: &AnyValue,
+    ) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        rust_vec.all_field_eq(field_name, expected)
+    }
+
+    #[export_name = "move_native_vector_any_field_eq"]
+    unsafe extern "C" fn any_field_eq(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        field_name: &MoveAsciiString,
+        expected: &AnyValue,
+    ) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        rust_vec.any_field_eq(field_name, expected)
+    }
+
+    /// Like `binary_search`, for struct elements sorted by their field
+    /// `field_name`.
+    #[export_name = "move_native_vector_binary_search_by_field"]
+    unsafe extern "C" fn binary_search_by_field(
+        type_ve: &MoveType,
+        v: &MoveUntypedVector,
+        field_name: &MoveAsciiString,
+        key: &AnyValue,
+        index: *mut u64,
+    ) -> bool {
+        let rust_vec = TypedMoveBorrowedRustVec::new(type_ve, v);
+        let field_name = field_name.bytes.as_rust_vec();
+        let field_name = core::str::from_utf8(&field_name).expect("invalid utf8");
+        let (found, i) = match rust_vec.binary_search_by_field(field_name, key) {
+            Ok(i) => (true, i),
+            Err(i) => (false, i),
+        };
+        *index = u64::try_from(i).expect("u64");
+        found
+    }
+
+    #[export_name = "move_native_vector_retain_ne"]
+    unsafe extern "C" fn retain_ne(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        sentinel: &AnyValue,
+    ) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.retain_ne(sentinel);
+    }
+
+    #[export_name = "move_native_vector_min_with"]
+    unsafe extern "C" fn min_with(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        other: &MoveUntypedVector,
+    ) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        let other = TypedMoveBorrowedRustVec::new(type_ve, other);
+        rust_vec.min_with(&other);
+    }
+
+    #[export_name = "move_native_vector_max_with"]
+    unsafe extern "C" fn max_with(
+        type_ve: &MoveType,
+        v: &mut MoveUntypedVector,
+        other: &MoveUntypedVector,
+    ) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        let other = TypedMoveBorrowedRustVec::new(type_ve, other);
+        rust_vec.max_with(&other);
+    }
+
+    #[export_name = "move_native_vector_bytes_to_u64_le"]
+    unsafe extern "C" fn bytes_to_u64_le(v: &MoveByteVector) -> u64 {
+        bytes(v).bytes_to_u64_le()
+    }
+
+    /// Writes the `u128` to `r`, as 128-bit integers have no stable C ABI.
+    #[export_name = "move_native_vector_bytes_to_u128_le"]
+    unsafe extern "C" fn bytes_to_u128_le(v: &MoveByteVector, r: *mut AnyValue) {
+        *(r as *mut u128) = bytes(v).bytes_to_u128_le();
+    }
+
+    /// Writes the `u256` to `r`, as 128-bit integers have no stable C ABI.
+    #[export_name = "move_native_vector_bytes_to_u256_le"]
+    unsafe extern "C" fn bytes_to_u256_le(v: &MoveByteVector, r: *mut AnyValue) {
+        *(r as *mut U256) = bytes(v).bytes_to_u256_le();
+    }
+
+    #[export_name = "move_native_vector_byte_swap_elements"]
+    unsafe extern "C" fn byte_swap_elements(type_ve: &MoveType, v: &mut MoveUntypedVector) {
+        let mut rust_vec = TypedMoveBorrowedRustVecMut::new(type_ve, v);
+        rust_vec.byte_swap_elements();
+    }
+
+    /// Returns a `vector<u64>` of the counts of each byte value, indexed by
+    /// the value.
+    #[export_name = "move_native_vector_byte_histogram"]
+    unsafe extern "C" fn byte_histogram(v: &MoveByteVector) -> MoveUntypedVector {
+        MoveUntypedVector::from_rust_vec(bytes(v).byte_histogram().to_vec())
+    }
+
+    #[export_name = "move_native_vector_hamming_distance"]
+    unsafe extern "C" fn hamming_distance(v1: &MoveByteVector, v2: &MoveByteVector) -> u64 {
+        bytes(v1).hamming_distance(&bytes(v2))
+    }
+
+    #[export_name = "move_native_vector_starts_with"]
+    unsafe extern "C" fn starts_with(v: &MoveByteVector, prefix: &MoveByteVector) -> bool {
+        bytes(v).starts_with(&bytes(prefix))
+    }
+
+    #[export_name = "move_native_vector_ends_with"]
+    unsafe extern "C" fn ends_with(v: &MoveByteVector, suffix: &MoveByteVector) -> bool {
+        bytes(v).ends_with(&bytes(suffix))
+    }
+
+    /// Writes the `u128` to `r`, as 128-bit integers have no stable C ABI.
+    #[export_name = "move_native_vector_dot_product"]
+    unsafe extern "C" fn dot_product(
+        type_ve: &MoveType,
+        v1: &MoveUntypedVector,
+        v2: &MoveUntypedVector,
+        r: *mut AnyValue,
+    ) {
+        let v1 = TypedMoveBorrowedRustVec::new(type_ve, v1);
+        let v2 = TypedMoveBorrowedRustVec::new(type_ve, v2);
+        *(r as *mut u128) = v1.dot_product(&v2);
+    }
 }
@@ -2,8 +2,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{rt_types::*, vector::TypedMoveBorrowedRustVec};
-use core::slice;
+use crate::{conv::BorrowedTypedMoveValue, rt_types::*, vector::TypedMoveBorrowedRustVec};
+use alloc::vec::Vec;
+use core::{cmp::Ordering, ptr, slice};
 
 pub unsafe fn walk_fields<'mv>(
     info: &'mv StructTypeInfo,
@@ -36,6 +37,42 @@ pub unsafe fn walk_fields_mut<'mv>(
     })
 }
 
+/// Pairs the name of each field with its value, in declaration order.
+pub unsafe fn struct_to_pairs<'mv>(
+    type_ve: &'mv MoveType,
+    struct_ref: &'mv AnyValue,
+) -> Vec<(&'mv str, BorrowedTypedMoveValue<'mv>)> {
+    let st_info = &(*type_ve.type_info).struct_;
+    walk_fields(st_info, struct_ref)
+        .map(|(fld_ty, fld_ref, fld_name)| {
+            let rv = crate::conv::borrow_move_value_as_rust_value(fld_ty, fld_ref);
+            (fld_name.as_ascii_str(), rv)
+        })
+        .collect()
+}
+
+/// Finds the field named `name`, returning its type and value.
+pub unsafe fn find_field<'mv>(
+    info: &'mv StructTypeInfo,
+    struct_ref: &'mv AnyValue,
+    name: &str,
+) -> Option<(&'mv MoveType, &'mv AnyValue)> {
+    walk_fields(info, struct_ref)
+        .find(|(_fld_ty, _fld_ref, fld_name)| fld_name.as_ascii_str() == name)
+        .map(|(fld_ty, fld_ref, _fld_name)| (fld_ty, fld_ref))
+}
+
+/// Finds the field named `name`, returning its type and a pointer to it.
+pub unsafe fn find_field_mut<'mv>(
+    info: &'mv StructTypeInfo,
+    struct_ref: *mut AnyValue,
+    name: &str,
+) -> Option<(&'mv MoveType, *mut AnyValue)> {
+    walk_fields_mut(info, struct_ref)
+        .find(|(_fld_ty, _fld_ptr, fld_name)| fld_name.as_ascii_str() == name)
+        .map(|(fld_ty, fld_ptr, _fld_name)| (fld_ty, fld_ptr))
+}
+
 pub unsafe fn destroy(info: &StructTypeInfo, struct_ref: *mut AnyValue) {
     // nb: destroying from back to front. Move doesn't
     // have side-effecting dtors so drop order probably doesn't matter.
@@ -78,45 +115,213 @@ pub unsafe fn destroy(info: &StructTypeInfo, struct_ref: *mut AnyValue) {
     }
 }
 
+/// Copies the struct at `src` into `dst`, deep-copying any vectors it owns.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of the struct, and is overwritten
+/// without being destroyed.
+pub unsafe fn copy(info: &StructTypeInfo, src: &AnyValue, dst: *mut AnyValue) {
+    let size = usize::try_from(info.size).expect("overflow");
+    // Everything but vectors is plain data, so start from a bitwise copy.
+    ptr::copy_nonoverlapping(src as *const AnyValue as *const u8, dst as *mut u8, size);
+    copy_owned_fields(info, src, dst);
+}
+
+/// Exchanges the contents of the structs at `a` and `b`.
+///
+/// The vectors each struct owns move along with its bytes, so nothing is
+/// copied or destroyed.
+///
+/// # Safety
+///
+/// `a` and `b` must both point to structs of `type_ve`. They may be the
+/// same struct, but must not otherwise overlap.
+pub unsafe fn swap_struct(type_ve: &MoveType, a: *mut AnyValue, b: *mut AnyValue) {
+    if a == b {
+        return;
+    }
+    let size = usize::try_from((*type_ve.type_info).struct_.size).expect("overflow");
+    ptr::swap_nonoverlapping(a as *mut u8, b as *mut u8, size);
+}
+
+/// Replaces the bitwise-copied vectors in `dst` with copies of their own.
+unsafe fn copy_owned_fields(info: &StructTypeInfo, src: &AnyValue, dst: *mut AnyValue) {
+    let src_fields = walk_fields(info, src);
+    let dst_fields = walk_fields_mut(info, dst);
+    for ((ty, src_fld, _name), (_dst_ty, dst_fld, _dst_name)) in
+        Iterator::zip(src_fields, dst_fields)
+    {
+        match ty.type_desc {
+            TypeDesc::Vector => {
+                let elt_type = (*ty.type_info).vector.element_type;
+                let src_vec = &*(src_fld as *const AnyValue as *const MoveUntypedVector);
+                // nb: indirect recursive call, possible stack overflow.
+                ptr::write(dst_fld as *mut MoveUntypedVector, src_vec.copy(elt_type));
+            }
+            TypeDesc::Struct => {
+                let struct_type = &(*ty.type_info).struct_;
+                // nb: recursive call, possible stack overflow.
+                copy_owned_fields(struct_type, src_fld, dst_fld);
+            }
+            _ => { /* copied bitwise */ }
+        }
+    }
+}
+
+/// Hashes the layout of a struct: its size, alignment, and the names,
+/// offsets and types of its fields, recursively. Type names are left out,
+/// so structurally identical structs have the same fingerprint.
+///
+/// The hash is 64-bit FNV-1a, which is stable across builds and targets.
+pub unsafe fn layout_fingerprint(info: &StructTypeInfo) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hash_struct_layout(&mut hasher, info);
+    hasher.0
+}
+
+unsafe fn hash_struct_layout(hasher: &mut Fnv1a, info: &StructTypeInfo) {
+    hasher.write(&info.size.to_le_bytes());
+    hasher.write(&info.alignment.to_le_bytes());
+    hasher.write(&info.field_array_len.to_le_bytes());
+    let field_len = usize::try_from(info.field_array_len).expect("overflow");
+    let fields: &[StructFieldInfo] = slice::from_raw_parts(info.field_array_ptr, field_len);
+    for field in fields {
+        let name = field.name.as_ascii_str().as_bytes();
+        hasher.write(&(name.len() as u64).to_le_bytes());
+        hasher.write(name);
+        hasher.write(&field.offset.to_le_bytes());
+        hash_type_layout(hasher, &field.type_);
+    }
+}
+
+unsafe fn hash_type_layout(hasher: &mut Fnv1a, ty: &MoveType) {
+    hasher.write(&(ty.type_desc as u64).to_le_bytes());
+    match ty.type_desc {
+        TypeDesc::Vector => {
+            // nb: recursive call, possible stack overflow.
+            hash_type_layout(hasher, (*ty.type_info).vector.element_type);
+        }
+        TypeDesc::Reference => {
+            hash_type_layout(hasher, (*ty.type_info).reference.element_type);
+        }
+        TypeDesc::Struct => {
+            // nb: recursive call, possible stack overflow.
+            hash_struct_layout(hasher, &(*ty.type_info).struct_);
+        }
+        _ => {}
+    }
+}
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
 pub unsafe fn cmp_eq(type_ve: &MoveType, s1: &AnyValue, s2: &AnyValue) -> bool {
+    let st_info = (*(type_ve.type_info)).struct_;
+    let fields1 = walk_fields(&st_info, s1);
+    let fields2 = walk_fields(&st_info, s2);
+    for ((fld_ty, fld_ref1, _fld_name1), (_fld_ty2, fld_ref2, _fld_name2)) in
+        Iterator::zip(fields1, fields2)
+    {
+        if !field_cmp_eq(fld_ty, fld_ref1, fld_ref2) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Compares two field values of type `fld_ty` for equality.
+pub unsafe fn field_cmp_eq(fld_ty: &MoveType, fld_ref1: &AnyValue, fld_ref2: &AnyValue) -> bool {
     use crate::conv::{borrow_move_value_as_rust_value, BorrowedTypedMoveValue as BTMV};
 
+    let rv1 = borrow_move_value_as_rust_value(fld_ty, fld_ref1);
+    let rv2 = borrow_move_value_as_rust_value(fld_ty, fld_ref2);
+
+    match (rv1, rv2) {
+        (BTMV::Bool(val1), BTMV::Bool(val2)) => val1 == val2,
+        (BTMV::U8(val1), BTMV::U8(val2)) => val1 == val2,
+        (BTMV::U16(val1), BTMV::U16(val2)) => val1 == val2,
+        (BTMV::U32(val1), BTMV::U32(val2)) => val1 == val2,
+        (BTMV::U64(val1), BTMV::U64(val2)) => val1 == val2,
+        (BTMV::U128(val1), BTMV::U128(val2)) => val1 == val2,
+        (BTMV::U256(val1), BTMV::U256(val2)) => val1 == val2,
+        (BTMV::Address(val1), BTMV::Address(val2)) => val1 == val2,
+        (BTMV::Signer(val1), BTMV::Signer(val2)) => val1 == val2,
+        (BTMV::Vector(t1, utv1), BTMV::Vector(t2, utv2)) => {
+            let v1 = TypedMoveBorrowedRustVec::new(&t1, utv1);
+            let v2 = TypedMoveBorrowedRustVec::new(&t2, utv2);
+            v1.cmp_eq(&v2)
+        }
+        (BTMV::Struct(t1, anyv1), BTMV::Struct(_t2, anyv2)) => cmp_eq(&t1, anyv1, anyv2),
+        (BTMV::Reference(_, _), BTMV::Reference(_, _)) => {
+            unreachable!("reference in struct field impossible")
+        }
+        _ => {
+            unreachable!("struct_cmp_eq unexpected value combination")
+        }
+    }
+}
+
+/// Orders two structs of the same type field by field, in declaration order.
+pub unsafe fn cmp_ord(type_ve: &MoveType, s1: &AnyValue, s2: &AnyValue) -> Ordering {
     let st_info = (*(type_ve.type_info)).struct_;
     let fields1 = walk_fields(&st_info, s1);
     let fields2 = walk_fields(&st_info, s2);
-    for ((fld_ty1, fld_ref1, _fld_name1), (fld_ty2, fld_ref2, _fld_name2)) in
+    for ((fld_ty, fld_ref1, _fld_name1), (_fld_ty2, fld_ref2, _fld_name2)) in
         Iterator::zip(fields1, fields2)
     {
-        let rv1 = borrow_move_value_as_rust_value(fld_ty1, fld_ref1);
-        let rv2 = borrow_move_value_as_rust_value(fld_ty2, fld_ref2);
-
-        let is_eq = match (rv1, rv2) {
-            (BTMV::Bool(val1), BTMV::Bool(val2)) => val1 == val2,
-            (BTMV::U8(val1), BTMV::U8(val2)) => val1 == val2,
-            (BTMV::U16(val1), BTMV::U16(val2)) => val1 == val2,
-            (BTMV::U32(val1), BTMV::U32(val2)) => val1 == val2,
-            (BTMV::U64(val1), BTMV::U64(val2)) => val1 == val2,
-            (BTMV::U128(val1), BTMV::U128(val2)) => val1 == val2,
-            (BTMV::U256(val1), BTMV::U256(val2)) => val1 == val2,
-            (BTMV::Address(val1), BTMV::Address(val2)) => val1 == val2,
-            (BTMV::Signer(val1), BTMV::Signer(val2)) => val1 == val2,
-            (BTMV::Vector(t1, utv1), BTMV::Vector(t2, utv2)) => {
-                let v1 = TypedMoveBorrowedRustVec::new(&t1, utv1);
-                let v2 = TypedMoveBorrowedRustVec::new(&t2, utv2);
-                v1.cmp_eq(&v2)
-            }
-            (BTMV::Struct(t1, anyv1), BTMV::Struct(_t2, anyv2)) => cmp_eq(&t1, anyv1, anyv2),
-            (BTMV::Reference(_, _), BTMV::Reference(_, _)) => {
-                unreachable!("reference in struct field impossible")
-            }
-            _ => {
-                unreachable!("struct_cmp_eq unexpected value combination")
-            }
-        };
+        let ord = field_cmp_ord(fld_ty, fld_ref1, fld_ref2);
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
 
-        if !is_eq {
-            return false;
+/// Orders two field values of type `fld_ty`.
+pub unsafe fn field_cmp_ord(
+    fld_ty: &MoveType,
+    fld_ref1: &AnyValue,
+    fld_ref2: &AnyValue,
+) -> Ordering {
+    use crate::conv::{borrow_move_value_as_rust_value, BorrowedTypedMoveValue as BTMV};
+
+    let rv1 = borrow_move_value_as_rust_value(fld_ty, fld_ref1);
+    let rv2 = borrow_move_value_as_rust_value(fld_ty, fld_ref2);
+
+    match (rv1, rv2) {
+        (BTMV::Bool(val1), BTMV::Bool(val2)) => val1.cmp(val2),
+        (BTMV::U8(val1), BTMV::U8(val2)) => val1.cmp(val2),
+        (BTMV::U16(val1), BTMV::U16(val2)) => val1.cmp(val2),
+        (BTMV::U32(val1), BTMV::U32(val2)) => val1.cmp(val2),
+        (BTMV::U64(val1), BTMV::U64(val2)) => val1.cmp(val2),
+        (BTMV::U128(val1), BTMV::U128(val2)) => val1.cmp(val2),
+        (BTMV::U256(val1), BTMV::U256(val2)) => val1.cmp(val2),
+        (BTMV::Address(val1), BTMV::Address(val2)) => val1.0.cmp(&val2.0),
+        (BTMV::Signer(val1), BTMV::Signer(val2)) => val1.0 .0.cmp(&val2.0 .0),
+        (BTMV::Vector(t1, utv1), BTMV::Vector(t2, utv2)) => {
+            let v1 = TypedMoveBorrowedRustVec::new(&t1, utv1);
+            let v2 = TypedMoveBorrowedRustVec::new(&t2, utv2);
+            v1.cmp_ord(&v2)
+        }
+        (BTMV::Struct(t1, anyv1), BTMV::Struct(_t2, anyv2)) => cmp_ord(&t1, anyv1, anyv2),
+        (BTMV::Reference(_, _), BTMV::Reference(_, _)) => {
+            unreachable!("reference in struct field impossible")
+        }
+        _ => {
+            unreachable!("struct_cmp_ord unexpected value combination")
         }
     }
-    true
 }
@@ -4,14 +4,219 @@
 
 use super::*;
 use crate::{
+    conv::{address_to_bytes, bytes_to_address, BorrowedTypedMoveValue, OwnedMoveValue},
     rt_types::*,
     std::string::*,
+    structs::struct_to_pairs,
     target_defs::ACCOUNT_ADDRESS_LENGTH,
     vector::{TypedMoveBorrowedRustVec, TypedMoveBorrowedRustVecMut},
 };
-use alloc::{string::String, vec};
+use alloc::{string::String, vec, vec::Vec};
 use core::mem;
 
+// Element types shared by the tests.
+
+static BOOL_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::Bool,
+    type_info: &TypeInfo { nothing: 0 },
+};
+
+static U8_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::U8,
+    type_info: &TypeInfo { nothing: 0 },
+};
+
+static U32_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::U32,
+    type_info: &TypeInfo { nothing: 0 },
+};
+
+static U64_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::U64,
+    type_info: &TypeInfo { nothing: 0 },
+};
+
+static U256_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::U256,
+    type_info: &TypeInfo { nothing: 0 },
+};
+
+// Struct types shared by the tests of struct elements.
+
+static ID_FLD_NAME_SLICE: &[u8] = b"id";
+static ID_FLD_NAME: StaticName = StaticName {
+    ptr: ID_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 2,
+};
+
+static PAYLOAD_FLD_NAME_SLICE: &[u8] = b"payload";
+static PAYLOAD_FLD_NAME: StaticName = StaticName {
+    ptr: PAYLOAD_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 7,
+};
+
+static PAYLOAD_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::Vector,
+    type_info: &TypeInfo {
+        vector: VectorTypeInfo {
+            element_type: &U64_TYPE,
+        },
+    },
+};
+
+/// A struct that owns a vector.
+#[repr(C)]
+struct StructWithVector {
+    id: u64,
+    payload: MoveUntypedVector,
+}
+
+impl StructWithVector {
+    fn new(id: u64, payload: Vec<u64>) -> StructWithVector {
+        StructWithVector {
+            id,
+            payload: MoveUntypedVector::from_rust_vec(payload),
+        }
+    }
+}
+
+static STRUCT_WITH_VECTOR_FIELD_INFO: [StructFieldInfo; 2] = [
+    StructFieldInfo {
+        type_: U64_TYPE,
+        offset: 0,
+        name: ID_FLD_NAME,
+    },
+    StructFieldInfo {
+        type_: PAYLOAD_TYPE,
+        offset: mem::size_of::<u64>() as u64,
+        name: PAYLOAD_FLD_NAME,
+    },
+];
+
+static STRUCT_WITH_VECTOR_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::Struct,
+    type_info: &TypeInfo {
+        struct_: StructTypeInfo {
+            field_array_ptr: &STRUCT_WITH_VECTOR_FIELD_INFO[0],
+            field_array_len: 2,
+            size: mem::size_of::<StructWithVector>() as u64,
+            alignment: mem::align_of::<StructWithVector>() as u64,
+        },
+    },
+};
+
+static MAJOR_FLD_NAME_SLICE: &[u8] = b"major";
+static MAJOR_FLD_NAME: StaticName = StaticName {
+    ptr: MAJOR_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 5,
+};
+
+static MINOR_FLD_NAME_SLICE: &[u8] = b"minor";
+static MINOR_FLD_NAME: StaticName = StaticName {
+    ptr: MINOR_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 5,
+};
+
+/// A plain data struct, ordered by `major` first.
+#[repr(C)]
+struct Version {
+    major: u64,
+    minor: u64,
+}
+
+static VERSION_FIELD_INFO: [StructFieldInfo; 2] = [
+    StructFieldInfo {
+        type_: U64_TYPE,
+        offset: 0,
+        name: MAJOR_FLD_NAME,
+    },
+    StructFieldInfo {
+        type_: U64_TYPE,
+        offset: mem::size_of::<u64>() as u64,
+        name: MINOR_FLD_NAME,
+    },
+];
+
+static VERSION_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::Struct,
+    type_info: &TypeInfo {
+        struct_: StructTypeInfo {
+            field_array_ptr: &VERSION_FIELD_INFO[0],
+            field_array_len: 2,
+            size: mem::size_of::<Version>() as u64,
+            alignment: mem::align_of::<Version>() as u64,
+        },
+    },
+};
+
+static BALANCE_FLD_NAME_SLICE: &[u8] = b"balance";
+static BALANCE_FLD_NAME: StaticName = StaticName {
+    ptr: BALANCE_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 7,
+};
+
+static FROZEN_FLD_NAME_SLICE: &[u8] = b"frozen";
+static FROZEN_FLD_NAME: StaticName = StaticName {
+    ptr: FROZEN_FLD_NAME_SLICE as *const [u8] as *const u8,
+    len: 6,
+};
+
+/// A plain data struct with fields of different types.
+#[repr(C)]
+struct Account {
+    balance: u64,
+    frozen: bool,
+}
+
+static ACCOUNT_FIELD_INFO: [StructFieldInfo; 2] = [
+    StructFieldInfo {
+        type_: U64_TYPE,
+        offset: 0,
+        name: BALANCE_FLD_NAME,
+    },
+    StructFieldInfo {
+        type_: BOOL_TYPE,
+        offset: mem::size_of::<u64>() as u64,
+        name: FROZEN_FLD_NAME,
+    },
+];
+
+static ACCOUNT_TYPE: MoveType = MoveType {
+    name: DUMMY_TYPE_NAME,
+    type_desc: TypeDesc::Struct,
+    type_info: &TypeInfo {
+        struct_: StructTypeInfo {
+            field_array_ptr: &ACCOUNT_FIELD_INFO[0],
+            field_array_len: 2,
+            size: mem::size_of::<Account>() as u64,
+            alignment: mem::align_of::<Account>() as u64,
+        },
+    },
+};
+
+/// Builds a vector of `type_ve` structs from their Rust representations.
+unsafe fn struct_vec<T>(
+    type_ve: &MoveType,
+    elements: impl IntoIterator<Item = T>,
+) -> MoveUntypedVector {
+    let mut move_vec = MoveUntypedVector::empty(type_ve);
+    for mut element in elements {
+        let element_ptr = &mut element as *mut T as *mut AnyValue;
+        TypedMoveBorrowedRustVecMut::new(type_ve, &mut move_vec).push_back(element_ptr);
+        // The vector owns the element now.
+        mem::forget(element);
+    }
+    move_vec
+}
+
 #[test]
 fn test_string_check_utf8() {
     unsafe {
@@ -323,3 +528,2500 @@ fn test_vec_with_struct() {
         move_vec.destroy_empty(&ELEMENT_TYPE);
     }
 }
+
+#[test]
+fn test_vec_split_off_with_struct() {
+    unsafe {
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            (0..4u64).map(|id| StructWithVector::new(id, vec![id; 3])),
+        );
+        assert_eq!(move_vec.length, 4);
+
+        let tail =
+            TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec).split_off(1);
+        assert_eq!(move_vec.length, 1);
+        assert_eq!(tail.length, 3);
+
+        {
+            let head_rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            let head_elt = &*(head_rv.borrow(0) as *const _ as *const StructWithVector);
+            assert_eq!(head_elt.id, 0);
+            assert_eq!(head_elt.payload.length, 3);
+
+            let tail_rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &tail);
+            for i in 0..3u64 {
+                let tail_elt = &*(tail_rv.borrow(i) as *const _ as *const StructWithVector);
+                assert_eq!(tail_elt.id, i + 1);
+                assert_eq!(tail_elt.payload.length, 3);
+            }
+        }
+
+        // Splitting at the end leaves an empty, unallocated tail.
+        let empty_tail =
+            TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec).split_off(1);
+        assert_eq!(move_vec.length, 1);
+        assert_eq!(empty_tail.length, 0);
+        assert_eq!(empty_tail.capacity, 0);
+        empty_tail.destroy_empty(&STRUCT_WITH_VECTOR_TYPE);
+
+        // Each half frees only the payloads it owns.
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+        tail.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_set_eq() {
+    unsafe {
+        let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 2, 3]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![2, 3, 1, 2]);
+        let move_vec3 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3, 3]);
+
+        {
+            let v1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+            let v2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+            let v3 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec3);
+            assert!(v1.set_eq(&v2));
+            assert!(v2.set_eq(&v1));
+            // Same distinct elements, but a different duplicate.
+            assert!(!v1.set_eq(&v3));
+        }
+
+        move_vec1.destroy(&U64_TYPE);
+        move_vec2.destroy(&U64_TYPE);
+        move_vec3.destroy(&U64_TYPE);
+
+        // References are compared by their referents, not their addresses.
+        static REF_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Reference,
+            type_info: &TypeInfo {
+                reference: ReferenceTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let referents1 = [1u64, 2];
+        let referents2 = [2u64, 1];
+        let to_refs = |referents: &[u64]| {
+            let refs = referents
+                .iter()
+                .map(|r| MoveUntypedReference(r as *const u64 as *const AnyValue))
+                .collect::<Vec<_>>();
+            MoveUntypedVector::from_rust_vec(refs)
+        };
+        let ref_vec1 = to_refs(&referents1);
+        let ref_vec2 = to_refs(&referents2);
+        assert!(TypedMoveBorrowedRustVec::new(&REF_TYPE, &ref_vec1)
+            .set_eq(&TypedMoveBorrowedRustVec::new(&REF_TYPE, &ref_vec2)));
+        ref_vec1.destroy(&REF_TYPE);
+        ref_vec2.destroy(&REF_TYPE);
+
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"is_black";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 8,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"is_white";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 8,
+        };
+
+        static STRUCT_FIELD_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Bool,
+            type_info: &TypeInfo { nothing: 0 },
+        };
+
+        static STRUCT_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: STRUCT_FIELD_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: STRUCT_FIELD_TYPE,
+                offset: 1,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static ELEMENT_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &STRUCT_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<SimpleStruct>() as u64,
+                    alignment: mem::align_of::<SimpleStruct>() as u64,
+                },
+            },
+        };
+
+        #[repr(C)]
+        struct SimpleStruct {
+            is_black: bool,
+            is_white: bool,
+        }
+
+        let elements = [
+            [(true, false), (false, true), (true, false)],
+            [(true, false), (true, false), (false, true)],
+            [(false, true), (true, false), (false, true)],
+        ];
+        let struct_vecs = elements.map(|elements| {
+            struct_vec(
+                &ELEMENT_TYPE,
+                elements.map(|(is_black, is_white)| SimpleStruct { is_black, is_white }),
+            )
+        });
+
+        {
+            let v1 = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &struct_vecs[0]);
+            let v2 = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &struct_vecs[1]);
+            let v3 = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &struct_vecs[2]);
+            assert!(v1.set_eq(&v2));
+            assert!(!v1.set_eq(&v3));
+        }
+
+        for move_vec in struct_vecs {
+            move_vec.destroy(&ELEMENT_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_remove() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 11, 12, 13]);
+        let removed = TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).remove(1);
+        assert!(matches!(removed, OwnedMoveValue::U64(11)));
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![10, 12, 13]);
+
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            (0..3u64).map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        let removed =
+            TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec).remove(0);
+        assert_eq!(move_vec.length, 2);
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            for i in 0..2u64 {
+                let elt = &*(rv.borrow(i) as *const _ as *const StructWithVector);
+                assert_eq!(elt.id, i + 1);
+            }
+        }
+
+        let mut removed_element = mem::MaybeUninit::<StructWithVector>::uninit();
+        removed.write_to(removed_element.as_mut_ptr() as *mut AnyValue);
+        let removed_element = removed_element.assume_init();
+        assert_eq!(removed_element.id, 0);
+        assert_eq!(removed_element.payload.into_rust_vec::<u64>(), vec![0, 0]);
+
+        // Removing the last element needs no shifting; the removed payload
+        // is freed through the owned value.
+        let removed =
+            TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec).remove(1);
+        assert_eq!(move_vec.length, 1);
+        removed.destroy();
+
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_slice() {
+    unsafe {
+        let move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 11, 12, 13, 14]);
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec);
+
+            let window = rv.slice(1..4);
+            assert_eq!(window.len(), 3);
+            for i in 0..3u64 {
+                let elt = *(window.borrow(i) as *const _ as *const u64);
+                assert_eq!(elt, 11 + i);
+            }
+
+            let empty_window = rv.slice(5..5);
+            assert_eq!(empty_window.len(), 0);
+
+            let copied = window.copy_elements();
+            assert_eq!(copied.into_rust_vec::<u64>(), vec![11, 12, 13]);
+        }
+
+        // The views did not take ownership of the buffer.
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![10, 11, 12, 13, 14]);
+    }
+}
+
+#[test]
+fn test_vec_dedup() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 1, 2, 3, 3, 3, 4, 5, 5]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).dedup();
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![1, 2, 3, 4, 5]);
+
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            [7u64, 7, 8, 9, 9, 9].map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec).dedup();
+        assert_eq!(move_vec.length, 3);
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            for i in 0..3u64 {
+                let elt = &*(rv.borrow(i) as *const _ as *const StructWithVector);
+                assert_eq!(elt.id, i + 7);
+                assert_eq!(elt.payload.length, 2);
+            }
+        }
+
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+
+        // References are compared by their referents, not their addresses.
+        static REF_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Reference,
+            type_info: &TypeInfo {
+                reference: ReferenceTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let referents = [1u64, 1, 2];
+        let refs = referents
+            .iter()
+            .map(|r| MoveUntypedReference(r as *const u64 as *const AnyValue))
+            .collect::<Vec<_>>();
+        let mut move_vec = MoveUntypedVector::from_rust_vec(refs);
+        TypedMoveBorrowedRustVecMut::new(&REF_TYPE, &mut move_vec).dedup();
+        let refs = move_vec.into_rust_vec::<MoveUntypedReference>();
+        assert_eq!(refs.len(), 2);
+        assert!(core::ptr::eq(
+            refs[0].0,
+            &referents[0] as *const u64 as *const AnyValue
+        ));
+        assert!(core::ptr::eq(
+            refs[1].0,
+            &referents[2] as *const u64 as *const AnyValue
+        ));
+    }
+}
+
+#[test]
+fn test_vec_fill() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3]);
+        let value = 9u8;
+        TypedMoveBorrowedRustVecMut::new(&U8_TYPE, &mut move_vec)
+            .fill(&*(&value as *const u8 as *const AnyValue));
+        assert_eq!(move_vec.into_rust_vec::<u8>(), vec![9, 9, 9]);
+
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            (0..3u64).map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        let value = StructWithVector::new(42, vec![4u64, 2]);
+        TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec)
+            .fill(&*(&value as *const StructWithVector as *const AnyValue));
+        assert_eq!(move_vec.length, 3);
+
+        {
+            // Every element owns a separate copy of the payload.
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            for i in 0..3u64 {
+                let elt = &*(rv.borrow(i) as *const _ as *const StructWithVector);
+                assert_eq!(elt.id, 42);
+                assert_ne!(elt.payload.ptr, value.payload.ptr);
+                let payload = TypedMoveBorrowedRustVec::new(&U64_TYPE, &elt.payload);
+                let expected = TypedMoveBorrowedRustVec::new(&U64_TYPE, &value.payload);
+                assert!(payload.cmp_eq(&expected));
+            }
+        }
+
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+        value.payload.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_rotate() {
+    unsafe {
+        let rotated_left = |n: usize| {
+            let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![0, 1, 2, 3, 4]);
+            TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).rotate_left(n);
+            move_vec.into_rust_vec::<u64>()
+        };
+        let rotated_right = |n: usize| {
+            let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![0, 1, 2, 3, 4]);
+            TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).rotate_right(n);
+            move_vec.into_rust_vec::<u64>()
+        };
+
+        assert_eq!(rotated_left(0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(rotated_left(5), vec![0, 1, 2, 3, 4]);
+        assert_eq!(rotated_left(7), vec![2, 3, 4, 0, 1]);
+        assert_eq!(rotated_right(0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(rotated_right(5), vec![0, 1, 2, 3, 4]);
+        assert_eq!(rotated_right(7), vec![3, 4, 0, 1, 2]);
+
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).rotate_left(3);
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![]);
+    }
+}
+
+#[test]
+fn test_vec_is_sorted() {
+    unsafe {
+        let is_sorted = |elements: Vec<u64>| {
+            let move_vec = MoveUntypedVector::from_rust_vec::<u64>(elements);
+            let sorted = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).is_sorted();
+            move_vec.destroy(&U64_TYPE);
+            sorted
+        };
+
+        assert!(is_sorted(vec![1, 2, 2, 5, 9]));
+        assert!(!is_sorted(vec![9, 5, 2, 2, 1]));
+        assert!(is_sorted(vec![7]));
+        assert!(is_sorted(vec![]));
+
+        let elements = [[(1, 9), (2, 0), (2, 3)], [(1, 9), (2, 3), (2, 0)]];
+        let struct_vecs = elements.map(|elements| {
+            struct_vec(
+                &VERSION_TYPE,
+                elements.map(|(major, minor)| Version { major, minor }),
+            )
+        });
+
+        // Fields compare in declaration order.
+        assert!(TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[0]).is_sorted());
+        assert!(!TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[1]).is_sorted());
+
+        for move_vec in struct_vecs {
+            move_vec.destroy(&VERSION_TYPE);
+        }
+
+        // References are ordered by their referents, not their addresses.
+        static REF_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Reference,
+            type_info: &TypeInfo {
+                reference: ReferenceTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let referents = [3u64, 1];
+        let refs = referents
+            .iter()
+            .map(|r| MoveUntypedReference(r as *const u64 as *const AnyValue))
+            .collect::<Vec<_>>();
+        let move_vec = MoveUntypedVector::from_rust_vec(refs);
+        assert!(!TypedMoveBorrowedRustVec::new(&REF_TYPE, &move_vec).is_sorted());
+        move_vec.destroy(&REF_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_binary_search() {
+    unsafe {
+        let search = |elements: Vec<u64>, needle: u64| {
+            let move_vec = MoveUntypedVector::from_rust_vec::<u64>(elements);
+            let needle_ptr = &needle as *const u64 as *const AnyValue;
+            let result =
+                TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).binary_search(&*needle_ptr);
+            move_vec.destroy(&U64_TYPE);
+            result
+        };
+
+        // Hits.
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 1), Ok(0));
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 7), Ok(3));
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 9), Ok(4));
+
+        // Misses give the insertion point.
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 0), Err(0));
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 4), Err(2));
+        assert_eq!(search(vec![1, 3, 5, 7, 9], 10), Err(5));
+
+        assert_eq!(search(vec![], 4), Err(0));
+
+        // References are ordered by their referents.
+        static REF_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Reference,
+            type_info: &TypeInfo {
+                reference: ReferenceTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let referents = [1u64, 3, 5];
+        let refs = referents
+            .iter()
+            .map(|r| MoveUntypedReference(r as *const u64 as *const AnyValue))
+            .collect::<Vec<_>>();
+        let move_vec = MoveUntypedVector::from_rust_vec(refs);
+        let needle_referent = 4u64;
+        let needle = MoveUntypedReference(&needle_referent as *const u64 as *const AnyValue);
+        let result = TypedMoveBorrowedRustVec::new(&REF_TYPE, &move_vec)
+            .binary_search(&*(&needle as *const _ as *const AnyValue));
+        assert_eq!(result, Err(2));
+        move_vec.destroy(&REF_TYPE);
+    }
+}
+
+#[test]
+fn test_struct_to_pairs() {
+    unsafe {
+        let account = Account {
+            balance: 100,
+            frozen: true,
+        };
+        let account_ref = &*(&account as *const Account as *const AnyValue);
+        let pairs = struct_to_pairs(&ACCOUNT_TYPE, account_ref);
+
+        let names: Vec<&str> = pairs.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["balance", "frozen"]);
+        assert!(matches!(pairs[0].1, BorrowedTypedMoveValue::U64(100)));
+        assert!(matches!(pairs[1].1, BorrowedTypedMoveValue::Bool(true)));
+    }
+}
+
+#[test]
+fn test_vec_add_assign() {
+    unsafe {
+        let mut move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 20, u64::MAX - 3]);
+        {
+            let other = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+            TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec1).add_assign(&other);
+        }
+        assert_eq!(move_vec1.into_rust_vec::<u64>(), vec![11, 22, u64::MAX]);
+        move_vec2.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "aborted with code 4017")]
+fn test_vec_add_assign_overflow() {
+    unsafe {
+        let mut move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, u64::MAX]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 1]);
+        let other = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec1).add_assign(&other);
+    }
+}
+
+#[test]
+fn test_vec_find_by_field() {
+    unsafe {
+        let move_vec = struct_vec(
+            &ACCOUNT_TYPE,
+            [(100, false), (0, true), (250, true)]
+                .map(|(balance, frozen)| Account { balance, frozen }),
+        );
+
+        let rust_vec = TypedMoveBorrowedRustVec::new(&ACCOUNT_TYPE, &move_vec);
+        let frozen = true;
+        let frozen_ref = &*(&frozen as *const bool as *const AnyValue);
+        assert_eq!(rust_vec.find_by_field("frozen", frozen_ref), Some(1));
+
+        let balance = 250u64;
+        let balance_ref = &*(&balance as *const u64 as *const AnyValue);
+        assert_eq!(rust_vec.find_by_field("balance", balance_ref), Some(2));
+
+        let balance = 7u64;
+        let balance_ref = &*(&balance as *const u64 as *const AnyValue);
+        assert_eq!(rust_vec.find_by_field("balance", balance_ref), None);
+
+        move_vec.destroy(&ACCOUNT_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_partition_by_field() {
+    unsafe {
+        let ids = [5u64, 1, 8, 3, 9, 2];
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            ids.into_iter()
+                .map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        let pivot = 5u64;
+        let pivot_ref = &*(&pivot as *const u64 as *const AnyValue);
+        let split = TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec)
+            .partition_by_field("id", pivot_ref);
+        assert_eq!(split, 3);
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            let mut seen = Vec::new();
+            for i in 0..ids.len() {
+                let elt = &*(rv.borrow(i as u64) as *const _ as *const StructWithVector);
+                if i < split {
+                    assert!(elt.id < pivot);
+                } else {
+                    assert!(elt.id >= pivot);
+                }
+                // Each payload moved along with its element.
+                let payload = TypedMoveBorrowedRustVec::new(&U64_TYPE, &elt.payload);
+                assert_eq!(payload.len(), 2);
+                assert_eq!(*(payload.borrow(0) as *const _ as *const u64), elt.id);
+                seen.push(elt.id);
+            }
+            seen.sort();
+            assert_eq!(seen, vec![1, 2, 3, 5, 8, 9]);
+        }
+
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_serialize() {
+    unsafe {
+        static BYTES_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &U8_TYPE,
+                },
+            },
+        };
+
+        static BYTES_VEC_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &BYTES_TYPE,
+                },
+            },
+        };
+
+        let inner = vec![
+            MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3]),
+            MoveUntypedVector::from_rust_vec::<u8>(vec![]),
+        ];
+        let move_vec = MoveUntypedVector::from_rust_vec::<MoveUntypedVector>(inner);
+
+        let mut out = Vec::new();
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&BYTES_TYPE, &move_vec);
+            rv.serialize(&mut out);
+            assert_eq!(out.len(), rv.serialized_size());
+        }
+        assert_eq!(out, vec![2, 3, 1, 2, 3, 0]);
+
+        // Round trip through the deserializer.
+        let bytes = MoveByteVector::from_rust_vec(out);
+        let mut copy = mem::MaybeUninit::<MoveUntypedVector>::uninit();
+        crate::serialization::deserialize(
+            &BYTES_VEC_TYPE,
+            &bytes,
+            copy.as_mut_ptr() as *mut AnyValue,
+        );
+        let copy = copy.assume_init();
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&BYTES_TYPE, &move_vec);
+            let copy_rv = TypedMoveBorrowedRustVec::new(&BYTES_TYPE, &copy);
+            assert!(rv.cmp_eq(&copy_rv));
+        }
+        bytes.into_rust_vec();
+        copy.destroy(&BYTES_TYPE);
+        move_vec.destroy(&BYTES_TYPE);
+
+        // The size estimate matches the output for every length.
+        for len in 0..16u64 {
+            let move_vec = MoveUntypedVector::from_rust_vec::<u64>((0..len).collect());
+            let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec);
+            let mut out = Vec::new();
+            rv.serialize(&mut out);
+            assert_eq!(out.len(), rv.serialized_size());
+            move_vec.destroy(&U64_TYPE);
+        }
+
+        // Lengths from 128 on take more than one prefix byte.
+        let move_vec = MoveUntypedVector::from_rust_vec::<u8>(vec![0; 300]);
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &move_vec);
+            let mut out = Vec::new();
+            rv.serialize(&mut out);
+            assert_eq!(out[..2], [0xac, 0x02]);
+            assert_eq!(out.len(), 2 + 300);
+            assert_eq!(out.len(), rv.serialized_size());
+        }
+        move_vec.destroy(&U8_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_serialize_struct() {
+    unsafe {
+        static VEC_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &STRUCT_WITH_VECTOR_TYPE,
+                },
+            },
+        };
+
+        // The size estimate matches the output, and the output round-trips,
+        // for every length, with payloads of varying length.
+        for len in 0..8u64 {
+            let move_vec = struct_vec(
+                &STRUCT_WITH_VECTOR_TYPE,
+                (0..len).map(|id| StructWithVector::new(id, (0..id).collect::<Vec<u64>>())),
+            );
+
+            let mut out = Vec::new();
+            {
+                let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+                rv.serialize(&mut out);
+                assert_eq!(out.len(), rv.serialized_size());
+            }
+
+            let bytes = MoveByteVector::from_rust_vec(out);
+            let mut copy = mem::MaybeUninit::<MoveUntypedVector>::uninit();
+            crate::serialization::deserialize(
+                &VEC_TYPE,
+                &bytes,
+                copy.as_mut_ptr() as *mut AnyValue,
+            );
+            let copy = copy.assume_init();
+            {
+                let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+                let copy_rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &copy);
+                assert!(rv.cmp_eq(&copy_rv));
+            }
+            bytes.into_rust_vec();
+            copy.destroy(&STRUCT_WITH_VECTOR_TYPE);
+            move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_deserialize() {
+    unsafe {
+        // vector<u64>, followed by a byte that is not part of it.
+        let move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, u64::MAX, 3]);
+        let mut bytes = Vec::new();
+        TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).serialize(&mut bytes);
+        bytes.push(0xff);
+        let (copy, consumed) = MoveUntypedVector::deserialize(&U64_TYPE, &bytes);
+        assert_eq!(consumed, bytes.len() - 1);
+        assert_eq!(copy.into_rust_vec::<u64>(), vec![1, u64::MAX, 3]);
+        move_vec.destroy(&U64_TYPE);
+
+        // vector<u8>
+        let (copy, consumed) = MoveUntypedVector::deserialize(&U8_TYPE, &[2, 7, 9]);
+        assert_eq!(consumed, 3);
+        assert_eq!(copy.into_rust_vec::<u8>(), vec![7, 9]);
+
+        // vector<Struct>
+        let move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            (0..3u64).map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        let mut bytes = Vec::new();
+        TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec).serialize(&mut bytes);
+        let (copy, consumed) = MoveUntypedVector::deserialize(&STRUCT_WITH_VECTOR_TYPE, &bytes);
+        assert_eq!(consumed, bytes.len());
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            let copy_rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &copy);
+            assert!(rv.cmp_eq(&copy_rv));
+        }
+        copy.destroy(&STRUCT_WITH_VECTOR_TYPE);
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "deserialization failure")]
+fn test_vec_deserialize_truncated() {
+    unsafe {
+        // One u64 element, missing its last byte.
+        MoveUntypedVector::deserialize(&U64_TYPE, &[1, 1, 0, 0, 0, 0, 0, 0]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "oversized vector length")]
+fn test_vec_deserialize_oversized() {
+    unsafe {
+        MoveUntypedVector::deserialize(&U8_TYPE, &[0xff, 0xff, 0xff, 0xff, 0x0f, 1]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "deserialization failure")]
+fn test_vec_deserialize_noncanonical_len() {
+    unsafe {
+        // Length 1, padded with a zero group.
+        MoveUntypedVector::deserialize(&U8_TYPE, &[0x81, 0x00, 7]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "deserialization failure")]
+fn test_vec_deserialize_truncated_len() {
+    unsafe {
+        // The prefix continues past the end of the input.
+        MoveUntypedVector::deserialize(&U8_TYPE, &[0x80]);
+    }
+}
+
+#[test]
+fn test_update_field_bcs() {
+    use crate::serialization::{update_field_bcs, DecodeError};
+
+    unsafe {
+        static ACCOUNT_FLD_NAME_SLICE: &[u8] = b"account";
+        pub static ACCOUNT_FLD_NAME: StaticName = StaticName {
+            ptr: ACCOUNT_FLD_NAME_SLICE as *const [u8] as *const u8,
+            len: 7,
+        };
+
+        #[repr(C)]
+        struct Wallet {
+            payload: MoveUntypedVector,
+            account: Account,
+        }
+
+        static WALLET_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: PAYLOAD_TYPE,
+                offset: 0,
+                name: PAYLOAD_FLD_NAME,
+            },
+            StructFieldInfo {
+                type_: ACCOUNT_TYPE,
+                offset: mem::size_of::<MoveUntypedVector>() as u64,
+                name: ACCOUNT_FLD_NAME,
+            },
+        ];
+
+        static WALLET_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &WALLET_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Wallet>() as u64,
+                    alignment: mem::align_of::<Wallet>() as u64,
+                },
+            },
+        };
+
+        let mut wallet = Wallet {
+            payload: MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3]),
+            account: Account {
+                balance: 100,
+                frozen: false,
+            },
+        };
+        let wallet_ptr = &mut wallet as *mut Wallet as *mut AnyValue;
+
+        // A vector field; the trailing byte is not part of it.
+        let mut bytes = vec![2];
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        bytes.extend_from_slice(&9u64.to_le_bytes());
+        bytes.push(0xff);
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "payload", &bytes);
+        assert_eq!(res, Ok(17));
+
+        // A nested struct field.
+        let mut bytes = 250u64.to_le_bytes().to_vec();
+        bytes.push(1);
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "account", &bytes);
+        assert_eq!(res, Ok(9));
+        assert_eq!(wallet.account.balance, 250);
+        assert!(wallet.account.frozen);
+
+        // Rejected input leaves the struct as it was.
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "account", &bytes[..8]);
+        assert_eq!(res, Err(DecodeError::Truncated));
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "owner", &bytes);
+        assert_eq!(res, Err(DecodeError::NoSuchField));
+        bytes[8] = 2;
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "account", &bytes);
+        assert_eq!(res, Err(DecodeError::Invalid));
+        assert_eq!(wallet.account.balance, 250);
+
+        // Bad vector length prefixes are rejected too.
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "payload", &[0x80]);
+        assert_eq!(res, Err(DecodeError::Truncated));
+        let res = update_field_bcs(&WALLET_TYPE, wallet_ptr, "payload", &[0x80, 0x00]);
+        assert_eq!(res, Err(DecodeError::Invalid));
+        let res = update_field_bcs(
+            &WALLET_TYPE,
+            wallet_ptr,
+            "payload",
+            &[0xff, 0xff, 0xff, 0xff, 0x1f],
+        );
+        assert_eq!(res, Err(DecodeError::Invalid));
+
+        assert_eq!(wallet.payload.into_rust_vec::<u64>(), vec![7, 9]);
+    }
+}
+
+#[test]
+fn test_vec_hash_into() {
+    use core::hash::Hasher;
+
+    /// Records what it is fed, to compare against the serialized bytes.
+    #[derive(Default)]
+    struct RecordingHasher(Vec<u8>);
+
+    impl Hasher for RecordingHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+
+        fn finish(&self) -> u64 {
+            0
+        }
+    }
+
+    unsafe {
+        let byte_vec = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3]);
+        let u64_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![7, u64::MAX]);
+        let move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            (0..3u64).map(|id| StructWithVector::new(id, vec![id; id as usize])),
+        );
+
+        for (elt_type, move_vec) in [
+            (&U8_TYPE, &byte_vec),
+            (&U64_TYPE, &u64_vec),
+            (&STRUCT_WITH_VECTOR_TYPE, &move_vec),
+        ] {
+            let rv = TypedMoveBorrowedRustVec::new(elt_type, move_vec);
+            let mut serialized = Vec::new();
+            rv.serialize(&mut serialized);
+            let mut hasher = RecordingHasher::default();
+            rv.hash_into(&mut hasher);
+            assert_eq!(hasher.0, serialized);
+        }
+
+        byte_vec.destroy(&U8_TYPE);
+        u64_vec.destroy(&U64_TYPE);
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+fn test_struct_eq_bytes() {
+    use crate::serialization::{serialize, struct_eq_bytes};
+
+    unsafe {
+        let s = StructWithVector::new(42, vec![1, u64::MAX]);
+        let s_ref = &*(&s as *const StructWithVector as *const AnyValue);
+        let serialized = serialize(&STRUCT_WITH_VECTOR_TYPE, s_ref).into_rust_vec();
+
+        assert!(struct_eq_bytes(
+            &STRUCT_WITH_VECTOR_TYPE,
+            s_ref,
+            &serialized
+        ));
+
+        // A difference in either field, or in the vector length.
+        for i in [0, 8, serialized.len() - 1] {
+            let mut mismatched = serialized.clone();
+            mismatched[i] ^= 1;
+            assert!(!struct_eq_bytes(
+                &STRUCT_WITH_VECTOR_TYPE,
+                s_ref,
+                &mismatched
+            ));
+        }
+
+        // Truncated and overlong byte strings.
+        let truncated = &serialized[..serialized.len() - 1];
+        assert!(!struct_eq_bytes(&STRUCT_WITH_VECTOR_TYPE, s_ref, truncated));
+        let mut overlong = serialized.clone();
+        overlong.push(0);
+        assert!(!struct_eq_bytes(&STRUCT_WITH_VECTOR_TYPE, s_ref, &overlong));
+        assert!(!struct_eq_bytes(&STRUCT_WITH_VECTOR_TYPE, s_ref, &[]));
+
+        s.payload.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_swap_struct() {
+    use crate::structs::swap_struct;
+
+    unsafe {
+        let mut bag1 = StructWithVector::new(1, vec![10, 20, 30]);
+        let mut bag2 = StructWithVector::new(2, vec![40]);
+        let bag1_ptr = &mut bag1 as *mut StructWithVector as *mut AnyValue;
+        let bag2_ptr = &mut bag2 as *mut StructWithVector as *mut AnyValue;
+
+        swap_struct(&STRUCT_WITH_VECTOR_TYPE, bag1_ptr, bag2_ptr);
+        assert_eq!(bag1.id, 2);
+        assert_eq!(bag2.id, 1);
+
+        // Swapping a struct with itself changes nothing.
+        swap_struct(&STRUCT_WITH_VECTOR_TYPE, bag1_ptr, bag1_ptr);
+        assert_eq!(bag1.id, 2);
+
+        // Each vector is owned exactly once, by the struct it moved to.
+        assert_eq!(bag1.payload.into_rust_vec::<u64>(), vec![40]);
+        assert_eq!(bag2.payload.into_rust_vec::<u64>(), vec![10, 20, 30]);
+    }
+}
+
+#[test]
+fn test_layout_fingerprint() {
+    use crate::structs::layout_fingerprint;
+
+    unsafe {
+        static OTHER_TYPE_NAME_SLICE: &[u8] = b"other";
+        static OTHER_TYPE_NAME: StaticTypeName = StaticTypeName {
+            ptr: OTHER_TYPE_NAME_SLICE as *const [u8] as *const u8,
+            len: 5,
+        };
+
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"a";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 1,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"inner";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 5,
+        };
+
+        static DUMMY_FLD_NAME3_SLICE: &[u8] = b"items";
+        pub static DUMMY_FLD_NAME3: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME3_SLICE as *const [u8] as *const u8,
+            len: 5,
+        };
+
+        // struct Inner { a: u64 }, and the same but for its name and for a u32 field.
+        static INNER_FIELD_INFO: [StructFieldInfo; 1] = [StructFieldInfo {
+            type_: U64_TYPE,
+            offset: 0,
+            name: DUMMY_FLD_NAME1,
+        }];
+
+        static NARROW_FIELD_INFO: [StructFieldInfo; 1] = [StructFieldInfo {
+            type_: U32_TYPE,
+            offset: 0,
+            name: DUMMY_FLD_NAME1,
+        }];
+
+        static INNER_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &INNER_FIELD_INFO[0],
+                    field_array_len: 1,
+                    size: 8,
+                    alignment: 8,
+                },
+            },
+        };
+
+        static RENAMED_TYPE: MoveType = MoveType {
+            name: OTHER_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &INNER_FIELD_INFO[0],
+                    field_array_len: 1,
+                    size: 8,
+                    alignment: 8,
+                },
+            },
+        };
+
+        static NARROW_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &NARROW_FIELD_INFO[0],
+                    field_array_len: 1,
+                    size: 8,
+                    alignment: 8,
+                },
+            },
+        };
+
+        static ITEMS_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &INNER_TYPE,
+                },
+            },
+        };
+
+        static NARROW_ITEMS_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &NARROW_TYPE,
+                },
+            },
+        };
+
+        // struct Outer { inner: Inner, items: vector<Inner> }, with variations of 'Inner'.
+        static OUTER_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: INNER_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME2,
+            },
+            StructFieldInfo {
+                type_: ITEMS_TYPE,
+                offset: 8,
+                name: DUMMY_FLD_NAME3,
+            },
+        ];
+
+        static RENAMED_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: RENAMED_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME2,
+            },
+            StructFieldInfo {
+                type_: ITEMS_TYPE,
+                offset: 8,
+                name: DUMMY_FLD_NAME3,
+            },
+        ];
+
+        static NARROW_INNER_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: NARROW_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME2,
+            },
+            StructFieldInfo {
+                type_: ITEMS_TYPE,
+                offset: 8,
+                name: DUMMY_FLD_NAME3,
+            },
+        ];
+
+        static NARROW_ITEMS_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: INNER_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME2,
+            },
+            StructFieldInfo {
+                type_: NARROW_ITEMS_TYPE,
+                offset: 8,
+                name: DUMMY_FLD_NAME3,
+            },
+        ];
+
+        let outer = |fields: &'static [StructFieldInfo; 2]| StructTypeInfo {
+            field_array_ptr: &fields[0],
+            field_array_len: 2,
+            size: 8 + mem::size_of::<MoveUntypedVector>() as u64,
+            alignment: 8,
+        };
+
+        let fingerprint = layout_fingerprint(&outer(&OUTER_FIELD_INFO));
+        assert_eq!(fingerprint, layout_fingerprint(&outer(&OUTER_FIELD_INFO)));
+        // Type names do not matter, field types do, in nested structs and vector elements alike.
+        assert_eq!(fingerprint, layout_fingerprint(&outer(&RENAMED_FIELD_INFO)));
+        assert_ne!(
+            fingerprint,
+            layout_fingerprint(&outer(&NARROW_INNER_FIELD_INFO))
+        );
+        assert_ne!(
+            fingerprint,
+            layout_fingerprint(&outer(&NARROW_ITEMS_FIELD_INFO))
+        );
+        assert_ne!(
+            layout_fingerprint(&(*INNER_TYPE.type_info).struct_),
+            layout_fingerprint(&(*NARROW_TYPE.type_info).struct_)
+        );
+    }
+}
+
+#[test]
+fn test_vec_clone_widen() {
+    unsafe {
+        let narrow = MoveUntypedVector::from_rust_vec::<u32>(vec![0, 1, u32::MAX]);
+        let rv = TypedMoveBorrowedRustVec::new(&U32_TYPE, &narrow);
+
+        let widened = rv.clone_widen_u32_to_u64();
+        assert_eq!(widened.length, 3);
+        assert_eq!(
+            widened.into_rust_vec::<u64>(),
+            vec![0, 1, u64::from(u32::MAX)]
+        );
+
+        let widened = rv.clone_widen(&U64_TYPE);
+        assert_eq!(widened.length, 3);
+        assert_eq!(
+            widened.into_rust_vec::<u64>(),
+            vec![0, 1, u64::from(u32::MAX)]
+        );
+
+        let widened = rv.clone_widen(&U256_TYPE);
+        assert_eq!(widened.length, 3);
+        let widened = widened.into_rust_vec::<U256>();
+        assert!(widened.windows(2).all(|w| w[0] < w[1]));
+
+        // The source is left as it was.
+        assert_eq!(narrow.into_rust_vec::<u32>(), vec![0, 1, u32::MAX]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "widening to a narrower integer")]
+fn test_vec_clone_widen_narrower() {
+    unsafe {
+        let narrow = MoveUntypedVector::from_rust_vec::<u32>(vec![1]);
+        let rv = TypedMoveBorrowedRustVec::new(&U32_TYPE, &narrow);
+        rv.clone_widen(&U32_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_subbytes() {
+    unsafe {
+        let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3, 4, 5]);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes);
+
+        let window = rv.subbytes(1, 3);
+        assert_eq!(window.into_rust_vec::<u8>(), vec![2, 3, 4]);
+
+        let full = rv.subbytes(0, 5);
+        assert_eq!(full.into_rust_vec::<u8>(), vec![1, 2, 3, 4, 5]);
+
+        let empty = rv.subbytes(5, 0);
+        assert_eq!(empty.length, 0);
+        empty.destroy(&U8_TYPE);
+
+        // The source is left as it was.
+        assert_eq!(bytes.into_rust_vec::<u8>(), vec![1, 2, 3, 4, 5]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_vec_subbytes_out_of_bounds() {
+    unsafe {
+        let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3]);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes);
+        rv.subbytes(2, 2);
+    }
+}
+
+#[test]
+fn test_vec_bits() {
+    unsafe {
+        let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![0b0000_0101, 0xff, 0, 0b1000_0000]);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes);
+
+        assert_eq!(rv.count_ones(), 2 + 8 + 1);
+
+        let bits: Vec<usize> = (0..32).filter(|&i| rv.get_bit(i)).collect();
+        assert_eq!(bits, vec![0, 2, 8, 9, 10, 11, 12, 13, 14, 15, 31]);
+
+        bytes.destroy(&U8_TYPE);
+
+        let empty = MoveUntypedVector::empty(&U8_TYPE);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &empty);
+        assert_eq!(rv.count_ones(), 0);
+        empty.destroy(&U8_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_vec_get_bit_out_of_bounds() {
+    unsafe {
+        let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![0xff, 0xff]);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes);
+        rv.get_bit(16);
+    }
+}
+
+#[test]
+fn test_vec_mask_with() {
+    unsafe {
+        let mut bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![0x00, 0xff, 0x0f, 0xf0]);
+        let mut rv = TypedMoveBorrowedRustVecMut::new(&U8_TYPE, &mut bytes);
+        rv.mask_with(&[0xaa, 0x55, 0xff, 0x0f]);
+        drop(rv);
+        assert_eq!(bytes.into_rust_vec::<u8>(), vec![0xaa, 0xaa, 0xf0, 0xff]);
+
+        // A shorter mask repeats over the data.
+        let mut bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3, 4, 5]);
+        let mut rv = TypedMoveBorrowedRustVecMut::new(&U8_TYPE, &mut bytes);
+        rv.mask_with(&[0xff, 0]);
+        drop(rv);
+        assert_eq!(bytes.into_rust_vec::<u8>(), vec![0xfe, 2, 0xfc, 4, 0xfa]);
+
+        let mut bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![0xff, 0x3c, 0xff]);
+        let mut rv = TypedMoveBorrowedRustVecMut::new(&U8_TYPE, &mut bytes);
+        rv.and_mask_with(&[0x0f]);
+        rv.mask_with(&[]);
+        drop(rv);
+        assert_eq!(bytes.into_rust_vec::<u8>(), vec![0x0f, 0x0c, 0x0f]);
+    }
+}
+
+#[test]
+fn test_vec_rolling_hash() {
+    use crate::serialization::rolling_hash_step;
+
+    unsafe {
+        let empty = MoveUntypedVector::empty(&U64_TYPE);
+        let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &empty);
+        assert_eq!(rv.rolling_hash(), [0; 32]);
+        empty.destroy(&U64_TYPE);
+
+        // sha256(sha256([0; 32] || 1u64) || 2u64), little-endian elements.
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec);
+        let hash = rv.rolling_hash();
+        assert_eq!(
+            hash,
+            [
+                22, 99, 55, 210, 52, 161, 212, 130, 29, 200, 112, 0, 84, 73, 221, 153, 145, 177,
+                147, 148, 135, 141, 221, 74, 30, 99, 78, 133, 29, 104, 211, 99
+            ]
+        );
+
+        // The order of the elements matters.
+        let swapped = MoveUntypedVector::from_rust_vec::<u64>(vec![2, 1]);
+        let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &swapped);
+        assert_ne!(rv.rolling_hash(), hash);
+        swapped.destroy(&U64_TYPE);
+
+        // Appending takes the hash one step further.
+        let mut element = 3u64;
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec)
+            .push_back(&mut element as *mut u64 as *mut AnyValue);
+        let rv = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec);
+        assert_eq!(
+            rv.rolling_hash(),
+            rolling_hash_step(&hash, &3u64.to_le_bytes())
+        );
+        move_vec.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_zip() {
+    unsafe {
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"first";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 5,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"second";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 6,
+        };
+
+        #[repr(C)]
+        #[derive(Debug, PartialEq)]
+        struct Pair {
+            first: u64,
+            second: u64,
+        }
+
+        static STRUCT_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: mem::size_of::<u64>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static PAIR_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &STRUCT_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Pair>() as u64,
+                    alignment: mem::align_of::<Pair>() as u64,
+                },
+            },
+        };
+
+        let firsts = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3]);
+        let seconds = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 20, 30]);
+        let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &firsts);
+        let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &seconds);
+
+        let pairs = rv1.zip(&rv2, &PAIR_TYPE);
+        let pairs = pairs.into_rust_vec::<Pair>();
+        assert_eq!(
+            pairs,
+            vec![
+                Pair {
+                    first: 1,
+                    second: 10
+                },
+                Pair {
+                    first: 2,
+                    second: 20
+                },
+                Pair {
+                    first: 3,
+                    second: 30
+                },
+            ]
+        );
+
+        firsts.destroy(&U64_TYPE);
+        seconds.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_transpose_to_columns() {
+    unsafe {
+        static TAGS_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &U8_TYPE,
+                },
+            },
+        };
+
+        static IDS_COLUMN_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        static TAGS_COLUMN_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &TAGS_TYPE,
+                },
+            },
+        };
+
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"id";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 2,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"tags";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 4,
+        };
+
+        #[repr(C)]
+        struct Record {
+            id: u64,
+            tags: MoveUntypedVector,
+        }
+
+        #[repr(C)]
+        struct Columns {
+            ids: MoveUntypedVector,
+            tags: MoveUntypedVector,
+        }
+
+        static RECORD_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: TAGS_TYPE,
+                offset: mem::size_of::<u64>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static RECORD_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &RECORD_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Record>() as u64,
+                    alignment: mem::align_of::<Record>() as u64,
+                },
+            },
+        };
+
+        static COLUMNS_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: IDS_COLUMN_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: TAGS_COLUMN_TYPE,
+                offset: mem::size_of::<MoveUntypedVector>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static COLUMNS_INFO: StructTypeInfo = StructTypeInfo {
+            field_array_ptr: &COLUMNS_FIELD_INFO[0],
+            field_array_len: 2,
+            size: mem::size_of::<Columns>() as u64,
+            alignment: mem::align_of::<Columns>() as u64,
+        };
+
+        let records = MoveUntypedVector::from_rust_vec::<Record>(vec![
+            Record {
+                id: 1,
+                tags: MoveUntypedVector::from_rust_vec::<u8>(vec![7, 8]),
+            },
+            Record {
+                id: 2,
+                tags: MoveUntypedVector::from_rust_vec::<u8>(vec![9]),
+            },
+        ]);
+
+        let mut columns = mem::MaybeUninit::<Columns>::uninit();
+        records.transpose_to_columns(
+            &RECORD_TYPE,
+            &COLUMNS_INFO,
+            columns.as_mut_ptr() as *mut AnyValue,
+        );
+        let columns = columns.assume_init();
+
+        assert_eq!(columns.ids.into_rust_vec::<u64>(), vec![1, 2]);
+        let tags: Vec<Vec<u8>> = columns
+            .tags
+            .into_rust_vec::<MoveUntypedVector>()
+            .into_iter()
+            .map(|t| t.into_rust_vec::<u8>())
+            .collect();
+        assert_eq!(tags, vec![vec![7, 8], vec![9]]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "vector lengths differ")]
+fn test_vec_zip_lengths_differ() {
+    unsafe {
+        let firsts = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        let seconds = MoveUntypedVector::from_rust_vec::<u64>(vec![10]);
+        let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &firsts);
+        let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &seconds);
+        // The pair type is not looked at before the lengths are.
+        rv1.zip(&rv2, &U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_prefix_sum() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3, 4]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).prefix_sum();
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![1, 3, 6, 10]);
+
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).prefix_sum();
+        assert_eq!(move_vec.into_rust_vec::<u64>(), Vec::<u64>::new());
+    }
+}
+
+#[test]
+#[should_panic(expected = "aborted with code 4017")]
+fn test_vec_prefix_sum_overflow() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, u64::MAX]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).prefix_sum();
+    }
+}
+
+#[test]
+fn test_vec_find_duplicates() {
+    unsafe {
+        let duplicates = |elts: Vec<u64>| {
+            let move_vec = MoveUntypedVector::from_rust_vec::<u64>(elts);
+            let duplicates = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).find_duplicates();
+            move_vec.destroy(&U64_TYPE);
+            duplicates
+        };
+        assert_eq!(duplicates(vec![]), Vec::<usize>::new());
+        assert_eq!(duplicates(vec![1, 2, 3]), Vec::<usize>::new());
+        assert_eq!(duplicates(vec![4, 1, 4]), vec![2]);
+        assert_eq!(duplicates(vec![1, 1, 2, 3, 3, 3, 1]), vec![1, 4, 5, 6]);
+
+        // Equal ids with different payloads are not duplicates.
+        let move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            [(7u64, 1u64), (7, 2), (8, 1), (7, 1)]
+                .map(|(id, payload)| StructWithVector::new(id, vec![payload; 2])),
+        );
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            assert_eq!(rv.find_duplicates(), vec![3]);
+        }
+
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+    }
+}
+
+#[test]
+fn test_serialized_field_sizes() {
+    use crate::serialization::{serialize, serialized_field_sizes};
+
+    unsafe {
+        let s = StructWithVector::new(42, vec![1, 2, u64::MAX]);
+        let s_ref = &*(&s as *const StructWithVector as *const AnyValue);
+        let serialized = serialize(&STRUCT_WITH_VECTOR_TYPE, s_ref).into_rust_vec();
+        let sizes = serialized_field_sizes(&STRUCT_WITH_VECTOR_TYPE, s_ref);
+
+        // A u64, and a one-byte length followed by three u64s.
+        assert_eq!(
+            sizes,
+            vec![
+                (String::from("id"), 8),
+                (String::from("payload"), 1 + 3 * 8)
+            ]
+        );
+        assert_eq!(
+            sizes.iter().map(|(_, size)| size).sum::<usize>(),
+            serialized.len()
+        );
+
+        s.payload.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_merge_sorted() {
+    unsafe {
+        let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 4, 4, 9]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![2, 4, 10, 11]);
+        let merged = {
+            let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+            let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+            rv1.merge_sorted(&rv2)
+        };
+        assert_eq!(
+            merged.into_rust_vec::<u64>(),
+            vec![1, 2, 4, 4, 4, 9, 10, 11]
+        );
+        move_vec1.destroy(&U64_TYPE);
+        move_vec2.destroy(&U64_TYPE);
+
+        let elements: [&[(u64, u64)]; 2] = [&[(1, 9), (2, 3)], &[(1, 2), (2, 0), (3, 1)]];
+        let struct_vecs = elements.map(|elements| {
+            struct_vec(
+                &VERSION_TYPE,
+                elements
+                    .iter()
+                    .map(|&(major, minor)| Version { major, minor }),
+            )
+        });
+
+        let merged = {
+            let rv1 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[0]);
+            let rv2 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[1]);
+            rv1.merge_sorted(&rv2)
+        };
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &merged);
+            assert!(rv.is_sorted());
+            let versions: Vec<(u64, u64)> = (0..rv.len())
+                .map(|i| {
+                    let v = &*(rv.borrow(i) as *const _ as *const Version);
+                    (v.major, v.minor)
+                })
+                .collect();
+            assert_eq!(versions, vec![(1, 2), (1, 9), (2, 0), (2, 3), (3, 1)]);
+        }
+
+        merged.destroy(&VERSION_TYPE);
+        for move_vec in struct_vecs {
+            move_vec.destroy(&VERSION_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_intersect() {
+    unsafe {
+        let intersect = |elements1: Vec<u64>, elements2: Vec<u64>| {
+            let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(elements1);
+            let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(elements2);
+            let common = {
+                let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+                let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+                rv1.intersect(&rv2)
+            };
+            move_vec1.destroy(&U64_TYPE);
+            move_vec2.destroy(&U64_TYPE);
+            common.into_rust_vec::<u64>()
+        };
+        assert_eq!(intersect(vec![5, 1, 3, 1, 7], vec![1, 7, 8, 1]), vec![1, 7]);
+        assert_eq!(intersect(vec![1, 2], vec![3, 4]), Vec::<u64>::new());
+        assert_eq!(intersect(vec![], vec![3, 4]), Vec::<u64>::new());
+
+        let elements: [&[(u64, u64)]; 2] =
+            [&[(2, 3), (1, 9), (2, 3), (3, 0)], &[(3, 0), (2, 3), (1, 8)]];
+        let struct_vecs = elements.map(|elements| {
+            struct_vec(
+                &VERSION_TYPE,
+                elements
+                    .iter()
+                    .map(|&(major, minor)| Version { major, minor }),
+            )
+        });
+
+        let common = {
+            let rv1 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[0]);
+            let rv2 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[1]);
+            rv1.intersect(&rv2)
+        };
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &common);
+            let versions: Vec<(u64, u64)> = (0..rv.len())
+                .map(|i| {
+                    let v = &*(rv.borrow(i) as *const _ as *const Version);
+                    (v.major, v.minor)
+                })
+                .collect();
+            assert_eq!(versions, vec![(2, 3), (3, 0)]);
+        }
+
+        common.destroy(&VERSION_TYPE);
+        for move_vec in struct_vecs {
+            move_vec.destroy(&VERSION_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_difference() {
+    unsafe {
+        let difference = |elements1: Vec<u64>, elements2: Vec<u64>| {
+            let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(elements1);
+            let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(elements2);
+            let common = {
+                let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+                let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+                rv1.difference(&rv2)
+            };
+            move_vec1.destroy(&U64_TYPE);
+            move_vec2.destroy(&U64_TYPE);
+            common.into_rust_vec::<u64>()
+        };
+        assert_eq!(difference(vec![5, 1, 3], vec![2, 4]), vec![5, 1, 3]);
+        assert_eq!(difference(vec![1, 2, 1], vec![2, 1]), Vec::<u64>::new());
+        assert_eq!(difference(vec![5, 1, 3, 5, 7], vec![1, 7, 8]), vec![5, 3]);
+
+        let elements: [&[(u64, u64)]; 2] =
+            [&[(2, 3), (1, 9), (2, 3), (3, 0)], &[(3, 0), (2, 3), (1, 8)]];
+        let struct_vecs = elements.map(|elements| {
+            struct_vec(
+                &VERSION_TYPE,
+                elements
+                    .iter()
+                    .map(|&(major, minor)| Version { major, minor }),
+            )
+        });
+
+        let common = {
+            let rv1 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[0]);
+            let rv2 = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &struct_vecs[1]);
+            rv1.difference(&rv2)
+        };
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &common);
+            let versions: Vec<(u64, u64)> = (0..rv.len())
+                .map(|i| {
+                    let v = &*(rv.borrow(i) as *const _ as *const Version);
+                    (v.major, v.minor)
+                })
+                .collect();
+            assert_eq!(versions, vec![(1, 9)]);
+        }
+
+        common.destroy(&VERSION_TYPE);
+        for move_vec in struct_vecs {
+            move_vec.destroy(&VERSION_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_group_by_field() {
+    unsafe {
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"kind";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 4,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"amount";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 6,
+        };
+
+        static STRUCT_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: mem::size_of::<u64>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        #[repr(C)]
+        struct Record {
+            kind: u64,
+            amount: u64,
+        }
+
+        static ELEMENT_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &STRUCT_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Record>() as u64,
+                    alignment: mem::align_of::<Record>() as u64,
+                },
+            },
+        };
+
+        let move_vec = struct_vec(
+            &ELEMENT_TYPE,
+            [(2, 10), (1, 20), (2, 30), (3, 40), (1, 50)]
+                .map(|(kind, amount)| Record { kind, amount }),
+        );
+
+        let groups = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &move_vec).group_by_field("kind");
+        let expected: [(u64, &[u64]); 3] = [(2, &[10, 30]), (1, &[20, 50]), (3, &[40])];
+        assert_eq!(groups.len(), expected.len());
+        for ((key, group), (kind, amounts)) in groups.into_iter().zip(expected) {
+            assert!(matches!(key, OwnedMoveValue::U64(k) if k == kind));
+            {
+                let rv = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &group);
+                let group_amounts: Vec<u64> = (0..rv.len())
+                    .map(|i| {
+                        let record = &*(rv.borrow(i) as *const _ as *const Record);
+                        assert_eq!(record.kind, kind);
+                        record.amount
+                    })
+                    .collect();
+                assert_eq!(group_amounts, amounts);
+            }
+            key.destroy();
+            group.destroy(&ELEMENT_TYPE);
+        }
+
+        move_vec.destroy(&ELEMENT_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_flatten() {
+    unsafe {
+        static VEC_U64_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Vector,
+            type_info: &TypeInfo {
+                vector: VectorTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let inner = vec![
+            MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]),
+            MoveUntypedVector::from_rust_vec::<u64>(vec![]),
+            MoveUntypedVector::from_rust_vec::<u64>(vec![3]),
+            MoveUntypedVector::from_rust_vec::<u64>(vec![4, 5, 6]),
+        ];
+        let outer = MoveUntypedVector::from_rust_vec(inner);
+        let flat = TypedMoveBorrowedRustVec::new(&VEC_U64_TYPE, &outer).flatten();
+        assert_eq!(flat.length, 6);
+        assert_eq!(flat.into_rust_vec::<u64>(), vec![1, 2, 3, 4, 5, 6]);
+
+        // The inner vectors are left as they are.
+        assert_eq!(
+            TypedMoveBorrowedRustVec::new(&VEC_U64_TYPE, &outer).len(),
+            4
+        );
+        outer.destroy(&VEC_U64_TYPE);
+
+        let outer = MoveUntypedVector::from_rust_vec::<MoveUntypedVector>(vec![]);
+        let flat = TypedMoveBorrowedRustVec::new(&VEC_U64_TYPE, &outer).flatten();
+        assert_eq!(flat.into_rust_vec::<u64>(), Vec::<u64>::new());
+        outer.destroy(&VEC_U64_TYPE);
+    }
+}
+
+#[test]
+fn test_address_bytes_round_trip() {
+    unsafe {
+        let mut known = [0; ACCOUNT_ADDRESS_LENGTH];
+        known[ACCOUNT_ADDRESS_LENGTH - 1] = 1;
+        let addresses = [
+            MoveAddress([u8::MIN; ACCOUNT_ADDRESS_LENGTH]),
+            MoveAddress(known),
+            MoveAddress(core::array::from_fn(|i| i as u8)),
+            MoveAddress([u8::MAX; ACCOUNT_ADDRESS_LENGTH]),
+        ];
+        for addr in addresses {
+            let bytes = address_to_bytes(&addr);
+            let converted = bytes_to_address(&TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes));
+            assert_eq!(bytes.into_rust_vec::<u8>(), addr.0.to_vec());
+            assert_eq!(converted, Some(addr));
+        }
+
+        for len in [0, ACCOUNT_ADDRESS_LENGTH - 1, ACCOUNT_ADDRESS_LENGTH + 1] {
+            let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![7; len]);
+            let converted = bytes_to_address(&TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes));
+            assert_eq!(converted, None);
+            bytes.destroy(&U8_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_eq_mask() {
+    unsafe {
+        let v1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3, 4]);
+        let v2 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 0, 3, 5]);
+        let mask = TypedMoveBorrowedRustVec::new(&U64_TYPE, &v1)
+            .eq_mask(&TypedMoveBorrowedRustVec::new(&U64_TYPE, &v2));
+        assert_eq!(mask.into_rust_vec::<bool>(), vec![true, false, true, false]);
+
+        let empty = MoveUntypedVector::from_rust_vec::<u64>(vec![]);
+        let mask = TypedMoveBorrowedRustVec::new(&U64_TYPE, &empty)
+            .eq_mask(&TypedMoveBorrowedRustVec::new(&U64_TYPE, &empty));
+        assert_eq!(mask.into_rust_vec::<bool>(), Vec::<bool>::new());
+
+        v1.destroy(&U64_TYPE);
+        v2.destroy(&U64_TYPE);
+        empty.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "vector lengths differ")]
+fn test_vec_eq_mask_lengths_differ() {
+    unsafe {
+        let v1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        let v2 = MoveUntypedVector::from_rust_vec::<u64>(vec![1]);
+        TypedMoveBorrowedRustVec::new(&U64_TYPE, &v1)
+            .eq_mask(&TypedMoveBorrowedRustVec::new(&U64_TYPE, &v2));
+    }
+}
+
+#[test]
+fn test_vec_apply_permutation() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 20, 30, 40]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).apply_permutation(&[2, 0, 3, 1]);
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![30, 10, 40, 20]);
+
+        let mut move_vec = struct_vec(
+            &VERSION_TYPE,
+            [(1, 0), (2, 1), (3, 2)].map(|(major, minor)| Version { major, minor }),
+        );
+
+        TypedMoveBorrowedRustVecMut::new(&VERSION_TYPE, &mut move_vec)
+            .apply_permutation(&[1, 2, 0]);
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&VERSION_TYPE, &move_vec);
+            let versions: Vec<(u64, u64)> = (0..rv.len())
+                .map(|i| {
+                    let v = &*(rv.borrow(i) as *const _ as *const Version);
+                    (v.major, v.minor)
+                })
+                .collect();
+            assert_eq!(versions, vec![(2, 1), (3, 2), (1, 0)]);
+        }
+        move_vec.destroy(&VERSION_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid permutation")]
+fn test_vec_apply_permutation_invalid() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 20, 30]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).apply_permutation(&[0, 2, 2]);
+    }
+}
+
+#[test]
+fn test_vec_chunks() {
+    unsafe {
+        let chunks = |elements: Vec<u64>, chunk_size: usize| {
+            let move_vec = MoveUntypedVector::from_rust_vec::<u64>(elements);
+            let chunks = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).chunks(chunk_size);
+            move_vec.destroy(&U64_TYPE);
+            chunks
+                .into_rust_vec::<MoveUntypedVector>()
+                .into_iter()
+                .map(|chunk| chunk.into_rust_vec::<u64>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            chunks(vec![1, 2, 3, 4, 5, 6, 7], 3),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+        assert_eq!(chunks(vec![1, 2], 2), vec![vec![1, 2]]);
+        assert_eq!(chunks(vec![1, 2], 5), vec![vec![1, 2]]);
+        assert_eq!(chunks(vec![], 2), Vec::<Vec<u64>>::new());
+    }
+}
+
+#[test]
+#[should_panic(expected = "zero chunk size")]
+fn test_vec_chunks_zero_size() {
+    unsafe {
+        let move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).chunks(0);
+    }
+}
+
+#[test]
+fn test_struct_checksum() {
+    use crate::serialization::struct_checksum;
+
+    unsafe {
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"flag";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 4,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"amount";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 6,
+        };
+
+        // Padding follows `flag`.
+        #[repr(C)]
+        struct Entry {
+            flag: u8,
+            amount: u64,
+        }
+
+        static STRUCT_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: U8_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: mem::size_of::<u64>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static STRUCT_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &STRUCT_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Entry>() as u64,
+                    alignment: mem::align_of::<Entry>() as u64,
+                },
+            },
+        };
+
+        // Equal values whose padding bytes differ.
+        let checksum = |flag: u8, amount: u64, padding: u8| {
+            let mut buf = [u64::from_ne_bytes([padding; 8]); 2];
+            let entry = buf.as_mut_ptr() as *mut Entry;
+            (*entry).flag = flag;
+            (*entry).amount = amount;
+            struct_checksum(&STRUCT_TYPE, &*(entry as *const AnyValue))
+        };
+        assert_eq!(checksum(1, 2, 0x00), 0xb357_0790);
+        assert_eq!(checksum(1, 2, 0xff), 0xb357_0790);
+        assert_ne!(checksum(1, 3, 0x00), checksum(1, 2, 0x00));
+        assert_ne!(checksum(0, 2, 0x00), checksum(1, 2, 0x00));
+    }
+}
+
+#[test]
+fn test_vec_all_any_field_eq() {
+    unsafe {
+        let move_vec = struct_vec(
+            &ACCOUNT_TYPE,
+            [(100, true), (0, true), (250, true)]
+                .map(|(balance, frozen)| Account { balance, frozen }),
+        );
+
+        let rust_vec = TypedMoveBorrowedRustVec::new(&ACCOUNT_TYPE, &move_vec);
+        let frozen = true;
+        let frozen_ref = &*(&frozen as *const bool as *const AnyValue);
+        let not_frozen = false;
+        let not_frozen_ref = &*(&not_frozen as *const bool as *const AnyValue);
+        // All match.
+        assert!(rust_vec.all_field_eq("frozen", frozen_ref));
+        assert!(rust_vec.any_field_eq("frozen", frozen_ref));
+        // None match.
+        assert!(!rust_vec.all_field_eq("frozen", not_frozen_ref));
+        assert!(!rust_vec.any_field_eq("frozen", not_frozen_ref));
+        // Some match.
+        let balance = 250u64;
+        let balance_ref = &*(&balance as *const u64 as *const AnyValue);
+        assert!(!rust_vec.all_field_eq("balance", balance_ref));
+        assert!(rust_vec.any_field_eq("balance", balance_ref));
+
+        move_vec.destroy(&ACCOUNT_TYPE);
+
+        let move_vec = MoveUntypedVector::empty(&ACCOUNT_TYPE);
+        let rust_vec = TypedMoveBorrowedRustVec::new(&ACCOUNT_TYPE, &move_vec);
+        assert!(rust_vec.all_field_eq("frozen", frozen_ref));
+        assert!(!rust_vec.any_field_eq("frozen", frozen_ref));
+        move_vec.destroy(&ACCOUNT_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_binary_search_by_field() {
+    unsafe {
+        static DUMMY_FLD_NAME1_SLICE: &[u8] = b"value";
+        pub static DUMMY_FLD_NAME1: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME1_SLICE as *const [u8] as *const u8,
+            len: 5,
+        };
+
+        static DUMMY_FLD_NAME2_SLICE: &[u8] = b"id";
+        pub static DUMMY_FLD_NAME2: StaticName = StaticName {
+            ptr: DUMMY_FLD_NAME2_SLICE as *const [u8] as *const u8,
+            len: 2,
+        };
+
+        #[repr(C)]
+        struct Record {
+            value: u64,
+            id: u64,
+        }
+
+        static STRUCT_FIELD_INFO: [StructFieldInfo; 2] = [
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: 0,
+                name: DUMMY_FLD_NAME1,
+            },
+            StructFieldInfo {
+                type_: U64_TYPE,
+                offset: mem::size_of::<u64>() as u64,
+                name: DUMMY_FLD_NAME2,
+            },
+        ];
+
+        static ELEMENT_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Struct,
+            type_info: &TypeInfo {
+                struct_: StructTypeInfo {
+                    field_array_ptr: &STRUCT_FIELD_INFO[0],
+                    field_array_len: 2,
+                    size: mem::size_of::<Record>() as u64,
+                    alignment: mem::align_of::<Record>() as u64,
+                },
+            },
+        };
+
+        // Sorted by id, not by value.
+        let move_vec = struct_vec(
+            &ELEMENT_TYPE,
+            [(9, 2), (1, 5), (7, 8), (3, 13)].map(|(value, id)| Record { value, id }),
+        );
+
+        let rust_vec = TypedMoveBorrowedRustVec::new(&ELEMENT_TYPE, &move_vec);
+        let search = |id: u64| {
+            let id_ref = &*(&id as *const u64 as *const AnyValue);
+            rust_vec.binary_search_by_field("id", id_ref)
+        };
+        assert_eq!(search(2), Ok(0));
+        assert_eq!(search(8), Ok(2));
+        assert_eq!(search(13), Ok(3));
+        assert_eq!(search(0), Err(0));
+        assert_eq!(search(6), Err(2));
+        assert_eq!(search(20), Err(4));
+
+        move_vec.destroy(&ELEMENT_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_retain_ne() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![0, 1, 0, 2, 3, 0]);
+        let sentinel = 0u64;
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec)
+            .retain_ne(&*(&sentinel as *const u64 as *const AnyValue));
+        assert_eq!(move_vec.into_rust_vec::<u64>(), vec![1, 2, 3]);
+
+        let mut move_vec = struct_vec(
+            &STRUCT_WITH_VECTOR_TYPE,
+            [7u64, 8, 7, 9, 7].map(|id| StructWithVector::new(id, vec![id; 2])),
+        );
+
+        let sentinel = StructWithVector::new(7, vec![7u64; 2]);
+        TypedMoveBorrowedRustVecMut::new(&STRUCT_WITH_VECTOR_TYPE, &mut move_vec)
+            .retain_ne(&*(&sentinel as *const _ as *const AnyValue));
+        assert_eq!(move_vec.length, 2);
+
+        {
+            let rv = TypedMoveBorrowedRustVec::new(&STRUCT_WITH_VECTOR_TYPE, &move_vec);
+            for (i, id) in [8u64, 9].into_iter().enumerate() {
+                let elt = &*(rv.borrow(i as u64) as *const _ as *const StructWithVector);
+                assert_eq!(elt.id, id);
+                assert_eq!(elt.payload.length, 2);
+            }
+        }
+
+        sentinel.payload.destroy(&U64_TYPE);
+        move_vec.destroy(&STRUCT_WITH_VECTOR_TYPE);
+
+        // References are compared by their referents, not their addresses.
+        static REF_TYPE: MoveType = MoveType {
+            name: DUMMY_TYPE_NAME,
+            type_desc: TypeDesc::Reference,
+            type_info: &TypeInfo {
+                reference: ReferenceTypeInfo {
+                    element_type: &U64_TYPE,
+                },
+            },
+        };
+
+        let referents = [0u64, 1, 0];
+        let refs = referents
+            .iter()
+            .map(|r| MoveUntypedReference(r as *const u64 as *const AnyValue))
+            .collect::<Vec<_>>();
+        let mut move_vec = MoveUntypedVector::from_rust_vec(refs);
+        let sentinel_referent = 0u64;
+        let sentinel = MoveUntypedReference(&sentinel_referent as *const u64 as *const AnyValue);
+        TypedMoveBorrowedRustVecMut::new(&REF_TYPE, &mut move_vec)
+            .retain_ne(&*(&sentinel as *const _ as *const AnyValue));
+        let refs = move_vec.into_rust_vec::<MoveUntypedReference>();
+        assert_eq!(refs.len(), 1);
+        assert!(core::ptr::eq(
+            refs[0].0,
+            &referents[1] as *const u64 as *const AnyValue
+        ));
+    }
+}
+
+#[test]
+fn test_vec_min_max_with() {
+    unsafe {
+        let mut move_vec_min = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 20, 3, u64::MAX]);
+        let mut move_vec_max = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 20, 3, u64::MAX]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![10, 2, 3, 0]);
+        {
+            let other = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+            TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec_min).min_with(&other);
+            TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec_max).max_with(&other);
+        }
+        assert_eq!(move_vec_min.into_rust_vec::<u64>(), vec![1, 2, 3, 0]);
+        assert_eq!(
+            move_vec_max.into_rust_vec::<u64>(),
+            vec![10, 20, 3, u64::MAX]
+        );
+        move_vec2.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "vector lengths differ")]
+fn test_vec_min_with_length_mismatch() {
+    unsafe {
+        let mut move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![1]);
+        let other = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec1).min_with(&other);
+    }
+}
+
+#[test]
+fn test_vec_bytes_to_int_le() {
+    let read_u64 = |bytes: Vec<u8>| {
+        let move_vec = MoveUntypedVector::from_rust_vec(bytes);
+        let n = unsafe { TypedMoveBorrowedRustVec::new(&U8_TYPE, &move_vec) }.bytes_to_u64_le();
+        unsafe { move_vec.destroy(&U8_TYPE) };
+        n
+    };
+    assert_eq!(read_u64(vec![0; 8]), 0);
+    assert_eq!(read_u64(vec![1, 0, 0, 0, 0, 0, 0, 0]), 1);
+    assert_eq!(
+        read_u64(vec![8, 7, 6, 5, 4, 3, 2, 1]),
+        0x0102_0304_0506_0708
+    );
+    assert_eq!(read_u64(vec![0xff; 8]), u64::MAX);
+
+    let read_u128 = |bytes: Vec<u8>| {
+        let move_vec = MoveUntypedVector::from_rust_vec(bytes);
+        let n = unsafe { TypedMoveBorrowedRustVec::new(&U8_TYPE, &move_vec) }.bytes_to_u128_le();
+        unsafe { move_vec.destroy(&U8_TYPE) };
+        n
+    };
+    let mut bytes = vec![0; 16];
+    bytes[15] = 0x80;
+    assert_eq!(read_u128(bytes), 1 << 127);
+    assert_eq!(read_u128(vec![0xff; 16]), u128::MAX);
+
+    let read_u256 = |bytes: Vec<u8>| {
+        let move_vec = MoveUntypedVector::from_rust_vec(bytes);
+        let n = unsafe { TypedMoveBorrowedRustVec::new(&U8_TYPE, &move_vec) }.bytes_to_u256_le();
+        unsafe { move_vec.destroy(&U8_TYPE) };
+        n
+    };
+    let mut bytes = vec![0; 32];
+    bytes[0] = 2;
+    bytes[16] = 1;
+    assert_eq!(read_u256(bytes), U256([2, 1]));
+    assert_eq!(read_u256(vec![0xff; 32]), U256([u128::MAX, u128::MAX]));
+}
+
+#[test]
+#[should_panic(expected = "wrong byte length")]
+fn test_vec_bytes_to_u64_le_wrong_length() {
+    let move_vec = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2, 3]);
+    unsafe { TypedMoveBorrowedRustVec::new(&U8_TYPE, &move_vec) }.bytes_to_u64_le();
+}
+
+#[test]
+fn test_vec_byte_swap_elements() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<u32>(vec![0x0102_0304, 0xff, 0]);
+        TypedMoveBorrowedRustVecMut::new(&U32_TYPE, &mut move_vec).byte_swap_elements();
+        assert_eq!(
+            move_vec.into_rust_vec::<u32>(),
+            vec![0x0403_0201, 0xff00_0000, 0]
+        );
+
+        let mut move_vec =
+            MoveUntypedVector::from_rust_vec::<u64>(vec![0x0102_0304_0506_0708, u64::MAX]);
+        TypedMoveBorrowedRustVecMut::new(&U64_TYPE, &mut move_vec).byte_swap_elements();
+        assert_eq!(
+            move_vec.into_rust_vec::<u64>(),
+            vec![0x0807_0605_0403_0201, u64::MAX]
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "byte_swap_elements: element type is not an integer")]
+fn test_vec_byte_swap_elements_not_integer() {
+    unsafe {
+        let mut move_vec = MoveUntypedVector::from_rust_vec::<bool>(vec![true]);
+        TypedMoveBorrowedRustVecMut::new(&BOOL_TYPE, &mut move_vec).byte_swap_elements();
+    }
+}
+
+#[test]
+fn test_vec_byte_histogram() {
+    unsafe {
+        let bytes = MoveUntypedVector::from_rust_vec::<u8>(vec![0, 7, 7, 0xff, 7, 0, 0xff]);
+        let rv = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes);
+
+        let histogram = rv.byte_histogram();
+        assert_eq!(histogram[0], 2);
+        assert_eq!(histogram[7], 3);
+        assert_eq!(histogram[0xff], 2);
+        assert_eq!(histogram[1], 0);
+        assert_eq!(histogram.iter().sum::<u64>(), 7);
+
+        bytes.destroy(&U8_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "expected a vector<u8>")]
+fn test_vec_byte_histogram_not_bytes() {
+    unsafe {
+        let move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![1]);
+        TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec).byte_histogram();
+    }
+}
+
+#[test]
+fn test_vec_hamming_distance() {
+    unsafe {
+        let bytes1 = MoveUntypedVector::from_rust_vec::<u8>(vec![0b1010_1010, 0xff, 0]);
+        let bytes2 = MoveUntypedVector::from_rust_vec::<u8>(vec![0b1010_1011, 0x0f, 0x80]);
+        {
+            let rv1 = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes1);
+            let rv2 = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes2);
+            assert_eq!(rv1.hamming_distance(&rv1), 0);
+            assert_eq!(rv1.hamming_distance(&rv2), 1 + 4 + 1);
+            assert_eq!(rv2.hamming_distance(&rv1), 1 + 4 + 1);
+        }
+        bytes1.destroy(&U8_TYPE);
+        bytes2.destroy(&U8_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "vector lengths differ")]
+fn test_vec_hamming_distance_length_mismatch() {
+    unsafe {
+        let bytes1 = MoveUntypedVector::from_rust_vec::<u8>(vec![1, 2]);
+        let bytes2 = MoveUntypedVector::from_rust_vec::<u8>(vec![1]);
+        let rv1 = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes1);
+        let rv2 = TypedMoveBorrowedRustVec::new(&U8_TYPE, &bytes2);
+        rv1.hamming_distance(&rv2);
+    }
+}
+
+#[test]
+fn test_vec_starts_ends_with() {
+    unsafe {
+        let data = MoveUntypedVector::from_rust_vec::<u8>(b"\x89PNG\r\n".to_vec());
+        let magic = MoveUntypedVector::from_rust_vec::<u8>(b"\x89PNG".to_vec());
+        let other = MoveUntypedVector::from_rust_vec::<u8>(b"GIF8".to_vec());
+        let tail = MoveUntypedVector::from_rust_vec::<u8>(b"\r\n".to_vec());
+        let longer = MoveUntypedVector::from_rust_vec::<u8>(b"\x89PNG\r\n\x1a\n".to_vec());
+        let empty = MoveUntypedVector::empty(&U8_TYPE);
+        {
+            let rv = |v| TypedMoveBorrowedRustVec::new(&U8_TYPE, v);
+            let data_rv = rv(&data);
+            assert!(data_rv.starts_with(&rv(&magic)));
+            assert!(data_rv.starts_with(&rv(&data)));
+            assert!(!data_rv.starts_with(&rv(&other)));
+            assert!(!data_rv.starts_with(&rv(&longer)));
+            assert!(data_rv.starts_with(&rv(&empty)));
+
+            assert!(data_rv.ends_with(&rv(&tail)));
+            assert!(data_rv.ends_with(&rv(&data)));
+            assert!(!data_rv.ends_with(&rv(&magic)));
+            assert!(!data_rv.ends_with(&rv(&longer)));
+            assert!(data_rv.ends_with(&rv(&empty)));
+        }
+        for v in [data, magic, other, tail, longer, empty] {
+            v.destroy(&U8_TYPE);
+        }
+    }
+}
+
+#[test]
+fn test_vec_dot_product() {
+    unsafe {
+        let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2, 3]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![4, 5, u64::MAX]);
+        {
+            let rv1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+            let rv2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+            assert_eq!(rv1.dot_product(&rv1), 1 + 4 + 9);
+            // Products of u64 elements are widened, so they don't overflow.
+            assert_eq!(rv1.dot_product(&rv2), 4 + 10 + 3 * u128::from(u64::MAX));
+        }
+        move_vec1.destroy(&U64_TYPE);
+        move_vec2.destroy(&U64_TYPE);
+    }
+}
+
+#[test]
+fn test_vec_dot_product_u256_large_times_zero() {
+    unsafe {
+        // 2^200, which only its product with zero brings within a u128.
+        let move_vec1 =
+            MoveUntypedVector::from_rust_vec::<U256>(vec![U256([0, 1 << 72]), U256([3, 0])]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<U256>(vec![U256([0, 0]), U256([5, 0])]);
+        {
+            let rv1 = TypedMoveBorrowedRustVec::new(&U256_TYPE, &move_vec1);
+            let rv2 = TypedMoveBorrowedRustVec::new(&U256_TYPE, &move_vec2);
+            assert_eq!(rv1.dot_product(&rv2), 15);
+        }
+        move_vec1.destroy(&U256_TYPE);
+        move_vec2.destroy(&U256_TYPE);
+    }
+}
+
+#[test]
+#[should_panic(expected = "aborted with code 4017")]
+fn test_vec_dot_product_overflow() {
+    unsafe {
+        let move_vec = MoveUntypedVector::from_rust_vec::<u64>(vec![u64::MAX, u64::MAX]);
+        let v = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec);
+        v.dot_product(&v);
+    }
+}
+
+#[test]
+#[should_panic(expected = "vector lengths differ")]
+fn test_vec_dot_product_length_mismatch() {
+    unsafe {
+        let move_vec1 = MoveUntypedVector::from_rust_vec::<u64>(vec![1, 2]);
+        let move_vec2 = MoveUntypedVector::from_rust_vec::<u64>(vec![1]);
+        let v1 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec1);
+        let v2 = TypedMoveBorrowedRustVec::new(&U64_TYPE, &move_vec2);
+        v1.dot_product(&v2);
+    }
+}
@@ -63,15 +63,23 @@
 use crate::{conv::*, rt_types::*};
 use alloc::vec::Vec;
 use core::{
+    cmp::Ordering,
     marker::PhantomData,
-    mem,
-    ops::{Deref, DerefMut},
-    ptr,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut, Range},
+    ptr, slice,
 };
 
+/// The abort code of Move arithmetic errors,
+/// `move_core_types::vm_status::StatusCode::ARITHMETIC_ERROR`.
+const ARITHMETIC_ERROR: u64 = 4017;
+
+/// A borrowed Move vector.
+///
+/// This views the buffer of the `MoveUntypedVector` as a slice rather
+/// than a fabricated `Vec`, so that it can also view a sub-range of it.
 pub struct MoveBorrowedRustVec<'mv, T> {
-    inner: Vec<T>,
-    _lifetime: PhantomData<&'mv ()>,
+    inner: &'mv [T],
 }
 
 #[derive(Debug)]
@@ -118,15 +126,19 @@ pub enum TypedMoveBorrowedRustVecMut<'mv> {
 /// Move's `vector<T>`.
 #[derive(Debug)]
 pub struct MoveBorrowedRustVecOfStruct<'mv> {
-    inner: &'mv MoveUntypedVector,
+    /// A non-owning copy of the borrowed header, so that a slice can
+    /// describe a sub-range of the parent's buffer.
+    inner: MoveUntypedVector,
     type_: &'mv StructTypeInfo,
     full_type: &'mv MoveType,
+    _lifetime: PhantomData<&'mv ()>,
 }
 
 #[derive(Debug)]
 pub struct MoveBorrowedRustVecOfStructMut<'mv> {
     inner: &'mv mut MoveUntypedVector,
     type_: &'mv StructTypeInfo,
+    full_type: &'mv MoveType,
 }
 
 impl MoveUntypedVector {
@@ -296,6 +308,116 @@ impl MoveUntypedVector {
         }
     }
 
+    /// Makes a deep copy of the vector, copying the vectors owned by its elements.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe because the provided type must be correct.
+    pub unsafe fn copy(&self, type_ve: &MoveType) -> MoveUntypedVector {
+        match type_ve.type_desc {
+            TypeDesc::Bool => MoveUntypedVector::from_rust_vec(copy_bitwise::<bool>(self)),
+            TypeDesc::U8 => MoveUntypedVector::from_rust_vec(copy_bitwise::<u8>(self)),
+            TypeDesc::U16 => MoveUntypedVector::from_rust_vec(copy_bitwise::<u16>(self)),
+            TypeDesc::U32 => MoveUntypedVector::from_rust_vec(copy_bitwise::<u32>(self)),
+            TypeDesc::U64 => MoveUntypedVector::from_rust_vec(copy_bitwise::<u64>(self)),
+            TypeDesc::U128 => MoveUntypedVector::from_rust_vec(copy_bitwise::<u128>(self)),
+            TypeDesc::U256 => MoveUntypedVector::from_rust_vec(copy_bitwise::<U256>(self)),
+            TypeDesc::Address => {
+                MoveUntypedVector::from_rust_vec(copy_bitwise::<MoveAddress>(self))
+            }
+            TypeDesc::Signer => MoveUntypedVector::from_rust_vec(copy_bitwise::<MoveSigner>(self)),
+            TypeDesc::Vector => {
+                let type_inner_elt = (*type_ve.type_info).vector.element_type;
+                let src = MoveBorrowedRustVec::<MoveUntypedVector>::new(self);
+                // nb: recursive call, possible stack overflow.
+                let copies: Vec<MoveUntypedVector> =
+                    src.iter().map(|v| v.copy(type_inner_elt)).collect();
+                MoveUntypedVector::from_rust_vec(copies)
+            }
+            TypeDesc::Struct => {
+                let length = usize::try_from(self.length).expect("overflow");
+                let mut dst = MoveUntypedVector::empty(type_ve);
+                if length != 0 {
+                    let src_rv = MoveBorrowedRustVecOfStruct::new(type_ve, self);
+                    let mut dst_rv = MoveBorrowedRustVecOfStructMut::new(type_ve, &mut dst);
+                    dst_rv.reserve_exact(length);
+                    for i in 0..length {
+                        // nb: indirect recursive call, possible stack overflow.
+                        crate::structs::copy(
+                            src_rv.type_,
+                            src_rv.get(i),
+                            dst_rv.get_mut_unchecked_raw(i),
+                        );
+                    }
+                    dst_rv.set_length(length);
+                }
+                dst
+            }
+            TypeDesc::Reference => {
+                MoveUntypedVector::from_rust_vec(copy_bitwise::<MoveUntypedReference>(self))
+            }
+        }
+    }
+
+    /// Writes to `out` a struct of `out_struct_info` whose fields are
+    /// vectors, one per field of the `type_ve` records in the vector, and
+    /// moves each record's field value into its column, in record order.
+    ///
+    /// Consumes the vector: the records are left without fields, so only
+    /// their buffer is freed.
+    ///
+    /// Panics if the records are not structs, or the field counts differ.
+    ///
+    /// # Safety
+    ///
+    /// Each field of `out_struct_info` must be a vector of the type of the
+    /// corresponding record field. `out` must be valid for writes of the
+    /// struct, and is overwritten without being destroyed.
+    pub unsafe fn transpose_to_columns(
+        mut self,
+        type_ve: &MoveType,
+        out_struct_info: &StructTypeInfo,
+        out: *mut AnyValue,
+    ) {
+        if type_ve.type_desc != TypeDesc::Struct {
+            panic!("expected a vector of structs");
+        }
+        let record_info = &(*type_ve.type_info).struct_;
+        if record_info.field_array_len != out_struct_info.field_array_len {
+            panic!("field counts differ");
+        }
+
+        let record_fields = slice::from_raw_parts(
+            record_info.field_array_ptr,
+            usize::try_from(record_info.field_array_len).expect("overflow"),
+        );
+        let columns = crate::structs::walk_fields_mut(out_struct_info, out);
+        for (record_field, (col_ty, col_ptr, _)) in record_fields.iter().zip(columns) {
+            assert_eq!(
+                col_ty.type_desc,
+                TypeDesc::Vector,
+                "column must be a vector"
+            );
+            let elt_type = (*col_ty.type_info).vector.element_type;
+            assert_eq!(elt_type.type_desc, record_field.type_.type_desc);
+
+            let mut column = MoveUntypedVector::empty(elt_type);
+            let mut rv = TypedMoveBorrowedRustVecMut::new(elt_type, &mut column);
+            let offset = isize::try_from(record_field.offset).expect("overflow");
+            for record in MoveBorrowedRustVecOfStruct::new(type_ve, &self).iter() {
+                let src = (record as *const AnyValue as *const u8).offset(offset) as *mut AnyValue;
+                // Moves the field out of the record.
+                rv.push_back(src);
+            }
+            drop(rv);
+            ptr::write(col_ptr as *mut MoveUntypedVector, column);
+        }
+
+        // Every field has moved to a column.
+        self.length = 0;
+        self.destroy_empty(type_ve);
+    }
+
     pub unsafe fn into_rust_vec<T>(self) -> Vec<T> {
         Vec::from_raw_parts(
             self.ptr as *mut T,
@@ -315,6 +437,87 @@ impl MoveUntypedVector {
     }
 }
 
+/// Copies the elements of a vector of plain data, which have no owned parts.
+unsafe fn copy_bitwise<T>(v: &MoveUntypedVector) -> Vec<T> {
+    let length = usize::try_from(v.length).expect("overflow");
+    let mut copy = Vec::with_capacity(length);
+    ptr::copy_nonoverlapping(v.ptr as *const T, copy.as_mut_ptr(), length);
+    copy.set_len(length);
+    copy
+}
+
+/// Adds `v2` into `v1` element by element, aborting like Move arithmetic on overflow.
+fn checked_add_assign<T: Copy>(v1: &mut [T], v2: &[T], checked_add: impl Fn(T, T) -> Option<T>) {
+    for (e1, e2) in v1.iter_mut().zip(v2) {
+        *e1 = checked_add(*e1, *e2).unwrap_or_else(|| crate::target_defs::abort(ARITHMETIC_ERROR));
+    }
+}
+
+/// Replaces each element of `v1` with `f` of it and the element of `v2` at its index.
+fn zip_assign<T: Copy>(v1: &mut [T], v2: &[T], f: impl Fn(T, T) -> T) {
+    for (e1, e2) in v1.iter_mut().zip(v2) {
+        *e1 = f(*e1, *e2);
+    }
+}
+
+/// Replaces each element with the sum of it and all before it, aborting
+/// like Move arithmetic on overflow.
+fn checked_prefix_sum<T: Copy>(v: &mut [T], checked_add: impl Fn(T, T) -> Option<T>) {
+    for i in 1..v.len() {
+        v[i] = checked_add(v[i - 1], v[i])
+            .unwrap_or_else(|| crate::target_defs::abort(ARITHMETIC_ERROR));
+    }
+}
+
+/// Sums the products of corresponding elements in a `u128` accumulator,
+/// aborting like Move arithmetic on overflow.
+fn checked_dot_product<T: Copy + Into<u128>>(v1: &[T], v2: &[T]) -> u128 {
+    v1.iter()
+        .zip(v2)
+        .try_fold(0u128, |acc, (&e1, &e2)| {
+            e1.into().checked_mul(e2.into())?.checked_add(acc)
+        })
+        .unwrap_or_else(|| crate::target_defs::abort(ARITHMETIC_ERROR))
+}
+
+/// Overwrites each element with a bitwise copy of `value`.
+///
+/// Only for plain data, since overwritten elements are not destroyed.
+unsafe fn fill_bitwise<T>(v: &mut [T], value: &AnyValue) {
+    for elt in v {
+        ptr::write(elt, ptr::read(value as *const AnyValue as *const T));
+    }
+}
+
+unsafe fn retain_ne_bitwise<T: PartialEq>(v: &mut Vec<T>, sentinel: &AnyValue) {
+    let sentinel = &*(sentinel as *const AnyValue as *const T);
+    v.retain(|elt| elt != sentinel);
+}
+
+/// Panics unless `perm` holds each index of `0..len` exactly once.
+fn check_permutation(perm: &[usize], len: usize) {
+    if perm.len() != len {
+        panic!("invalid permutation");
+    }
+    let mut seen = alloc::vec![false; len];
+    for &i in perm {
+        if i >= len || mem::replace(&mut seen[i], true) {
+            panic!("invalid permutation");
+        }
+    }
+}
+
+/// Moves the element at `perm[i]` to `i` through a temporary buffer.
+///
+/// `perm` must be a permutation of the indices of `v`, so each element is
+/// moved exactly once.
+unsafe fn permute_moving<T>(v: &mut [T], perm: &[usize]) {
+    let mut permuted: Vec<T> = perm.iter().map(|&from| ptr::read(&v[from])).collect();
+    ptr::copy_nonoverlapping(permuted.as_ptr(), v.as_mut_ptr(), v.len());
+    // The elements are owned by `v` again.
+    permuted.set_len(0);
+}
+
 impl MoveByteVector {
     pub unsafe fn as_rust_vec<'mv>(&'mv self) -> MoveBorrowedRustVec<'mv, u8> {
         assert_eq!(
@@ -351,15 +554,26 @@ impl MoveByteVector {
 
 impl<'mv, T> MoveBorrowedRustVec<'mv, T> {
     pub unsafe fn new(mv: &MoveUntypedVector) -> MoveBorrowedRustVec<'_, T> {
-        let rv = Vec::from_raw_parts(
-            mv.ptr as *mut T,
+        let rv = slice::from_raw_parts(
+            mv.ptr as *const T,
             usize::try_from(mv.length).expect("overflow"),
-            usize::try_from(mv.capacity).expect("overflow"),
         );
-        MoveBorrowedRustVec {
-            inner: rv,
-            _lifetime: PhantomData,
+        MoveBorrowedRustVec { inner: rv }
+    }
+
+    /// Borrows the elements in `range`, for as long as the parent vector.
+    fn slice(&self, range: Range<usize>) -> &'mv [T] {
+        if range.start > range.end || range.end > self.inner.len() {
+            panic!("index out of bounds");
         }
+
+        &self.inner[range]
+    }
+}
+
+impl<'mv, T> From<&'mv [T]> for MoveBorrowedRustVec<'mv, T> {
+    fn from(inner: &'mv [T]) -> MoveBorrowedRustVec<'mv, T> {
+        MoveBorrowedRustVec { inner }
     }
 }
 
@@ -377,24 +591,6 @@ impl<'mv, T> MoveBorrowedRustVecMut<'mv, T> {
     }
 }
 
-impl<'mv, T> Drop for MoveBorrowedRustVec<'mv, T> {
-    fn drop(&mut self) {
-        let rv = mem::take(&mut self.inner);
-
-        /* mem::forget takes rv by value so it is no longer in scope to be
-        passed to any other function.  We call forget here because
-        rv is type Vec, which owns its interior buffer pointer, but
-        this Vec was temporarily fabricated from a MoveUntypedVector,
-        which actually owns that buffer. So forgetting the Vec quietly
-        destroys it without running the Vec destructor - so that the
-        original MoveUntypedVector can continue owning that buffer.
-        The only reason to call mem::forget is to not run a
-        destructor. */
-
-        mem::forget(rv);
-    }
-}
-
 impl<'mv, T> Drop for MoveBorrowedRustVecMut<'mv, T> {
     fn drop(&mut self) {
         let mut rv = mem::take(&mut self.inner);
@@ -408,10 +604,10 @@ impl<'mv, T> Drop for MoveBorrowedRustVecMut<'mv, T> {
 }
 
 impl<'mv, T> Deref for MoveBorrowedRustVec<'mv, T> {
-    type Target = Vec<T>;
+    type Target = [T];
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        self.inner
     }
 }
 
@@ -504,9 +700,14 @@ impl<'mv> MoveBorrowedRustVecOfStruct<'mv> {
     ) -> MoveBorrowedRustVecOfStruct<'mv> {
         assert_eq!(ty.type_desc, TypeDesc::Struct);
         MoveBorrowedRustVecOfStruct {
-            inner: mv,
+            inner: MoveUntypedVector {
+                ptr: mv.ptr,
+                capacity: mv.capacity,
+                length: mv.length,
+            },
             type_: &(*ty.type_info).struct_,
             full_type: ty,
+            _lifetime: PhantomData,
         }
     }
 }
@@ -520,6 +721,7 @@ impl<'mv> MoveBorrowedRustVecOfStructMut<'mv> {
         MoveBorrowedRustVecOfStructMut {
             inner: mv,
             type_: &(*ty.type_info).struct_,
+            full_type: ty,
         }
     }
 }
@@ -628,163 +830,1449 @@ impl<'mv> TypedMoveBorrowedRustVec<'mv> {
         };
         is_eq
     }
-}
 
-impl<'mv> TypedMoveBorrowedRustVecMut<'mv> {
-    pub fn len(&self) -> u64 {
-        let len = match self {
-            TypedMoveBorrowedRustVecMut::Bool(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U8(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U16(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U32(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U64(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U128(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::U256(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::Address(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::Signer(v) => v.len(),
-            TypedMoveBorrowedRustVecMut::Vector(_t, v) => v.len(),
-            TypedMoveBorrowedRustVecMut::Struct(s) => {
-                usize::try_from(s.inner.length).expect("overflow")
-            }
-            TypedMoveBorrowedRustVecMut::Reference(_t, v) => v.len(),
+    /// Copies the vector into a new vector of `type_to` elements, each
+    /// zero-extended from the element type of this vector.
+    ///
+    /// Panics unless both element types are integers and `type_to` is wider.
+    pub fn clone_widen(&self, type_to: &MoveType) -> MoveUntypedVector {
+        let width_from = match self {
+            TypedMoveBorrowedRustVec::U8(_) => 8,
+            TypedMoveBorrowedRustVec::U16(_) => 16,
+            TypedMoveBorrowedRustVec::U32(_) => 32,
+            TypedMoveBorrowedRustVec::U64(_) => 64,
+            TypedMoveBorrowedRustVec::U128(_) => 128,
+            _ => panic!("widening a vector of non-integers"),
+        };
+        let width_to = match type_to.type_desc {
+            TypeDesc::U8 => 8,
+            TypeDesc::U16 => 16,
+            TypeDesc::U32 => 32,
+            TypeDesc::U64 => 64,
+            TypeDesc::U128 => 128,
+            TypeDesc::U256 => 256,
+            _ => panic!("widening to a non-integer"),
         };
+        assert!(width_from < width_to, "widening to a narrower integer");
 
-        u64::try_from(len).expect("u64")
+        match self {
+            TypedMoveBorrowedRustVec::U8(v) => widen(v, type_to),
+            TypedMoveBorrowedRustVec::U16(v) => widen(v, type_to),
+            TypedMoveBorrowedRustVec::U32(v) => widen(v, type_to),
+            TypedMoveBorrowedRustVec::U64(v) => widen(v, type_to),
+            TypedMoveBorrowedRustVec::U128(v) => widen(v, type_to),
+            _ => unreachable!(),
+        }
     }
 
-    pub unsafe fn push_back(&mut self, e: *mut AnyValue) {
+    /// Copies a `vector<u32>` into a new `vector<u64>`.
+    pub fn clone_widen_u32_to_u64(&self) -> MoveUntypedVector {
         match self {
-            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.push(ptr::read(e as *const bool)),
-            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.push(ptr::read(e as *const u8)),
-            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.push(ptr::read(e as *const u16)),
-            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.push(ptr::read(e as *const u32)),
-            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.push(ptr::read(e as *const u64)),
-            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.push(ptr::read(e as *const u128)),
-            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.push(ptr::read(e as *const U256)),
-            TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
-                v.push(ptr::read(e as *const MoveAddress))
-            }
-            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
-                v.push(ptr::read(e as *const MoveSigner))
-            }
-            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
-                v.push(ptr::read(e as *const MoveUntypedVector))
-            }
-            TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.push(e),
-            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
-                v.push(ptr::read(e as *const MoveUntypedReference))
+            TypedMoveBorrowedRustVec::U32(v) => {
+                let widened: Vec<u64> = v.iter().map(|&e| u64::from(e)).collect();
+                MoveUntypedVector::from_rust_vec(widened)
             }
+            _ => panic!("expected a vector<u32>"),
         }
     }
 
-    pub fn borrow_mut(&mut self, i: u64) -> *mut AnyValue {
-        unsafe {
-            let i = usize::try_from(i).expect("usize");
-            match self {
-                TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
-                    &mut v[i] as *mut bool as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::U8(ref mut v) => &mut v[i] as *mut u8 as *mut AnyValue,
-                TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
-                    &mut v[i] as *mut u16 as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::U32(ref mut v) => {
-                    &mut v[i] as *mut u32 as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::U64(ref mut v) => {
-                    &mut v[i] as *mut u64 as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::U128(ref mut v) => {
-                    &mut v[i] as *mut u128 as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::U256(ref mut v) => {
-                    &mut v[i] as *mut U256 as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
-                    &mut v[i] as *mut MoveAddress as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
-                    &mut v[i] as *mut MoveSigner as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
-                    &mut v[i] as *mut MoveUntypedVector as *mut AnyValue
-                }
-                TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.get_mut(i),
-                TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
-                    &mut v[i] as *mut MoveUntypedReference as *mut AnyValue
+    /// Copies the bytes `start..start + len` of a `vector<u8>` into a new vector.
+    ///
+    /// Panics if the window is out of bounds.
+    pub fn subbytes(&self, start: usize, len: usize) -> MoveUntypedVector {
+        match self {
+            TypedMoveBorrowedRustVec::U8(v) => {
+                let end = start.checked_add(len).expect("overflow");
+                if end > v.len() {
+                    panic!("index out of bounds");
                 }
+                MoveUntypedVector::from_rust_vec(v[start..end].to_vec())
             }
+            _ => panic!("expected a vector<u8>"),
         }
     }
 
-    pub unsafe fn pop_back(&mut self, r: *mut AnyValue) {
-        let msg = "popping from empty vec";
+    /// Counts the bits set across all bytes of a `vector<u8>`.
+    pub fn count_ones(&self) -> u64 {
         match self {
-            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
-                ptr::write(r as *mut bool, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
-                ptr::write(r as *mut u8, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
-                ptr::write(r as *mut u16, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U32(ref mut v) => {
-                ptr::write(r as *mut u32, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U64(ref mut v) => {
-                ptr::write(r as *mut u64, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U128(ref mut v) => {
-                ptr::write(r as *mut u128, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::U256(ref mut v) => {
-                ptr::write(r as *mut U256, v.pop().expect(msg));
-            }
-            TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
-                ptr::write(r as *mut MoveAddress, v.pop().expect(msg));
+            TypedMoveBorrowedRustVec::U8(v) => v.iter().map(|b| u64::from(b.count_ones())).sum(),
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Whether a `vector<u8>` begins with the bytes of `prefix`.
+    pub fn starts_with(&self, prefix: &TypedMoveBorrowedRustVec) -> bool {
+        match (self, prefix) {
+            (TypedMoveBorrowedRustVec::U8(v), TypedMoveBorrowedRustVec::U8(prefix)) => {
+                v.starts_with(prefix)
             }
-            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
-                ptr::write(r as *mut MoveSigner, v.pop().expect(msg));
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Whether a `vector<u8>` ends with the bytes of `suffix`.
+    pub fn ends_with(&self, suffix: &TypedMoveBorrowedRustVec) -> bool {
+        match (self, suffix) {
+            (TypedMoveBorrowedRustVec::U8(v), TypedMoveBorrowedRustVec::U8(suffix)) => {
+                v.ends_with(suffix)
             }
-            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
-                ptr::write(r as *mut MoveUntypedVector, v.pop().expect(msg));
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Counts the bits that differ between two `vector<u8>` of the same length.
+    pub fn hamming_distance(&self, other: &TypedMoveBorrowedRustVec) -> u64 {
+        match (self, other) {
+            (TypedMoveBorrowedRustVec::U8(v1), TypedMoveBorrowedRustVec::U8(v2)) => {
+                if v1.len() != v2.len() {
+                    panic!("vector lengths differ");
+                }
+                v1.iter()
+                    .zip(v2.iter())
+                    .map(|(b1, b2)| u64::from((b1 ^ b2).count_ones()))
+                    .sum()
             }
-            TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.pop_into(r),
-            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
-                ptr::write(r as *mut MoveUntypedReference, v.pop().expect(msg));
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Sums the products of corresponding elements of two integer vectors
+    /// of the same type and length, aborting if a product or the sum
+    /// overflows a `u128`.
+    pub fn dot_product(&self, other: &TypedMoveBorrowedRustVec) -> u128 {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+
+        use TypedMoveBorrowedRustVec as V;
+        match (self, other) {
+            (V::U8(v1), V::U8(v2)) => checked_dot_product(v1, v2),
+            (V::U16(v1), V::U16(v2)) => checked_dot_product(v1, v2),
+            (V::U32(v1), V::U32(v2)) => checked_dot_product(v1, v2),
+            (V::U64(v1), V::U64(v2)) => checked_dot_product(v1, v2),
+            (V::U128(v1), V::U128(v2)) => checked_dot_product(v1, v2),
+            (V::U256(v1), V::U256(v2)) => {
+                // Multiplied in U256, so only products that don't fit the
+                // accumulator overflow it, however large their factors.
+                v1.iter()
+                    .zip(v2.iter())
+                    .try_fold(0u128, |acc, (e1, e2)| {
+                        let product = ethnum::U256(e1.0).checked_mul(ethnum::U256(e2.0))?;
+                        u128::try_from(product).ok()?.checked_add(acc)
+                    })
+                    .unwrap_or_else(|| crate::target_defs::abort(ARITHMETIC_ERROR))
             }
+            _ => panic!("dot_product: non-integer or mismatched element type"),
         }
     }
 
-    pub fn swap(&mut self, i: u64, j: u64) {
-        let i = usize::try_from(i).expect("usize");
-        let j = usize::try_from(j).expect("usize");
+    /// Counts the occurrences of each byte value in a `vector<u8>`,
+    /// indexed by the value.
+    pub fn byte_histogram(&self) -> [u64; 256] {
+        match self {
+            TypedMoveBorrowedRustVec::U8(v) => {
+                let mut histogram = [0u64; 256];
+                for b in v.iter() {
+                    histogram[usize::from(*b)] += 1;
+                }
+                histogram
+            }
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
 
+    /// Reads bit `index` of a `vector<u8>`, as bit `index % 8` of byte
+    /// `index / 8`, counting from the least significant bit.
+    ///
+    /// Panics if the bit is out of bounds.
+    pub fn get_bit(&self, index: usize) -> bool {
         match self {
-            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::Address(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => v.swap(i, j),
-            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.swap(i, j) },
-            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVec::U8(v) => {
+                let byte = v.get(index / 8).expect("index out of bounds");
+                byte & (1 << (index % 8)) != 0
+            }
+            _ => panic!("expected a vector<u8>"),
         }
     }
 
-    fn pop_back_discard(&mut self) {
-        let msg = "popping from empty vec";
+    /// Reads a `vector<u8>` of 8 bytes as a little-endian `u64`.
+    ///
+    /// Panics if the vector is not 8 bytes long.
+    pub fn bytes_to_u64_le(&self) -> u64 {
+        u64::from_le_bytes(self.le_bytes())
+    }
+
+    /// Reads a `vector<u8>` of 16 bytes as a little-endian `u128`.
+    ///
+    /// Panics if the vector is not 16 bytes long.
+    pub fn bytes_to_u128_le(&self) -> u128 {
+        u128::from_le_bytes(self.le_bytes())
+    }
+
+    /// Reads a `vector<u8>` of 32 bytes as a little-endian `u256`.
+    ///
+    /// Panics if the vector is not 32 bytes long.
+    pub fn bytes_to_u256_le(&self) -> U256 {
+        U256(ethnum::U256::from_le_bytes(self.le_bytes()).0)
+    }
+
+    fn le_bytes<const N: usize>(&self) -> [u8; N] {
         match self {
-            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
-                v.pop().expect(msg);
+            TypedMoveBorrowedRustVec::U8(v) => {
+                <[u8; N]>::try_from(&v[..]).expect("wrong byte length")
             }
-            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
-                v.pop().expect(msg);
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Builds a vector of `pair_type` structs, whose two fields are copies
+    /// of the corresponding elements of `self` and `other`.
+    ///
+    /// Panics if the lengths differ, or if `pair_type` is not a struct of
+    /// two fields.
+    ///
+    /// # Safety
+    ///
+    /// The fields of `pair_type` must have the element types of `self` and
+    /// `other`, in that order.
+    pub unsafe fn zip(
+        &self,
+        other: &TypedMoveBorrowedRustVec,
+        pair_type: &MoveType,
+    ) -> MoveUntypedVector {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+        assert_eq!(pair_type.type_desc, TypeDesc::Struct);
+        let pair_info = &(*pair_type.type_info).struct_;
+        assert_eq!(pair_info.field_array_len, 2, "pair must have two fields");
+
+        let len = usize::try_from(self.len()).expect("overflow");
+        let mut pairs = MoveUntypedVector::empty(pair_type);
+        let mut rv = MoveBorrowedRustVecOfStructMut::new(pair_type, &mut pairs);
+        rv.reserve_exact(len);
+        for i in 0..self.len() {
+            let pair_ptr = rv.get_mut_unchecked_raw(usize::try_from(i).expect("usize"));
+            let fields = crate::structs::walk_fields_mut(pair_info, pair_ptr);
+            for ((fld_ty, fld_ptr, _), src) in fields.zip([self.borrow(i), other.borrow(i)]) {
+                copy_value_to(fld_ty, src, fld_ptr);
+            }
+        }
+        rv.set_length(len);
+        pairs
+    }
+
+    /// Concatenates the inner vectors of a vector of vectors into a new
+    /// vector of copies of their elements, allocated once.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe because the stored element types must be correct.
+    pub unsafe fn flatten(&self) -> MoveUntypedVector {
+        let (elt_t, inner) = match self {
+            TypedMoveBorrowedRustVec::Vector(elt_t, inner) => (elt_t, inner),
+            _ => panic!("expected a vector of vectors"),
+        };
+        let total = inner.iter().fold(0usize, |total, v| {
+            let len = usize::try_from(v.length).expect("overflow");
+            total.checked_add(len).expect("overflow")
+        });
+
+        // Only for plain data, which is copied bitwise.
+        unsafe fn flatten_bitwise<T>(
+            inner: &[MoveUntypedVector],
+            total: usize,
+        ) -> MoveUntypedVector {
+            let mut flat = Vec::with_capacity(total);
+            for v in inner {
+                flat.extend(
+                    MoveBorrowedRustVec::<T>::new(v)
+                        .iter()
+                        .map(|e| ptr::read(e)),
+                );
+            }
+            MoveUntypedVector::from_rust_vec(flat)
+        }
+
+        match elt_t.type_desc {
+            TypeDesc::Bool => flatten_bitwise::<bool>(inner, total),
+            TypeDesc::U8 => flatten_bitwise::<u8>(inner, total),
+            TypeDesc::U16 => flatten_bitwise::<u16>(inner, total),
+            TypeDesc::U32 => flatten_bitwise::<u32>(inner, total),
+            TypeDesc::U64 => flatten_bitwise::<u64>(inner, total),
+            TypeDesc::U128 => flatten_bitwise::<u128>(inner, total),
+            TypeDesc::U256 => flatten_bitwise::<U256>(inner, total),
+            TypeDesc::Address => flatten_bitwise::<MoveAddress>(inner, total),
+            TypeDesc::Signer => flatten_bitwise::<MoveSigner>(inner, total),
+            TypeDesc::Vector => {
+                let inner_elt_t = (*elt_t.type_info).vector.element_type;
+                let mut flat = Vec::with_capacity(total);
+                for v in inner.iter() {
+                    let v = MoveBorrowedRustVec::<MoveUntypedVector>::new(v);
+                    flat.extend(v.iter().map(|e| e.copy(inner_elt_t)));
+                }
+                MoveUntypedVector::from_rust_vec(flat)
+            }
+            TypeDesc::Struct => {
+                let mut out = MoveUntypedVector::empty(elt_t);
+                let mut rv = MoveBorrowedRustVecOfStructMut::new(elt_t, &mut out);
+                rv.reserve_exact(total);
+                let mut k = 0;
+                for v in inner.iter() {
+                    for elt in MoveBorrowedRustVecOfStruct::new(elt_t, v).iter() {
+                        copy_value_to(elt_t, elt, rv.get_mut_unchecked_raw(k));
+                        k += 1;
+                    }
+                }
+                rv.set_length(total);
+                out
+            }
+            TypeDesc::Reference => flatten_bitwise::<MoveUntypedReference>(inner, total),
+        }
+    }
+
+    /// Borrows the elements in `range` as a vector, without copying them.
+    pub fn slice(&'mv self, range: Range<usize>) -> TypedMoveBorrowedRustVec<'mv> {
+        use TypedMoveBorrowedRustVec as V;
+        match self {
+            V::Bool(v) => V::Bool(v.slice(range).into()),
+            V::U8(v) => V::U8(v.slice(range).into()),
+            V::U16(v) => V::U16(v.slice(range).into()),
+            V::U32(v) => V::U32(v.slice(range).into()),
+            V::U64(v) => V::U64(v.slice(range).into()),
+            V::U128(v) => V::U128(v.slice(range).into()),
+            V::U256(v) => V::U256(v.slice(range).into()),
+            V::Address(v) => V::Address(v.slice(range).into()),
+            V::Signer(v) => V::Signer(v.slice(range).into()),
+            V::Vector(t, v) => V::Vector(*t, v.slice(range).into()),
+            V::Struct(s) => V::Struct(unsafe { s.slice(range) }),
+            V::Reference(t, v) => V::Reference(*t, v.slice(range).into()),
+        }
+    }
+
+    /// Copies the elements into a new vector, deep copying those that own vectors.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe because the stored element types must be correct.
+    pub unsafe fn copy_elements(&self) -> MoveUntypedVector {
+        let len = usize::try_from(self.len()).expect("usize");
+        let picks: Vec<(bool, usize)> = (0..len).map(|i| (true, i)).collect();
+        self.copy_picked(self, &picks)
+    }
+
+    /// Splits the vector into a vector of vectors of copies of up to
+    /// `chunk_size` consecutive elements each, the last one possibly shorter.
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe because the stored element types must be correct.
+    pub unsafe fn chunks(&self, chunk_size: usize) -> MoveUntypedVector {
+        if chunk_size == 0 {
+            panic!("zero chunk size");
+        }
+        let len = usize::try_from(self.len()).expect("usize");
+        let chunks: Vec<MoveUntypedVector> = (0..len)
+            .step_by(chunk_size)
+            .map(|start| {
+                let end = start.saturating_add(chunk_size).min(len);
+                let picks: Vec<(bool, usize)> = (start..end).map(|i| (true, i)).collect();
+                self.copy_picked(self, &picks)
+            })
+            .collect();
+        MoveUntypedVector::from_rust_vec(chunks)
+    }
+
+    /// Tests whether both vectors contain the same multiset of elements,
+    /// regardless of order.
+    ///
+    /// Matching is quadratic in the vector length, which is fine for the
+    /// small vectors that Move code treats as sets.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn set_eq(&self, v2: &TypedMoveBorrowedRustVec) -> bool {
+        let v_len = self.len();
+
+        if v_len != v2.len() {
+            return false;
+        }
+
+        let mut matched = alloc::vec![false; usize::try_from(v_len).expect("usize")];
+        'next_elt: for i in 0..v_len {
+            for j in 0..v_len {
+                let matched_j = &mut matched[usize::try_from(j).expect("usize")];
+                if !*matched_j && self.elt_cmp_eq(i, v2, j) {
+                    *matched_j = true;
+                    continue 'next_elt;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Returns, in order, the indices of the elements equal to an earlier
+    /// element.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn find_duplicates(&self) -> Vec<usize> {
+        let v_len = self.len();
+
+        let mut duplicates = Vec::new();
+        for i in 1..v_len {
+            if (0..i).any(|j| self.elt_cmp_eq(i, self, j)) {
+                duplicates.push(usize::try_from(i).expect("usize"));
+            }
+        }
+        duplicates
+    }
+
+    /// Compares two vectors element by element, returning a `vector<bool>`
+    /// of whether the elements at each index are equal.
+    ///
+    /// Panics if the lengths differ.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn eq_mask(&self, other: &TypedMoveBorrowedRustVec) -> MoveUntypedVector {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+        let mask: Vec<bool> = (0..self.len())
+            .map(|i| self.elt_cmp_eq(i, other, i))
+            .collect();
+        MoveUntypedVector::from_rust_vec(mask)
+    }
+
+    /// Orders two vectors lexicographically, a proper prefix first.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn cmp_ord(&self, v2: &TypedMoveBorrowedRustVec) -> Ordering {
+        let v1_len = self.len();
+        let v2_len = v2.len();
+
+        for i in 0..v1_len.min(v2_len) {
+            let ord = self.elt_cmp_ord(i, v2, i);
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        v1_len.cmp(&v2_len)
+    }
+
+    /// Whether every element is less than or equal to the next one.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn is_sorted(&self) -> bool {
+        (1..self.len()).all(|i| self.elt_cmp_ord(i - 1, self, i) != Ordering::Greater)
+    }
+
+    /// Merges two sorted vectors of the same type into a new sorted vector
+    /// of copies of their elements. Of equal elements, those of `self`
+    /// come first.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn merge_sorted(&self, other: &TypedMoveBorrowedRustVec) -> MoveUntypedVector {
+        debug_assert!(self.is_sorted());
+        debug_assert!(other.is_sorted());
+
+        // For each merged element, whether it is from `self`, and its index there.
+        let (len1, len2) = (self.len(), other.len());
+        let mut order = Vec::with_capacity(usize::try_from(len1 + len2).expect("usize"));
+        let (mut i, mut j) = (0, 0);
+        while i < len1 || j < len2 {
+            if j == len2 || (i < len1 && self.elt_cmp_ord(i, other, j) != Ordering::Greater) {
+                order.push((true, usize::try_from(i).expect("usize")));
+                i += 1;
+            } else {
+                order.push((false, usize::try_from(j).expect("usize")));
+                j += 1;
+            }
+        }
+
+        self.copy_picked(other, &order)
+    }
+
+    /// Returns a new vector of copies of the elements of `self` that are
+    /// also in `other`, each once, in the order of `self`.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn intersect(&self, other: &TypedMoveBorrowedRustVec) -> MoveUntypedVector {
+        let picks = self.pick_by_membership(other, true);
+        self.copy_picked(other, &picks)
+    }
+
+    /// Returns a new vector of copies of the elements of `self` that are
+    /// not in `other`, each once, in the order of `self`.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn difference(&self, other: &TypedMoveBorrowedRustVec) -> MoveUntypedVector {
+        let picks = self.pick_by_membership(other, false);
+        self.copy_picked(other, &picks)
+    }
+
+    /// Picks for `copy_picked` the first occurrence of each element of
+    /// `self` whose membership in `other` is `in_other`.
+    unsafe fn pick_by_membership(
+        &self,
+        other: &TypedMoveBorrowedRustVec,
+        in_other: bool,
+    ) -> Vec<(bool, usize)> {
+        let mut picks = Vec::new();
+        for i in 0..self.len() {
+            let is_repeated = (0..i).any(|j| self.elt_cmp_eq(i, self, j));
+            if !is_repeated && (0..other.len()).any(|j| self.elt_cmp_eq(i, other, j)) == in_other {
+                picks.push((true, usize::try_from(i).expect("usize")));
+            }
+        }
+        picks
+    }
+
+    /// Builds a vector of copies of the `picks`, each an index into `self`
+    /// if its flag is set, or else into `other`.
+    unsafe fn copy_picked(
+        &self,
+        other: &TypedMoveBorrowedRustVec,
+        picks: &[(bool, usize)],
+    ) -> MoveUntypedVector {
+        // Only for plain data, which is copied bitwise.
+        unsafe fn picked<T>(v1: &[T], v2: &[T], picks: &[(bool, usize)]) -> MoveUntypedVector {
+            let elts = picks.iter().map(|&(from_v1, k)| {
+                let elt = if from_v1 { &v1[k] } else { &v2[k] };
+                ptr::read(elt)
+            });
+            MoveUntypedVector::from_rust_vec(elts.collect::<Vec<T>>())
+        }
+
+        use TypedMoveBorrowedRustVec as V;
+        match (self, other) {
+            (V::Bool(v1), V::Bool(v2)) => picked(v1, v2, picks),
+            (V::U8(v1), V::U8(v2)) => picked(v1, v2, picks),
+            (V::U16(v1), V::U16(v2)) => picked(v1, v2, picks),
+            (V::U32(v1), V::U32(v2)) => picked(v1, v2, picks),
+            (V::U64(v1), V::U64(v2)) => picked(v1, v2, picks),
+            (V::U128(v1), V::U128(v2)) => picked(v1, v2, picks),
+            (V::U256(v1), V::U256(v2)) => picked(v1, v2, picks),
+            (V::Address(v1), V::Address(v2)) => picked(v1, v2, picks),
+            (V::Signer(v1), V::Signer(v2)) => picked(v1, v2, picks),
+            (V::Reference(_, v1), V::Reference(_, v2)) => picked(v1, v2, picks),
+            (V::Vector(elt_t, v1), V::Vector(_, v2)) => {
+                let elts = picks.iter().map(|&(from_v1, k)| {
+                    let elt = if from_v1 { &v1[k] } else { &v2[k] };
+                    elt.copy(elt_t)
+                });
+                MoveUntypedVector::from_rust_vec(elts.collect::<Vec<_>>())
+            }
+            (V::Struct(s1), V::Struct(s2)) => {
+                let elt_t = s1.full_type;
+                let mut out = MoveUntypedVector::empty(elt_t);
+                let mut rv = MoveBorrowedRustVecOfStructMut::new(elt_t, &mut out);
+                rv.reserve_exact(picks.len());
+                for (k, &(from_s1, idx)) in picks.iter().enumerate() {
+                    let src = if from_s1 { s1.get(idx) } else { s2.get(idx) };
+                    copy_value_to(elt_t, src, rv.get_mut_unchecked_raw(k));
+                }
+                rv.set_length(picks.len());
+                out
+            }
+            _ => panic!("vec_copy_picked: mismatched element types"),
+        }
+    }
+
+    /// Searches a sorted vector for `needle`, with the semantics of `slice::binary_search`.
+    ///
+    /// Returns `Ok` with the index of a matching element, or `Err` with the
+    /// index where `needle` could be inserted to keep the vector sorted.
+    ///
+    /// # Safety
+    ///
+    /// `needle` must be of the element type. Otherwise unsafe for the same
+    /// reasons as `cmp_eq`.
+    pub unsafe fn binary_search(&self, needle: &AnyValue) -> Result<usize, usize> {
+        debug_assert!(self.is_sorted());
+
+        let mut low = 0;
+        let mut high = usize::try_from(self.len()).expect("usize");
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.elt_cmp_ord_value(mid, needle) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    /// Searches a vector of structs sorted by their field `field_name` for
+    /// one whose field equals `key`, with the semantics of
+    /// `slice::binary_search`.
+    ///
+    /// # Safety
+    ///
+    /// `key` must be of the field's type. Otherwise unsafe for the same
+    /// reasons as `cmp_eq`.
+    pub unsafe fn binary_search_by_field(
+        &self,
+        field_name: &str,
+        key: &AnyValue,
+    ) -> Result<usize, usize> {
+        let s = match self {
+            TypedMoveBorrowedRustVec::Struct(s) => s,
+            _ => panic!("binary_search_by_field: element type is not a struct"),
+        };
+        let field_of =
+            |elt| crate::structs::find_field(s.type_, elt, field_name).expect("no such field");
+        debug_assert!((1..s.len()).all(|i| {
+            let (fld_ty, prev_ref) = field_of(s.get(i - 1));
+            let (_, fld_ref) = field_of(s.get(i));
+            crate::structs::field_cmp_ord(fld_ty, prev_ref, fld_ref) != Ordering::Greater
+        }));
+
+        let mut low = 0;
+        let mut high = s.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (fld_ty, fld_ref) = field_of(s.get(mid));
+            match crate::structs::field_cmp_ord(fld_ty, fld_ref, key) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    /// Returns the index of the first struct element whose field
+    /// `field_name` equals `expected`.
+    ///
+    /// # Safety
+    ///
+    /// `expected` must be of the field's type. Otherwise unsafe for the same
+    /// reasons as `cmp_eq`.
+    pub unsafe fn find_by_field(&self, field_name: &str, expected: &AnyValue) -> Option<usize> {
+        let s = match self {
+            TypedMoveBorrowedRustVec::Struct(s) => s,
+            _ => panic!("find_by_field: element type is not a struct"),
+        };
+        s.iter().position(|elt| {
+            let (fld_ty, fld_ref) =
+                crate::structs::find_field(s.type_, elt, field_name).expect("no such field");
+            crate::structs::field_cmp_eq(fld_ty, fld_ref, expected)
+        })
+    }
+
+    /// Tests whether the field `field_name` of every struct element equals
+    /// `expected`, stopping at the first that does not. True for an empty
+    /// vector.
+    ///
+    /// # Safety
+    ///
+    /// Same as `find_by_field`.
+    pub unsafe fn all_field_eq(&self, field_name: &str, expected: &AnyValue) -> bool {
+        let s = match self {
+            TypedMoveBorrowedRustVec::Struct(s) => s,
+            _ => panic!("all_field_eq: element type is not a struct"),
+        };
+        s.iter().all(|elt| {
+            let (fld_ty, fld_ref) =
+                crate::structs::find_field(s.type_, elt, field_name).expect("no such field");
+            crate::structs::field_cmp_eq(fld_ty, fld_ref, expected)
+        })
+    }
+
+    /// Tests whether the field `field_name` of some struct element equals
+    /// `expected`, stopping at the first that does. False for an empty
+    /// vector.
+    ///
+    /// # Safety
+    ///
+    /// Same as `find_by_field`.
+    pub unsafe fn any_field_eq(&self, field_name: &str, expected: &AnyValue) -> bool {
+        self.find_by_field(field_name, expected).is_some()
+    }
+
+    /// Groups the struct elements by the value of their field `field_name`,
+    /// in order of first appearance. Each group pairs a copy of the value
+    /// with a new vector of copies of its elements, in their order.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn group_by_field(
+        &self,
+        field_name: &str,
+    ) -> Vec<(OwnedMoveValue, MoveUntypedVector)> {
+        let s = match self {
+            TypedMoveBorrowedRustVec::Struct(s) => s,
+            _ => panic!("group_by_field: element type is not a struct"),
+        };
+        let field_of =
+            |elt| crate::structs::find_field(s.type_, elt, field_name).expect("no such field");
+
+        // The elements of each group, by index.
+        let mut groups: Vec<Vec<(bool, usize)>> = Vec::new();
+        for (i, elt) in s.iter().enumerate() {
+            let (fld_ty, fld_ref) = field_of(elt);
+            let group = groups.iter_mut().find(|group| {
+                let (_, first_ref) = field_of(s.get(group[0].1));
+                crate::structs::field_cmp_eq(fld_ty, first_ref, fld_ref)
+            });
+            match group {
+                Some(group) => group.push((true, i)),
+                None => groups.push(alloc::vec![(true, i)]),
+            }
+        }
+
+        groups
+            .iter()
+            .map(|picks| {
+                let (fld_ty, fld_ref) = field_of(s.get(picks[0].1));
+                let key = OwnedMoveValue::copy_from(fld_ty, fld_ref);
+                (key, self.copy_picked(self, picks))
+            })
+            .collect()
+    }
+
+    /// Orders element `i` of `self` against a value of the element type.
+    unsafe fn elt_cmp_ord_value(&self, i: usize, value: &AnyValue) -> Ordering {
+        let value = value as *const AnyValue;
+
+        use TypedMoveBorrowedRustVec as V;
+        match self {
+            V::Bool(rv) => rv[i].cmp(&*(value as *const bool)),
+            V::U8(rv) => rv[i].cmp(&*(value as *const u8)),
+            V::U16(rv) => rv[i].cmp(&*(value as *const u16)),
+            V::U32(rv) => rv[i].cmp(&*(value as *const u32)),
+            V::U64(rv) => rv[i].cmp(&*(value as *const u64)),
+            V::U128(rv) => rv[i].cmp(&*(value as *const u128)),
+            V::U256(rv) => rv[i].cmp(&*(value as *const U256)),
+            V::Address(rv) => rv[i].0.cmp(&(*(value as *const MoveAddress)).0),
+            V::Signer(rv) => rv[i].0 .0.cmp(&(*(value as *const MoveSigner)).0 .0),
+            V::Vector(elt_t, rv) => {
+                let mv_vec1 = TypedMoveBorrowedRustVec::new(elt_t, &rv[i]);
+                let mv_vec2 =
+                    TypedMoveBorrowedRustVec::new(elt_t, &*(value as *const MoveUntypedVector));
+                mv_vec1.cmp_ord(&mv_vec2)
+            }
+            V::Struct(s) => crate::structs::cmp_ord(s.full_type, s.get(i), &*value),
+            V::Reference(t, rv) => {
+                let value = &*(value as *const MoveUntypedReference);
+                crate::structs::field_cmp_ord(t, &*rv[i].0, &*value.0)
+            }
+        }
+    }
+
+    /// Orders element `i` of `self` against element `j` of `v2`.
+    unsafe fn elt_cmp_ord(&self, i: u64, v2: &TypedMoveBorrowedRustVec, j: u64) -> Ordering {
+        let i = usize::try_from(i).expect("usize");
+        let j = usize::try_from(j).expect("usize");
+
+        use TypedMoveBorrowedRustVec as V;
+        match (self, v2) {
+            (V::Bool(rv1), V::Bool(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U8(rv1), V::U8(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U16(rv1), V::U16(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U32(rv1), V::U32(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U64(rv1), V::U64(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U128(rv1), V::U128(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::U256(rv1), V::U256(rv2)) => rv1[i].cmp(&rv2[j]),
+            (V::Address(rv1), V::Address(rv2)) => rv1[i].0.cmp(&rv2[j].0),
+            (V::Signer(rv1), V::Signer(rv2)) => rv1[i].0 .0.cmp(&rv2[j].0 .0),
+            (V::Vector(elt_t1, rv1), V::Vector(elt_t2, rv2)) => {
+                assert_eq!(elt_t1.type_desc, elt_t2.type_desc);
+                let mv_vec1 = TypedMoveBorrowedRustVec::new(elt_t1, &rv1[i]);
+                let mv_vec2 = TypedMoveBorrowedRustVec::new(elt_t2, &rv2[j]);
+                mv_vec1.cmp_ord(&mv_vec2)
+            }
+            (V::Struct(s1), V::Struct(s2)) => {
+                crate::structs::cmp_ord(s1.full_type, s1.get(i), s2.get(j))
+            }
+            (V::Reference(t, rv1), V::Reference(_, rv2)) => {
+                crate::structs::field_cmp_ord(t, &*rv1[i].0, &*rv2[j].0)
+            }
+            _ => panic!("vec_cmp_ord: mismatched element types"),
+        }
+    }
+
+    /// Compares element `i` of `self` with element `j` of `v2`.
+    unsafe fn elt_cmp_eq(&self, i: u64, v2: &TypedMoveBorrowedRustVec, j: u64) -> bool {
+        let i = usize::try_from(i).expect("usize");
+        let j = usize::try_from(j).expect("usize");
+
+        use TypedMoveBorrowedRustVec as V;
+        match (self, v2) {
+            (V::Bool(rv1), V::Bool(rv2)) => rv1[i] == rv2[j],
+            (V::U8(rv1), V::U8(rv2)) => rv1[i] == rv2[j],
+            (V::U16(rv1), V::U16(rv2)) => rv1[i] == rv2[j],
+            (V::U32(rv1), V::U32(rv2)) => rv1[i] == rv2[j],
+            (V::U64(rv1), V::U64(rv2)) => rv1[i] == rv2[j],
+            (V::U128(rv1), V::U128(rv2)) => rv1[i] == rv2[j],
+            (V::U256(rv1), V::U256(rv2)) => rv1[i] == rv2[j],
+            (V::Address(rv1), V::Address(rv2)) => rv1[i] == rv2[j],
+            (V::Signer(rv1), V::Signer(rv2)) => rv1[i] == rv2[j],
+            (V::Vector(elt_t1, rv1), V::Vector(elt_t2, rv2)) => {
+                assert_eq!(elt_t1.type_desc, elt_t2.type_desc);
+                let mv_vec1 = TypedMoveBorrowedRustVec::new(elt_t1, &rv1[i]);
+                let mv_vec2 = TypedMoveBorrowedRustVec::new(elt_t2, &rv2[j]);
+                mv_vec1.cmp_eq(&mv_vec2)
+            }
+            (V::Struct(s1), V::Struct(s2)) => {
+                crate::structs::cmp_eq(s1.full_type, s1.get(i), s2.get(j))
+            }
+            (V::Reference(t, rv1), V::Reference(_, rv2)) => {
+                crate::structs::field_cmp_eq(t, &*rv1[i].0, &*rv2[j].0)
+            }
+            _ => panic!("vec_elt_cmp_eq: mismatched element types"),
+        }
+    }
+}
+
+/// Writes a deep copy of the `ty` value at `src` to the uninitialized `dst`.
+unsafe fn copy_value_to(ty: &MoveType, src: &AnyValue, dst: *mut AnyValue) {
+    unsafe fn bitwise<T>(v: &T, dst: *mut AnyValue) {
+        ptr::copy_nonoverlapping(v, dst as *mut T, 1);
+    }
+
+    match borrow_move_value_as_rust_value(ty, src) {
+        BorrowedTypedMoveValue::Bool(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U8(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U16(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U32(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U64(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U128(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::U256(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::Address(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::Signer(v) => bitwise(v, dst),
+        BorrowedTypedMoveValue::Vector(t, v) => {
+            ptr::write(dst as *mut MoveUntypedVector, v.copy(&t));
+        }
+        BorrowedTypedMoveValue::Struct(t, v) => {
+            crate::structs::copy(&(*t.type_info).struct_, v, dst);
+        }
+        BorrowedTypedMoveValue::Reference(_, v) => bitwise(v, dst),
+    }
+}
+
+/// Zero-extends each element of `v` into a new vector of `type_to` elements.
+///
+/// The caller checks that `type_to` is wider, so the casts don't truncate.
+fn widen<T: Copy + Into<u128>>(v: &[T], type_to: &MoveType) -> MoveUntypedVector {
+    let elts = v.iter().map(|&e| -> u128 { e.into() });
+    match type_to.type_desc {
+        TypeDesc::U16 => {
+            MoveUntypedVector::from_rust_vec(elts.map(|e| e as u16).collect::<Vec<_>>())
+        }
+        TypeDesc::U32 => {
+            MoveUntypedVector::from_rust_vec(elts.map(|e| e as u32).collect::<Vec<_>>())
+        }
+        TypeDesc::U64 => {
+            MoveUntypedVector::from_rust_vec(elts.map(|e| e as u64).collect::<Vec<_>>())
+        }
+        TypeDesc::U128 => MoveUntypedVector::from_rust_vec(elts.collect::<Vec<_>>()),
+        TypeDesc::U256 => {
+            let widened: Vec<U256> = elts.map(|e| U256(ethnum::U256::from(e).0)).collect();
+            MoveUntypedVector::from_rust_vec(widened)
+        }
+        _ => unreachable!(),
+    }
+}
+
+impl<'mv> TypedMoveBorrowedRustVecMut<'mv> {
+    pub fn len(&self) -> u64 {
+        let len = match self {
+            TypedMoveBorrowedRustVecMut::Bool(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U8(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U16(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U32(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U64(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U128(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::U256(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::Address(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::Signer(v) => v.len(),
+            TypedMoveBorrowedRustVecMut::Vector(_t, v) => v.len(),
+            TypedMoveBorrowedRustVecMut::Struct(s) => {
+                usize::try_from(s.inner.length).expect("overflow")
+            }
+            TypedMoveBorrowedRustVecMut::Reference(_t, v) => v.len(),
+        };
+
+        u64::try_from(len).expect("u64")
+    }
+
+    pub unsafe fn push_back(&mut self, e: *mut AnyValue) {
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.push(ptr::read(e as *const bool)),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.push(ptr::read(e as *const u8)),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.push(ptr::read(e as *const u16)),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.push(ptr::read(e as *const u32)),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.push(ptr::read(e as *const u64)),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.push(ptr::read(e as *const u128)),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.push(ptr::read(e as *const U256)),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
+                v.push(ptr::read(e as *const MoveAddress))
+            }
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
+                v.push(ptr::read(e as *const MoveSigner))
+            }
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
+                v.push(ptr::read(e as *const MoveUntypedVector))
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.push(e),
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
+                v.push(ptr::read(e as *const MoveUntypedReference))
+            }
+        }
+    }
+
+    pub fn borrow_mut(&mut self, i: u64) -> *mut AnyValue {
+        unsafe {
+            let i = usize::try_from(i).expect("usize");
+            match self {
+                TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
+                    &mut v[i] as *mut bool as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::U8(ref mut v) => &mut v[i] as *mut u8 as *mut AnyValue,
+                TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
+                    &mut v[i] as *mut u16 as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::U32(ref mut v) => {
+                    &mut v[i] as *mut u32 as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::U64(ref mut v) => {
+                    &mut v[i] as *mut u64 as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::U128(ref mut v) => {
+                    &mut v[i] as *mut u128 as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::U256(ref mut v) => {
+                    &mut v[i] as *mut U256 as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
+                    &mut v[i] as *mut MoveAddress as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
+                    &mut v[i] as *mut MoveSigner as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
+                    &mut v[i] as *mut MoveUntypedVector as *mut AnyValue
+                }
+                TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.get_mut(i),
+                TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
+                    &mut v[i] as *mut MoveUntypedReference as *mut AnyValue
+                }
+            }
+        }
+    }
+
+    pub unsafe fn pop_back(&mut self, r: *mut AnyValue) {
+        let msg = "popping from empty vec";
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
+                ptr::write(r as *mut bool, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
+                ptr::write(r as *mut u8, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
+                ptr::write(r as *mut u16, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => {
+                ptr::write(r as *mut u32, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => {
+                ptr::write(r as *mut u64, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => {
+                ptr::write(r as *mut u128, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => {
+                ptr::write(r as *mut U256, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
+                ptr::write(r as *mut MoveAddress, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
+                ptr::write(r as *mut MoveSigner, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
+                ptr::write(r as *mut MoveUntypedVector, v.pop().expect(msg));
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.pop_into(r),
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
+                ptr::write(r as *mut MoveUntypedReference, v.pop().expect(msg));
+            }
+        }
+    }
+
+    pub fn swap(&mut self, i: u64, j: u64) {
+        let i = usize::try_from(i).expect("usize");
+        let j = usize::try_from(j).expect("usize");
+
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => v.swap(i, j),
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.swap(i, j) },
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => v.swap(i, j),
+        }
+    }
+
+    /// Removes and returns the element at `i`, shifting the later elements down.
+    ///
+    /// Unlike `swap`-based removal this preserves the order of the remaining
+    /// elements. Ownership of an owning element passes to the returned value.
+    pub fn remove(&mut self, i: usize) -> OwnedMoveValue {
+        if i >= usize::try_from(self.len()).expect("usize") {
+            panic!("index out of bounds");
+        }
+
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => OwnedMoveValue::Bool(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => OwnedMoveValue::U8(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => OwnedMoveValue::U16(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => OwnedMoveValue::U32(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => OwnedMoveValue::U64(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => OwnedMoveValue::U128(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => OwnedMoveValue::U256(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => OwnedMoveValue::Address(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => OwnedMoveValue::Signer(v.remove(i)),
+            TypedMoveBorrowedRustVecMut::Vector(t, ref mut v) => {
+                OwnedMoveValue::Vector(*t, v.remove(i))
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.remove(i) },
+            TypedMoveBorrowedRustVecMut::Reference(t, ref mut v) => {
+                OwnedMoveValue::Reference(*t, v.remove(i))
+            }
+        }
+    }
+
+    /// Overwrites every element with a copy of `value`, keeping the length.
+    ///
+    /// Each element gets its own deep copy of `value`, and elements that
+    /// own vectors are destroyed before being overwritten.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be of the element type, and must not be an element of `self`.
+    pub unsafe fn fill(&mut self, value: &AnyValue) {
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => fill_bitwise(v, value),
+            TypedMoveBorrowedRustVecMut::Vector(t, ref mut v) => {
+                let src = &*(value as *const AnyValue as *const MoveUntypedVector);
+                for elt in v.iter_mut() {
+                    ptr::read(elt).destroy(t);
+                    ptr::write(elt, src.copy(t));
+                }
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut s) => s.fill(value),
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => fill_bitwise(v, value),
+        }
+    }
+
+    /// Adds the elements of `other` to the corresponding elements of `self`.
+    ///
+    /// Both must be integer vectors of the same type and length. Overflow
+    /// aborts with Move's arithmetic error.
+    pub fn add_assign(&mut self, other: &TypedMoveBorrowedRustVec) {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+
+        use TypedMoveBorrowedRustVec as V;
+        use TypedMoveBorrowedRustVecMut as VM;
+        match (self, other) {
+            (VM::U8(ref mut v1), V::U8(v2)) => checked_add_assign(v1, v2, u8::checked_add),
+            (VM::U16(ref mut v1), V::U16(v2)) => checked_add_assign(v1, v2, u16::checked_add),
+            (VM::U32(ref mut v1), V::U32(v2)) => checked_add_assign(v1, v2, u32::checked_add),
+            (VM::U64(ref mut v1), V::U64(v2)) => checked_add_assign(v1, v2, u64::checked_add),
+            (VM::U128(ref mut v1), V::U128(v2)) => checked_add_assign(v1, v2, u128::checked_add),
+            (VM::U256(ref mut v1), V::U256(v2)) => checked_add_assign(v1, v2, |a, b| {
+                let sum = ethnum::U256(a.0).checked_add(ethnum::U256(b.0))?;
+                Some(U256(sum.0))
+            }),
+            _ => panic!("vec_add_assign: non-integer or mismatched element type"),
+        }
+    }
+
+    /// Sets each element of `self` to the minimum of it and the
+    /// corresponding element of `other`.
+    ///
+    /// Both must be integer vectors of the same type and length.
+    pub fn min_with(&mut self, other: &TypedMoveBorrowedRustVec) {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+
+        use TypedMoveBorrowedRustVec as V;
+        use TypedMoveBorrowedRustVecMut as VM;
+        match (self, other) {
+            (VM::U8(ref mut v1), V::U8(v2)) => zip_assign(v1, v2, u8::min),
+            (VM::U16(ref mut v1), V::U16(v2)) => zip_assign(v1, v2, u16::min),
+            (VM::U32(ref mut v1), V::U32(v2)) => zip_assign(v1, v2, u32::min),
+            (VM::U64(ref mut v1), V::U64(v2)) => zip_assign(v1, v2, u64::min),
+            (VM::U128(ref mut v1), V::U128(v2)) => zip_assign(v1, v2, u128::min),
+            (VM::U256(ref mut v1), V::U256(v2)) => zip_assign(v1, v2, |a, b| {
+                U256(ethnum::U256(a.0).min(ethnum::U256(b.0)).0)
+            }),
+            _ => panic!("vec_min_with: non-integer or mismatched element type"),
+        }
+    }
+
+    /// Sets each element of `self` to the maximum of it and the
+    /// corresponding element of `other`.
+    ///
+    /// Both must be integer vectors of the same type and length.
+    pub fn max_with(&mut self, other: &TypedMoveBorrowedRustVec) {
+        if self.len() != other.len() {
+            panic!("vector lengths differ");
+        }
+
+        use TypedMoveBorrowedRustVec as V;
+        use TypedMoveBorrowedRustVecMut as VM;
+        match (self, other) {
+            (VM::U8(ref mut v1), V::U8(v2)) => zip_assign(v1, v2, u8::max),
+            (VM::U16(ref mut v1), V::U16(v2)) => zip_assign(v1, v2, u16::max),
+            (VM::U32(ref mut v1), V::U32(v2)) => zip_assign(v1, v2, u32::max),
+            (VM::U64(ref mut v1), V::U64(v2)) => zip_assign(v1, v2, u64::max),
+            (VM::U128(ref mut v1), V::U128(v2)) => zip_assign(v1, v2, u128::max),
+            (VM::U256(ref mut v1), V::U256(v2)) => zip_assign(v1, v2, |a, b| {
+                U256(ethnum::U256(a.0).max(ethnum::U256(b.0)).0)
+            }),
+            _ => panic!("vec_max_with: non-integer or mismatched element type"),
+        }
+    }
+
+    /// Reverses the byte order of each element of an integer vector, to
+    /// convert between little- and big-endian.
+    ///
+    /// Panics if the elements are not integers.
+    pub fn byte_swap_elements(&mut self) {
+        use TypedMoveBorrowedRustVecMut as VM;
+        match self {
+            VM::U8(_) => {}
+            VM::U16(ref mut v) => v.iter_mut().for_each(|e| *e = e.swap_bytes()),
+            VM::U32(ref mut v) => v.iter_mut().for_each(|e| *e = e.swap_bytes()),
+            VM::U64(ref mut v) => v.iter_mut().for_each(|e| *e = e.swap_bytes()),
+            VM::U128(ref mut v) => v.iter_mut().for_each(|e| *e = e.swap_bytes()),
+            VM::U256(ref mut v) => v
+                .iter_mut()
+                .for_each(|e| *e = U256([e.0[1].swap_bytes(), e.0[0].swap_bytes()])),
+            _ => panic!("byte_swap_elements: element type is not an integer"),
+        }
+    }
+
+    /// Replaces each element of an integer vector with the running sum of
+    /// the elements up to and including it.
+    ///
+    /// Overflow aborts with Move's arithmetic error.
+    pub fn prefix_sum(&mut self) {
+        use TypedMoveBorrowedRustVecMut as VM;
+        match self {
+            VM::U8(ref mut v) => checked_prefix_sum(v, u8::checked_add),
+            VM::U16(ref mut v) => checked_prefix_sum(v, u16::checked_add),
+            VM::U32(ref mut v) => checked_prefix_sum(v, u32::checked_add),
+            VM::U64(ref mut v) => checked_prefix_sum(v, u64::checked_add),
+            VM::U128(ref mut v) => checked_prefix_sum(v, u128::checked_add),
+            VM::U256(ref mut v) => checked_prefix_sum(v, |a, b| {
+                let sum = ethnum::U256(a.0).checked_add(ethnum::U256(b.0))?;
+                Some(U256(sum.0))
+            }),
+            _ => panic!("vec_prefix_sum: non-integer element type"),
+        }
+    }
+
+    /// XORs `mask` over the bytes of a `vector<u8>`, repeating the mask if
+    /// it is shorter. An empty mask leaves the bytes as they are.
+    pub fn mask_with(&mut self, mask: &[u8]) {
+        self.apply_mask(mask, |b, m| b ^ m);
+    }
+
+    /// Like `mask_with`, but ANDs the mask over the bytes.
+    pub fn and_mask_with(&mut self, mask: &[u8]) {
+        self.apply_mask(mask, |b, m| b & m);
+    }
+
+    fn apply_mask(&mut self, mask: &[u8], op: impl Fn(u8, u8) -> u8) {
+        match self {
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
+                for (b, m) in v.iter_mut().zip(mask.iter().cycle()) {
+                    *b = op(*b, *m);
+                }
+            }
+            _ => panic!("expected a vector<u8>"),
+        }
+    }
+
+    /// Reorders struct elements so that those whose field `field_name` is
+    /// less than `pivot` come first, and returns the number of such elements.
+    ///
+    /// The order within each part is unspecified.
+    ///
+    /// # Safety
+    ///
+    /// `pivot` must be of the field's type.
+    pub unsafe fn partition_by_field(&mut self, field_name: &str, pivot: &AnyValue) -> usize {
+        match self {
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => {
+                v.partition_by_field(field_name, pivot)
+            }
+            _ => panic!("partition_by_field: element type is not a struct"),
+        }
+    }
+
+    /// Rotates the elements left by `n` places, so the element at `n` comes first.
+    ///
+    /// `n` may exceed the length and is taken modulo it. Elements are
+    /// moved, so owning elements keep their payloads.
+    pub fn rotate_left(&mut self, n: usize) {
+        let len = usize::try_from(self.len()).expect("usize");
+        if len == 0 {
+            return;
+        }
+        let n = n % len;
+
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => v.rotate_left(n),
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.rotate_left(n) },
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => v.rotate_left(n),
+        }
+    }
+
+    /// Rotates the elements right by `n` places, so the last `n` elements come first.
+    ///
+    /// `n` may exceed the length and is taken modulo it.
+    pub fn rotate_right(&mut self, n: usize) {
+        let len = usize::try_from(self.len()).expect("usize");
+        if len == 0 {
+            return;
+        }
+        let n = n % len;
+
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => v.rotate_right(n),
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.rotate_right(n) },
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => v.rotate_right(n),
+        }
+    }
+
+    /// Reorders the elements so that the element at `perm[i]` comes to `i`.
+    ///
+    /// Elements are moved, so owning elements keep their payloads. Panics
+    /// if `perm` is not a permutation of `0..len`.
+    pub fn apply_permutation(&mut self, perm: &[usize]) {
+        let len = usize::try_from(self.len()).expect("usize");
+        check_permutation(perm, len);
+
+        unsafe {
+            match self {
+                TypedMoveBorrowedRustVecMut::Bool(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U8(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U16(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U32(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U64(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U128(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::U256(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::Address(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::Signer(ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => permute_moving(v, perm),
+                TypedMoveBorrowedRustVecMut::Struct(ref mut v) => v.apply_permutation(perm),
+                TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => permute_moving(v, perm),
+            }
+        }
+    }
+
+    /// Moves the elements `[at..]` into a new vector, leaving `[..at]` in `self`.
+    ///
+    /// Element payloads are moved, not copied or cloned, so ownership of any
+    /// vectors owned by the elements passes to the returned vector.
+    pub fn split_off(&mut self, at: usize) -> MoveUntypedVector {
+        if at > usize::try_from(self.len()).expect("usize") {
+            panic!("index out of bounds");
+        }
+
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::Vector(_t, ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => unsafe { v.split_off(at) },
+            TypedMoveBorrowedRustVecMut::Reference(_t, ref mut v) => {
+                MoveUntypedVector::from_rust_vec(v.split_off(at))
+            }
+        }
+    }
+
+    /// Removes consecutive equal elements, keeping the first of each run.
+    ///
+    /// Applied to a sorted vector this leaves each distinct element once.
+    /// Removed elements that own vectors are destroyed.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe for the same reasons as `cmp_eq`.
+    pub unsafe fn dedup(&mut self) {
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::Vector(t, ref mut v) => {
+                let elt_t = &*t;
+                // Safety: `MoveUntypedVector` has no destructor, so the
+                // duplicate is destroyed here before `dedup_by` discards it.
+                v.dedup_by(|dup, kept| {
+                    let is_eq = {
+                        let dup_rv = TypedMoveBorrowedRustVec::new(elt_t, dup);
+                        let kept_rv = TypedMoveBorrowedRustVec::new(elt_t, kept);
+                        dup_rv.cmp_eq(&kept_rv)
+                    };
+                    if is_eq {
+                        ptr::read(dup).destroy(elt_t);
+                    }
+                    is_eq
+                })
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => v.dedup(),
+            TypedMoveBorrowedRustVecMut::Reference(t, ref mut v) => {
+                // References are equal if their referents are.
+                let referent_t = &*t;
+                v.dedup_by(|dup, kept| crate::structs::field_cmp_eq(referent_t, &*dup.0, &*kept.0))
+            }
+        }
+    }
+
+    /// Removes all elements equal to `sentinel`, keeping the order of the rest.
+    ///
+    /// Removed elements that own vectors are destroyed.
+    ///
+    /// # Safety
+    ///
+    /// `sentinel` must be of the element type, and must not be an element of `self`.
+    pub unsafe fn retain_ne(&mut self, sentinel: &AnyValue) {
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U16(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U32(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U64(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U128(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::U256(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::Address(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::Signer(ref mut v) => retain_ne_bitwise(v, sentinel),
+            TypedMoveBorrowedRustVecMut::Vector(t, ref mut v) => {
+                let elt_t = &*t;
+                let sentinel = &*(sentinel as *const AnyValue as *const MoveUntypedVector);
+                let sentinel_rv = TypedMoveBorrowedRustVec::new(elt_t, sentinel);
+                // Safety: `MoveUntypedVector` has no destructor, so a removed
+                // element is destroyed here before `retain` discards it.
+                v.retain(|elt| {
+                    let is_eq = TypedMoveBorrowedRustVec::new(elt_t, elt).cmp_eq(&sentinel_rv);
+                    if is_eq {
+                        ptr::read(elt).destroy(elt_t);
+                    }
+                    !is_eq
+                })
+            }
+            TypedMoveBorrowedRustVecMut::Struct(ref mut v) => v.retain_ne(sentinel),
+            TypedMoveBorrowedRustVecMut::Reference(t, ref mut v) => {
+                // References are equal if their referents are.
+                let referent_t = &*t;
+                let sentinel = &*(sentinel as *const AnyValue as *const MoveUntypedReference);
+                v.retain(|elt| !crate::structs::field_cmp_eq(referent_t, &*elt.0, &*sentinel.0))
+            }
+        }
+    }
+
+    fn pop_back_discard(&mut self) {
+        let msg = "popping from empty vec";
+        match self {
+            TypedMoveBorrowedRustVecMut::Bool(ref mut v) => {
+                v.pop().expect(msg);
+            }
+            TypedMoveBorrowedRustVecMut::U8(ref mut v) => {
+                v.pop().expect(msg);
             }
             TypedMoveBorrowedRustVecMut::U16(ref mut v) => {
                 v.pop().expect(msg);
@@ -875,6 +2363,29 @@ impl<'mv> MoveBorrowedRustVecOfStruct<'mv> {
         let element_ptr = base_ptr.offset(offset);
         &*(element_ptr as *const AnyValue)
     }
+
+    pub unsafe fn slice(&self, range: Range<usize>) -> MoveBorrowedRustVecOfStruct<'mv> {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        if range.start > range.end || range.end > vec_len {
+            panic!("index out of bounds");
+        }
+
+        let offset = range.start.checked_mul(struct_size).expect("overflow");
+        let offset = isize::try_from(offset).expect("overflow");
+        let sub_len = u64::try_from(range.end - range.start).expect("overflow");
+        MoveBorrowedRustVecOfStruct {
+            inner: MoveUntypedVector {
+                ptr: self.inner.ptr.offset(offset),
+                capacity: sub_len,
+                length: sub_len,
+            },
+            type_: self.type_,
+            full_type: self.full_type,
+            _lifetime: PhantomData,
+        }
+    }
 }
 
 impl<'mv> MoveBorrowedRustVecOfStructMut<'mv> {
@@ -1059,6 +2570,186 @@ impl<'mv> MoveBorrowedRustVecOfStructMut<'mv> {
         // we must (I think) do this swap with raw pointers, not slices.
         ptr::swap_nonoverlapping(i_element_ptr, j_element_ptr, struct_size);
     }
+
+    pub unsafe fn remove(&mut self, i: usize) -> OwnedMoveValue {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        let element_ptr = self.get_mut(i);
+        let value = OwnedMoveValue::read_from(self.full_type, element_ptr);
+
+        // Safety: the moved-out slot is overwritten by the elements after it,
+        // which may overlap, so this must be `ptr::copy`.
+        let tail_len = vec_len - i - 1;
+        let byte_len = tail_len.checked_mul(struct_size).expect("overflow");
+        let element_ptr = element_ptr as *mut u8;
+        let struct_size_isize = isize::try_from(struct_size).expect("overflow");
+        ptr::copy(element_ptr.offset(struct_size_isize), element_ptr, byte_len);
+
+        self.inner.length = self.inner.length.checked_sub(1).expect("overflow");
+
+        value
+    }
+
+    pub unsafe fn rotate_left(&mut self, n: usize) {
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        if n > vec_len {
+            panic!("index out of bounds");
+        }
+
+        // Three reversals. Whole elements are swapped, so padding bytes are
+        // never read as bytes, and no temporary buffer is needed.
+        self.reverse_range(0, n);
+        self.reverse_range(n, vec_len);
+        self.reverse_range(0, vec_len);
+    }
+
+    pub unsafe fn rotate_right(&mut self, n: usize) {
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        if n > vec_len {
+            panic!("index out of bounds");
+        }
+
+        self.rotate_left(vec_len - n);
+    }
+
+    pub unsafe fn apply_permutation(&mut self, perm: &[usize]) {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+        let byte_len = vec_len.checked_mul(struct_size).expect("overflow");
+
+        // Safety: elements may have uninitialized padding bytes, so the
+        // temporary buffer holds them as `MaybeUninit`, and is copied with
+        // raw pointers.
+        let mut buf: Vec<MaybeUninit<u8>> = Vec::with_capacity(byte_len);
+        let buf_ptr = buf.as_mut_ptr() as *mut u8;
+        for (i, &from) in perm.iter().enumerate() {
+            let element_ptr = self.get_mut(from) as *const u8;
+            let offset = i.checked_mul(struct_size).expect("overflow");
+            ptr::copy_nonoverlapping(element_ptr, buf_ptr.add(offset), struct_size);
+        }
+        ptr::copy_nonoverlapping(buf_ptr, self.inner.ptr, byte_len);
+    }
+
+    pub unsafe fn partition_by_field(&mut self, field_name: &str, pivot: &AnyValue) -> usize {
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        // Whole elements are swapped, as in `rotate_left`.
+        let mut split = 0;
+        for i in 0..vec_len {
+            let element_ref = &*self.get_mut(i);
+            let (fld_ty, fld_ref) = crate::structs::find_field(self.type_, element_ref, field_name)
+                .expect("no such field");
+            if crate::structs::field_cmp_ord(fld_ty, fld_ref, pivot) == Ordering::Less {
+                self.swap(split, i);
+                split += 1;
+            }
+        }
+        split
+    }
+
+    unsafe fn reverse_range(&mut self, start: usize, end: usize) {
+        let (mut i, mut j) = (start, end);
+        while i + 1 < j {
+            j -= 1;
+            self.swap(i, j);
+            i += 1;
+        }
+    }
+
+    pub unsafe fn fill(&mut self, value: &AnyValue) {
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        for i in 0..vec_len {
+            let element_ptr = self.get_mut(i);
+            crate::structs::destroy(self.type_, element_ptr);
+            crate::structs::copy(self.type_, value, element_ptr);
+        }
+    }
+
+    pub unsafe fn dedup(&mut self) {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        if vec_len == 0 {
+            return;
+        }
+
+        // Index of the last element kept so far.
+        let mut kept = 0;
+        for i in 1..vec_len {
+            let kept_ptr = self.get_mut(kept);
+            let element_ptr = self.get_mut(i);
+            if crate::structs::cmp_eq(self.full_type, &*kept_ptr, &*element_ptr) {
+                crate::structs::destroy(self.type_, element_ptr);
+            } else {
+                kept += 1;
+                if kept != i {
+                    // Safety: the slot at `kept` has either been destroyed or
+                    // moved out already, so it is overwritten without a drop.
+                    let dst_ptr = self.get_mut(kept) as *mut u8;
+                    ptr::copy_nonoverlapping(element_ptr as *const u8, dst_ptr, struct_size);
+                }
+            }
+        }
+
+        self.set_length(kept + 1);
+    }
+
+    pub unsafe fn retain_ne(&mut self, sentinel: &AnyValue) {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        // Number of elements kept so far, compacted at the front as in `dedup`.
+        let mut kept = 0;
+        for i in 0..vec_len {
+            let element_ptr = self.get_mut(i);
+            if crate::structs::cmp_eq(self.full_type, &*element_ptr, sentinel) {
+                crate::structs::destroy(self.type_, element_ptr);
+            } else {
+                if kept != i {
+                    let dst_ptr = self.get_mut(kept) as *mut u8;
+                    ptr::copy_nonoverlapping(element_ptr as *const u8, dst_ptr, struct_size);
+                }
+                kept += 1;
+            }
+        }
+
+        self.set_length(kept);
+    }
+
+    pub unsafe fn split_off(&mut self, at: usize) -> MoveUntypedVector {
+        let struct_size = usize::try_from(self.type_.size).expect("overflow");
+        let vec_len = usize::try_from(self.inner.length).expect("overflow");
+
+        if at > vec_len {
+            panic!("index out of bounds");
+        }
+
+        let tail_len = vec_len - at;
+        let mut tail = MoveUntypedVector::empty(self.full_type);
+
+        // Safety: the tail elements are moved bitwise into the new buffer and
+        // `self` is truncated below, so each element keeps exactly one owner.
+        if tail_len != 0 {
+            let mut tail_rv = MoveBorrowedRustVecOfStructMut::new(self.full_type, &mut tail);
+            tail_rv.reserve_exact(tail_len);
+
+            let offset = at.checked_mul(struct_size).expect("overflow");
+            let offset = isize::try_from(offset).expect("overflow");
+            let src_ptr = self.inner.ptr.offset(offset);
+            let byte_len = tail_len.checked_mul(struct_size).expect("overflow");
+            ptr::copy_nonoverlapping(src_ptr, tail_rv.inner.ptr, byte_len);
+
+            tail_rv.set_length(tail_len);
+        }
+
+        self.set_length(at);
+
+        tail
+    }
 }
 
 impl<'mv> core::fmt::Debug for TypedMoveBorrowedRustVec<'mv> {
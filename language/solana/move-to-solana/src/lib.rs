@@ -421,6 +421,10 @@ fn compile(global_env: &GlobalEnv, options: &Options) -> anyhow::Result<()> {
             module_source_path,
         );
         mod_cx.translate();
+        // The index holds the structs of all modules translated so far.
+        if let Some(type_index) = &options.type_index {
+            mod_cx.llvm_di_builder.export_type_index(type_index)?;
+        }
 
         let mut out_path = out_path.join(&modname);
         out_path.set_extension(&options.output_file_extension);
@@ -65,6 +65,46 @@ pub struct Options {
     #[clap(short = 'g')]
     pub debug: bool,
 
+    /// Emit DWARF accelerator tables (.debug_names) for faster symbol lookup. Used with -g option.
+    #[clap(long = "debug-names")]
+    pub debug_names: bool,
+
+    /// Do not create debug information for dependency modules, only for the
+    /// package's own. Used with -g option.
+    #[clap(long = "no-debug-dependencies")]
+    pub no_debug_dependencies: bool,
+
+    /// Create only line tables, which is enough for backtraces with line numbers,
+    /// without type and variable information. Does not need the -g option.
+    #[clap(long = "line-tables-only")]
+    pub line_tables_only: bool,
+
+    /// Flags string recorded in the DWARF compile unit, e.g. the compiler invocation.
+    /// Used with -g option.
+    #[clap(long = "dwarf-flags", default_value = "")]
+    pub dwarf_flags: String,
+
+    /// Build id of the compiler, e.g. its git hash, recorded in the DWARF producer.
+    /// Used with -g option.
+    #[clap(long = "build-id", default_value = "")]
+    pub build_id: String,
+
+    /// DWARF version of the debug information, 4 or 5, instead of the backend default.
+    /// Used with -g option.
+    #[clap(long = "dwarf-version", possible_values = ["4", "5"])]
+    pub dwarf_version: Option<u32>,
+
+    /// Create debug information only for the named functions, e.g. `0x1::coin::transfer`
+    /// or `coin::transfer`; the others get no subprogram, locations or variables.
+    /// May be repeated. Used with -g option.
+    #[clap(long = "debug-function")]
+    pub debug_functions: Vec<String>,
+
+    /// Write the layouts of the structs described in the debug information to this file,
+    /// as JSON by Move name, for tools that do not read DWARF. Used with -g option.
+    #[clap(long = "type-index")]
+    pub type_index: Option<String>,
+
     /// Location of precompiled move native library.
     #[clap(long = "move-native-archive")]
     pub move_native_archive: Option<String>,
@@ -5,8 +5,13 @@
 //! Dwarf routines.
 //!
 
-use crate::stackless::{
-    extensions::FunctionEnvExt, llvm::Module, Alloca, FunctionContext, ModuleContext, TargetData,
+use crate::{
+    options::Options,
+    stackless::{
+        extensions::{FunctionEnvExt, StructEnvExt},
+        llvm::{BasicBlock, Function, Module},
+        Alloca, FunctionContext, ModuleContext, TargetData,
+    },
 };
 use anyhow::{Context, Result};
 use codespan::Location;
@@ -14,29 +19,35 @@ use itertools::enumerate;
 use llvm_sys::{
     core::*,
     debuginfo::{
-        LLVMCreateDIBuilder, LLVMDIBuilderCreateAutoVariable, LLVMDIBuilderCreateBasicType,
-        LLVMDIBuilderCreateCompileUnit, LLVMDIBuilderCreateDebugLocation,
-        LLVMDIBuilderCreateExpression, LLVMDIBuilderCreateFile, LLVMDIBuilderCreateFunction,
-        LLVMDIBuilderCreateLexicalBlock, LLVMDIBuilderCreateMemberType,
-        LLVMDIBuilderCreateNameSpace, LLVMDIBuilderCreatePointerType,
+        LLVMCreateDIBuilder, LLVMDIBuilderCreateArrayType, LLVMDIBuilderCreateAutoVariable,
+        LLVMDIBuilderCreateBasicType, LLVMDIBuilderCreateCompileUnit,
+        LLVMDIBuilderCreateDebugLocation, LLVMDIBuilderCreateExpression, LLVMDIBuilderCreateFile,
+        LLVMDIBuilderCreateFunction, LLVMDIBuilderCreateLexicalBlock,
+        LLVMDIBuilderCreateMemberType, LLVMDIBuilderCreateNameSpace,
+        LLVMDIBuilderCreatePointerType, LLVMDIBuilderCreateStaticMemberType,
         LLVMDIBuilderCreateStructType, LLVMDIBuilderCreateSubroutineType,
         LLVMDIBuilderCreateUnspecifiedType, LLVMDIBuilderCreateVectorType, LLVMDIBuilderFinalize,
         LLVMDIBuilderFinalizeSubprogram, LLVMDIBuilderGetOrCreateSubrange,
-        LLVMDIBuilderInsertDeclareAtEnd, LLVMDIFlagObjcClassComplete, LLVMDIFlagZero, LLVMDIFlags,
-        LLVMDITypeGetName, LLVMDWARFEmissionKind,
+        LLVMDIBuilderInsertDeclareAtEnd, LLVMDIFlagArtificial, LLVMDIFlagObjcClassComplete,
+        LLVMDIFlagZero, LLVMDIFlags, LLVMDITypeGetAlignInBits, LLVMDITypeGetName,
+        LLVMDITypeGetSizeInBits, LLVMDWARFEmissionKind,
         LLVMDWARFSourceLanguage::LLVMDWARFSourceLanguageRust, LLVMDWARFTypeEncoding,
-        LLVMGetMetadataKind, LLVMInstructionSetDebugLoc, LLVMMetadataKind, LLVMSetSubprogram,
+        LLVMDebugMetadataVersion, LLVMGetMetadataKind, LLVMInstructionSetDebugLoc,
+        LLVMMetadataKind, LLVMSetSubprogram,
     },
     prelude::*,
-    LLVMModule, LLVMOpaqueMetadata, LLVMValue,
+    LLVMModule, LLVMModuleFlagBehavior, LLVMOpaqueMetadata, LLVMValue,
 };
 
-use log::{debug, error, warn};
-use move_model::model::{GlobalEnv, ModuleId, StructId};
-use move_stackless_bytecode::stackless_bytecode::Bytecode;
+use log::{debug, error, log_enabled, warn, Level};
+use move_binary_format::file_format::AbilitySet;
+use move_core_types::account_address;
+use move_model::model::{FieldEnv, FunctionEnv, GlobalEnv, Loc, ModuleId, StructEnv, StructId};
+use move_stackless_bytecode::stackless_bytecode::{Bytecode, Label};
+use serde::Serialize;
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     env,
     ffi::CStr,
     fs::File,
@@ -78,10 +89,20 @@ macro_rules! dbg_meta_operand {
     };
 }
 
-// Similar to llvm::Context, lives in GlobalContext, used for keeping persistent objects
+// Similar to llvm::Context, lives in GlobalContext, used for keeping persistent objects.
+//
+// Threading contract: DIContext, like the llvm::Context next to it, is confined to the thread
+// that owns the GlobalContext, modules are compiled one after another on that thread.
+// The caches hold raw LLVM metadata pointers, which are neither Send nor Sync, so putting them
+// behind a Mutex would not make DIBuilder shareable; parallel codegen would need one DIContext
+// (and one llvm::Context) per worker. RefCell is therefore sufficient, and every borrow must be
+// released before calling back into DIBuilder, so a nested access can never panic.
 pub struct DIContext {
-    // Used for resolving types in nested structs
-    pub type_struct_db: RefCell<HashMap<StructId, LLVMMetadataRef>>,
+    // Used for resolving types in nested structs, keyed by the struct and its type arguments, so
+    // that each instantiation of a generic struct has its own members
+    pub type_struct_db: RefCell<HashMap<(StructId, Vec<mty::Type>), LLVMMetadataRef>>,
+    // Structs without a StructEnv, e.g. closure environments, keyed by a synthesized unique id
+    pub type_anonymous_struct_db: RefCell<HashMap<String, LLVMMetadataRef>>,
     pub unresolved_mty: RefCell<
         HashSet<(
             mty::Type,
@@ -89,13 +110,53 @@ pub struct DIContext {
             String, // general msg
         )>,
     >,
+    // Layouts of the structs described so far, by Move name, for DIBuilder::export_type_index
+    pub type_index: RefCell<BTreeMap<String, StructLayout>>,
 }
 
 impl DIContext {
     pub fn new() -> DIContext {
         DIContext {
             type_struct_db: RefCell::new(HashMap::new()),
+            type_anonymous_struct_db: RefCell::new(HashMap::new()),
             unresolved_mty: RefCell::new(HashSet::new()),
+            type_index: RefCell::new(BTreeMap::new()),
+        }
+    }
+}
+
+// The layout of a struct as described in DWARF, for tools that read it from JSON instead of
+// parsing DWARF. Sizes and offsets are in bytes.
+#[derive(Clone, Debug, Serialize)]
+pub struct StructLayout {
+    pub size: u64,
+    pub align: u64,
+    pub fields: Vec<FieldLayout>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FieldLayout {
+    pub name: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl StructLayout {
+    // From the (offset, size) in bits of each named member.
+    fn from_members(names: &[String], members: &[(u64, u64)], size: u64, align: u32) -> Self {
+        let fields = names
+            .iter()
+            .zip(members)
+            .map(|(name, (offset, size))| FieldLayout {
+                name: name.clone(),
+                offset: offset / 8,
+                size: (size + 7) / 8,
+            })
+            .collect();
+        StructLayout {
+            size: (size + 7) / 8,
+            align: u64::from(align) / 8,
+            fields,
         }
     }
 }
@@ -113,6 +174,9 @@ pub struct DIBuilderCore<'up> {
     producer: String,
     module_source: String,
     current_function: RefCell<*mut LLVMOpaqueMetadata>,
+    // files other than module_source that declarations come from, by absolute path
+    other_files: RefCell<HashMap<String, LLVMMetadataRef>>,
+    line_tables_only: bool,
     // basic types
     type_unspecified: LLVMMetadataRef,
     type_u8: LLVMMetadataRef,
@@ -123,6 +187,27 @@ pub struct DIBuilderCore<'up> {
     type_u256: LLVMMetadataRef,
     type_bool: LLVMMetadataRef,
     type_address: LLVMMetadataRef,
+    type_signer: LLVMMetadataRef,
+}
+
+// How much debug information a DIBuilder creates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugInfo {
+    None,
+    // Only the compile unit, subprograms and instruction locations, which is what backtraces
+    // need for line numbers. No types or variables are described.
+    LineTablesOnly,
+    Full,
+}
+
+impl DebugInfo {
+    pub fn emission_kind(self) -> LLVMDWARFEmissionKind {
+        match self {
+            DebugInfo::None => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindNone,
+            DebugInfo::LineTablesOnly => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindLineTablesOnly,
+            DebugInfo::Full => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+        }
+    }
 }
 
 pub enum UnresolvedPrintLogLevel {
@@ -169,6 +254,39 @@ impl<'up> PublicInstruction<'up> {
         }
     }
 
+    // A branch gets the location of its bytecode.
+    pub fn create_branch(&self, br_instr: *mut LLVMValue) {
+        if let Some(instruction) = &self.0 {
+            instruction.create_naked_instr(br_instr);
+        }
+    }
+
+    // The first instruction of a loop header block gets the location of the header label, so
+    // every iteration steps through the loop line rather than wherever the block's code came from.
+    // So do the back-edges, the branches to the header from the blocks placed after it: the
+    // bytecode of a back-edge is located after the loop body, often at the statement past the loop.
+    pub fn create_loop_header(&self, bb: BasicBlock) {
+        if let Some(instruction) = &self.0 {
+            let header = *bb.get_basic_block_ref();
+            let first_instr = unsafe { LLVMGetFirstInstruction(header) };
+            if !first_instr.is_null() {
+                instruction.create_naked_instr(first_instr);
+            }
+            let mut block = unsafe { LLVMGetNextBasicBlock(header) };
+            while !block.is_null() {
+                let terminator = unsafe { LLVMGetBasicBlockTerminator(block) };
+                if !terminator.is_null() {
+                    let successors = unsafe { LLVMGetNumSuccessors(terminator) };
+                    if (0..successors).any(|i| unsafe { LLVMGetSuccessor(terminator, i) } == header)
+                    {
+                        instruction.create_naked_instr(terminator);
+                    }
+                }
+                block = unsafe { LLVMGetNextBasicBlock(block) };
+            }
+        }
+    }
+
     pub fn none() -> PublicInstruction<'up> {
         let none = PublicInstruction(None);
         none
@@ -306,7 +424,7 @@ impl<'up> Instruction<'up> {
                 "LLVM modules must be the same"
             );
 
-            let current_function = *di_builder.core().current_function.borrow_mut();
+            let current_function = *di_builder.core().current_function.borrow();
 
             let debug_location = LLVMDIBuilderCreateDebugLocation(
                 module_context,
@@ -325,7 +443,7 @@ impl<'up> Instruction<'up> {
 
             LLVMInstructionSetDebugLoc(instr, debug_location);
 
-            if let Some((mty, _ty, alloca)) = more {
+            if let Some((mty, _ty, alloca)) = more.filter(|_| !di_builder.line_tables_only()) {
                 let lexical_block = LLVMDIBuilderCreateLexicalBlock(
                     builder_ref,
                     current_function,
@@ -378,6 +496,262 @@ impl<'up> Instruction<'up> {
     }
 }
 
+// DWARF base type encodings, see DWARF 5 section 7.8.
+const DW_ATE_BOOLEAN: LLVMDWARFTypeEncoding = 0x02;
+const DW_ATE_UNSIGNED: LLVMDWARFTypeEncoding = 0x07;
+
+// Encoding of a Move primitive. Without one debuggers show a 128-bit value, which LLVM lowers
+// as two i64 halves, byte-wise; with DW_ATE_unsigned a single wide basic type is printed as
+// one decimal number.
+pub fn basic_type_encoding(name: &str) -> LLVMDWARFTypeEncoding {
+    match name {
+        "bool" => DW_ATE_BOOLEAN,
+        "u8" | "u16" | "u32" | "u64" | "u128" | "u256" => DW_ATE_UNSIGNED,
+        _ => 0,
+    }
+}
+
+// The DW_AT_producer of compile units. A build id, e.g. the git hash of the compiler, tells
+// which compiler built a deployed program.
+fn producer_name(build_id: &str) -> String {
+    if build_id.is_empty() {
+        "move-mv-llvm-compiler".to_string()
+    } else {
+        format!("move-mv-llvm-compiler {build_id}")
+    }
+}
+
+// An address is emitted as an array of bytes, which debuggers show byte by byte, rather than
+// as a wide integer, which they would print as a misleading decimal number.
+fn create_address_type(builder_ref: LLVMDIBuilderRef, type_u8: LLVMMetadataRef) -> LLVMMetadataRef {
+    let addr_len = account_address::AccountAddress::LENGTH as u64;
+    unsafe {
+        let subrange = LLVMDIBuilderGetOrCreateSubrange(builder_ref, 0, addr_len as i64);
+        LLVMDIBuilderCreateArrayType(
+            builder_ref,
+            8 * addr_len,
+            8,
+            type_u8,
+            vec![subrange].as_mut_ptr(),
+            1,
+        )
+    }
+}
+
+// A signer is a struct wrapping the account address, as MoveSigner in move-native, with the
+// address as its only member at offset 0, so a debugger can expand a signer to its account.
+fn create_signer_type(
+    builder_ref: LLVMDIBuilderRef,
+    builder_file: LLVMMetadataRef,
+    type_address: LLVMMetadataRef,
+) -> LLVMMetadataRef {
+    let addr_len = account_address::AccountAddress::LENGTH as u64;
+    let name_cstr = to_cstring!("signer");
+    let member_name_cstr = to_cstring!("address");
+    unsafe {
+        let address = LLVMDIBuilderCreateMemberType(
+            builder_ref,
+            builder_file,
+            member_name_cstr.as_ptr(),
+            member_name_cstr.as_bytes().len(),
+            builder_file,
+            0,
+            8 * addr_len,
+            8,
+            0,
+            LLVMDIFlagZero,
+            type_address,
+        );
+        LLVMDIBuilderCreateStructType(
+            builder_ref,
+            builder_file,
+            name_cstr.as_ptr(),
+            name_cstr.as_bytes().len(),
+            builder_file,
+            0,
+            8 * addr_len,
+            8,
+            LLVMDIFlagZero,
+            ptr::null_mut(),
+            vec![address].as_mut_ptr(),
+            1,
+            0,
+            ptr::null_mut(),
+            ptr::null(),
+            0,
+        )
+    }
+}
+
+// Attaches a subprogram declaration, i.e. with the definition flag clear, to the LLVM function
+// declaration `ll_fn`. The linkage name is the function's symbol.
+fn create_declaration_subprogram(
+    di_builder: LLVMDIBuilderRef,
+    di_builder_file: LLVMMetadataRef,
+    ll_fn: Function,
+    name: &str,
+    lineno: u32,
+) -> LLVMMetadataRef {
+    let name_cstr = to_cstring!(name);
+    let linkage_name_cstr = to_cstring!(ll_fn.get_name());
+    unsafe {
+        let subroutine_ty = LLVMDIBuilderCreateSubroutineType(
+            di_builder,
+            di_builder_file,
+            ptr::null_mut(),
+            0,
+            LLVMDIFlagZero,
+        );
+        let function = LLVMDIBuilderCreateFunction(
+            di_builder,
+            di_builder_file,
+            name_cstr.as_ptr(),
+            name_cstr.as_bytes().len(),
+            linkage_name_cstr.as_ptr(),
+            linkage_name_cstr.as_bytes().len(),
+            di_builder_file,
+            lineno,
+            subroutine_ty,
+            0, // IsLocalToUnit: natives are external
+            0, // IsDefinition: there is no body
+            lineno,
+            LLVMDIFlagZero,
+            0, // IsOptimized
+        );
+        // DIBuilder finalizes definitions only, which would leave the retained nodes of the
+        // declaration a temporary.
+        LLVMDIBuilderFinalizeSubprogram(di_builder, function);
+        LLVMSetSubprogram(ll_fn.0, function);
+        function
+    }
+}
+
+// Entry functions are the ones the program entrypoint dispatches to, so only their subprograms
+// are external (DW_AT_external), and debuggers offer them first as breakpoint targets.
+// DISPFlagMainSubprogram (DW_AT_main_subprogram) is not settable through the LLVM C API,
+// and it names a single program entry, where a module may have many entry functions.
+pub fn subprogram_is_local_to_unit(is_entry: bool) -> LLVMBool {
+    LLVMBool::from(!is_entry)
+}
+
+// Loop headers are the labels jumped or branched to from further down the code, by the back-edge
+// that closes an iteration.
+pub fn loop_header_labels(code: &[Bytecode]) -> BTreeSet<Label> {
+    let mut seen = BTreeSet::new();
+    let mut headers = BTreeSet::new();
+    for bc in code {
+        let targets = match bc {
+            Bytecode::Label(_, label) => {
+                seen.insert(*label);
+                continue;
+            }
+            Bytecode::Jump(_, label) => vec![*label],
+            Bytecode::Branch(_, label0, label1, _) => vec![*label0, *label1],
+            _ => continue,
+        };
+        headers.extend(targets.into_iter().filter(|label| seen.contains(label)));
+    }
+    headers
+}
+
+// The scope line of a subprogram is where its body starts, which debuggers use to place a
+// breakpoint on the function. The body may not start before the declaration.
+pub fn scope_line(decl_line: u32, body_line: Option<u32>) -> u32 {
+    body_line.map_or(decl_line, |body_line| body_line.max(decl_line))
+}
+
+// Makes a name safe for a C string without losing it to the "unknown" fallback of to_cstring!.
+// NUL, other control characters and the escape character '%' itself are percent-encoded,
+// so distinct names stay distinct and the original name can be recovered.
+pub fn escape_di_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c == '%' || c.is_control() {
+            escaped.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+// Types the compiler synthesizes (closure environments, tuple returns) are marked artificial,
+// so debuggers can hide them from users who only care about source-level types.
+pub fn composite_type_flags(flags: LLVMDIFlags, artificial: bool) -> LLVMDIFlags {
+    if artificial {
+        flags | LLVMDIFlagArtificial
+    } else {
+        flags
+    }
+}
+
+// The alignment of a member: 'align_in_bits' if it is a power of two, as debuggers reject others,
+// zero in particular. Otherwise the natural alignment 'natural_in_bits' of its type, or byte
+// alignment if that is no better.
+fn checked_member_align(member_name: &str, align_in_bits: u32, natural_in_bits: u32) -> u32 {
+    if align_in_bits.is_power_of_two() {
+        return align_in_bits;
+    }
+    let corrected = if natural_in_bits.is_power_of_two() {
+        natural_in_bits
+    } else {
+        8
+    };
+    warn!(target: "struct", "{member_name}: alignment {align_in_bits} is not a power of two, using {corrected}");
+    corrected
+}
+
+// The LLVM element index and bit offset of a Move field in the lowered struct type. Lowering
+// (ModuleContext::translate_struct) emits the fields in 'get_fields' order, i.e. by their offset,
+// so a field's offset is its element index. The bit offset includes any padding the data layout
+// puts before the element, so it must be used rather than the sum of the preceding field sizes.
+fn lowered_field_position(
+    struct_type: &StructType,
+    data_layout: TargetData,
+    field: &FieldEnv,
+) -> (usize, u64) {
+    let ll_index = field.get_offset();
+    let offset_in_bits = struct_type.offset_of_element(data_layout, ll_index) as u64 * 8;
+    (ll_index, offset_in_bits)
+}
+
+// Self-check of a struct layout, given as (offset, size, alignment) in bits per member in
+// declaration order: members must not overlap, and the members, the padding between them and the
+// tail padding up to the largest member alignment must add up to the struct size.
+// Logs a warning and returns false on mismatch.
+pub fn check_struct_layout(
+    struct_name: &str,
+    members: &[(u64, u64, u32)],
+    struct_in_bits: u64,
+) -> bool {
+    let mut end_in_bits = 0;
+    let mut align_in_bits = 1;
+    for (offset, size, align) in members {
+        if *offset < end_in_bits {
+            warn!(target: "struct", "{struct_name}: member at bit {offset} overlaps the previous one ending at bit {end_in_bits}");
+            return false;
+        }
+        end_in_bits = offset + size;
+        align_in_bits = align_in_bits.max(*align as u64);
+    }
+    let expected_in_bits = (end_in_bits + align_in_bits - 1) / align_in_bits * align_in_bits;
+    if expected_in_bits != struct_in_bits {
+        warn!(target: "struct", "{struct_name}: members and padding take {expected_in_bits} bits, but the struct has {struct_in_bits}");
+        return false;
+    }
+    true
+}
+
+// Unique id of an anonymous struct: the name alone may be reused by different generated
+// environments, so the field names, types and offsets are part of the id.
+pub fn anonymous_struct_unique_id(name: &str, fields: &[(String, mty::Type, u64)]) -> String {
+    let mut unique_id = format!("anon.{name}");
+    for (fld_name, fld_mty, fld_offset) in fields {
+        unique_id.push_str(&format!(".{fld_name}:{fld_mty:?}@{fld_offset}"));
+    }
+    unique_id
+}
+
 pub fn type_get_name(x: LLVMMetadataRef) -> String {
     let mut length: ::libc::size_t = 0;
     let name_c_str = unsafe { LLVMDITypeGetName(x, &mut length) };
@@ -390,26 +764,32 @@ pub fn type_get_name(x: LLVMMetadataRef) -> String {
 }
 
 impl<'up> DIBuilderCore<'up> {
-    pub fn add_type_struct(&self, struct_id: StructId, ty: LLVMMetadataRef) {
+    pub fn add_type_struct(&self, struct_id: StructId, tys: &[mty::Type], ty: LLVMMetadataRef) {
         let name = type_get_name(ty);
         debug!(target: "struct", "set type {} for struct {:#?}", name, struct_id);
         self.g_ctx
             .di_context
             .type_struct_db
             .borrow_mut()
-            .insert(struct_id, ty);
+            .insert((struct_id, tys.to_vec()), ty);
     }
 
     pub fn get_type_struct(
         &self,
         _module_id: ModuleId, // reserved for future usage and debugging
         struct_id: StructId,
+        tys: &[mty::Type],
         struct_name: &String,
     ) -> LLVMMetadataRef {
-        let binding = self.g_ctx.di_context.type_struct_db.borrow_mut();
-        let val: Option<&*mut llvm_sys::LLVMOpaqueMetadata> = binding.get(&struct_id);
+        let val: Option<*mut llvm_sys::LLVMOpaqueMetadata> = self
+            .g_ctx
+            .di_context
+            .type_struct_db
+            .borrow()
+            .get(&(struct_id, tys.to_vec()))
+            .copied();
         let ty = match val {
-            Some(res) => *res,
+            Some(res) => res,
             None => self.type_unspecified,
         };
         let type_name = type_get_name(ty);
@@ -417,6 +797,14 @@ impl<'up> DIBuilderCore<'up> {
         ty
     }
 
+    pub fn add_type_layout(&self, name: &str, layout: StructLayout) {
+        self.g_ctx
+            .di_context
+            .type_index
+            .borrow_mut()
+            .insert(name.to_string(), layout);
+    }
+
     pub fn add_unresolved_mty(&self, mty: mty::Type, mty_name: String, msg: String) {
         debug!(target: "struct", "unresolved mty type {}: {}", mty_name, msg);
         self.g_ctx
@@ -427,8 +815,8 @@ impl<'up> DIBuilderCore<'up> {
     }
 
     pub fn print_log_unresolved_types(&self, lev: UnresolvedPrintLogLevel) {
-        let binding = self.g_ctx.di_context.unresolved_mty.borrow_mut();
-        for el in binding.clone().into_iter() {
+        let unresolved = self.g_ctx.di_context.unresolved_mty.borrow().clone();
+        for el in unresolved.into_iter() {
             let (mty, name, msg) = el;
             match lev {
                 UnresolvedPrintLogLevel::Debug => {
@@ -446,7 +834,7 @@ impl<'up> DIBuilderCore<'up> {
 
     // reserved for future usage
     fn _has_unresolved_types(&self) -> bool {
-        return self.g_ctx.di_context.unresolved_mty.borrow_mut().capacity() > 0;
+        return !self.g_ctx.di_context.unresolved_mty.borrow().is_empty();
     }
 }
 
@@ -472,13 +860,40 @@ fn relative_to_absolute(relative_path: &str) -> std::io::Result<String> {
 }
 
 impl<'up> DIBuilder<'up> {
+    // The debug info level is decided per module, the other DWARF settings come from 'options'.
     pub fn new(
         g_ctx: &'up GlobalContext,
         module: &Module,
         source: &str,
-        debug: bool,
+        debug_info: DebugInfo,
+        options: &Options,
+    ) -> DIBuilder<'up> {
+        Self::new_in_module(g_ctx, module, source, debug_info, options, false)
+    }
+
+    // Attaches to a module that holds the code and debug info of other Move modules too, as when a
+    // whole package is compiled into one LLVM module. Each Move module still gets its own compile
+    // unit, but the LLVM module keeps its source file name and module flags. All compile units
+    // live in the one LLVM context, so references to structs of other Move modules resolve.
+    pub fn new_shared(
+        g_ctx: &'up GlobalContext,
+        module: &Module,
+        source: &str,
+        debug_info: DebugInfo,
+        options: &Options,
+    ) -> DIBuilder<'up> {
+        Self::new_in_module(g_ctx, module, source, debug_info, options, true)
+    }
+
+    fn new_in_module(
+        g_ctx: &'up GlobalContext,
+        module: &Module,
+        source: &str,
+        debug_info: DebugInfo,
+        options: &Options,
+        shared: bool,
     ) -> DIBuilder<'up> {
-        if debug {
+        if debug_info != DebugInfo::None {
             let llmod = module.0;
             let module_ref_name = module.get_module_id();
 
@@ -502,8 +917,10 @@ impl<'up> DIBuilder<'up> {
             debug!(target: "dwarf", "Created dbg module {:#?}", module_di_name);
 
             let source = relative_to_absolute(source).expect("Must be the legal path");
-            let cstr = to_cstring!(source.as_str());
-            unsafe { LLVMSetSourceFileName(module_di, cstr.as_ptr(), cstr.as_bytes().len()) };
+            if !shared {
+                let cstr = to_cstring!(source.as_str());
+                unsafe { LLVMSetSourceFileName(module_di, cstr.as_ptr(), cstr.as_bytes().len()) };
+            }
 
             // check the source name
             let mut src_len: ::libc::size_t = 0;
@@ -542,9 +959,9 @@ impl<'up> DIBuilder<'up> {
                 builder_ref: LLVMDIBuilderRef,
                 name: &str,
                 size_in_bits: u64,
-                encoding: LLVMDWARFTypeEncoding,
                 flags: LLVMDIFlags,
             ) -> LLVMMetadataRef {
+                let encoding = basic_type_encoding(name);
                 let name_cstr = to_cstring!(name);
                 let (name_ptr, name_len) = (name_cstr.as_ptr(), name_cstr.as_bytes().len());
                 unsafe {
@@ -566,9 +983,34 @@ impl<'up> DIBuilder<'up> {
             }
 
             // create compile unit
-            let producer = "move-mv-llvm-compiler".to_string();
-            let compiled_unit =
-                Self::create_compiled_unit(builder_ref, builder_file, producer.clone());
+            let producer = producer_name(&options.build_id);
+            let compiled_unit = Self::create_compiled_unit(
+                builder_ref,
+                builder_file,
+                producer.clone(),
+                &options.dwarf_flags,
+                debug_info.emission_kind(),
+            );
+
+            // A pinned version takes precedence over the one requested for accelerator tables.
+            if let Some(version) = options.dwarf_version {
+                Self::add_module_flags(
+                    module_di,
+                    &[
+                        ("Dwarf Version", version),
+                        ("Debug Info Version", unsafe { LLVMDebugMetadataVersion() }),
+                    ],
+                );
+            }
+            if options.debug_names {
+                if let Some(version) = options.dwarf_version.filter(|version| *version < 5) {
+                    warn!(target: "dwarf", "DWARF version {version} has no .debug_names");
+                }
+                Self::add_accelerator_table_flags(module_di);
+            }
+
+            let type_u8 = create_type(builder_ref, "u8", 8, LLVMDIFlagZero);
+            let type_address = create_address_type(builder_ref, type_u8);
 
             // store all control fields for future usage
             let builder_core = DIBuilderCore {
@@ -580,15 +1022,18 @@ impl<'up> DIBuilder<'up> {
                 producer: producer.clone(),
                 module_source: source.to_string(),
                 current_function: RefCell::new(std::ptr::null_mut::<LLVMOpaqueMetadata>()),
+                other_files: RefCell::new(HashMap::new()),
+                line_tables_only: debug_info == DebugInfo::LineTablesOnly,
                 type_unspecified: create_unspecified_type(builder_ref),
-                type_u8: create_type(builder_ref, "u8", 8, 0, LLVMDIFlagZero),
-                type_u16: create_type(builder_ref, "u16", 16, 0, LLVMDIFlagZero),
-                type_u32: create_type(builder_ref, "u32", 32, 0, LLVMDIFlagZero),
-                type_u64: create_type(builder_ref, "u64", 64, 0, LLVMDIFlagZero),
-                type_u128: create_type(builder_ref, "u128", 128, 0, LLVMDIFlagZero),
-                type_u256: create_type(builder_ref, "u256", 256, 0, LLVMDIFlagZero),
-                type_bool: create_type(builder_ref, "bool", 8, 0, LLVMDIFlagZero),
-                type_address: create_type(builder_ref, "address", 256, 0, LLVMDIFlagZero),
+                type_u8,
+                type_u16: create_type(builder_ref, "u16", 16, LLVMDIFlagZero),
+                type_u32: create_type(builder_ref, "u32", 32, LLVMDIFlagZero),
+                type_u64: create_type(builder_ref, "u64", 64, LLVMDIFlagZero),
+                type_u128: create_type(builder_ref, "u128", 128, LLVMDIFlagZero),
+                type_u256: create_type(builder_ref, "u256", 256, LLVMDIFlagZero),
+                type_bool: create_type(builder_ref, "bool", 8, LLVMDIFlagZero),
+                type_address,
+                type_signer: create_signer_type(builder_ref, builder_file, type_address),
             };
             let module_di_info = print_module_to_str(&module_di);
             debug!(target: "dwarf", "DIBuilder bof DI starting at next line and until line starting with !!!\n{module_di_info}\n!!!\n");
@@ -601,6 +1046,49 @@ impl<'up> DIBuilder<'up> {
         }
     }
 
+    // Requests accelerator tables (.debug_names) from the backend, which speed up symbol lookup
+    // in gdb/lldb. LLVM emits .debug_names by default for DWARF 5, so it suffices to ask for that
+    // version. Without "Debug Info Version" the debug info would be dropped by llc.
+    fn add_accelerator_table_flags(module_di: LLVMModuleRef) {
+        Self::add_module_flags(
+            module_di,
+            &[
+                ("Dwarf Version", 5),
+                ("Debug Info Version", unsafe { LLVMDebugMetadataVersion() }),
+            ],
+        );
+    }
+
+    // Flags already set, by a pinned DWARF version or for another compile unit of the module, are
+    // kept, since the verifier rejects duplicates.
+    fn add_module_flags(module_di: LLVMModuleRef, flags: &[(&str, u32)]) {
+        let module_ctx = unsafe { LLVMGetModuleContext(module_di) };
+        let i32_type = unsafe { LLVMInt32TypeInContext(module_ctx) };
+        for (key, value) in flags.iter().copied() {
+            let key_cstr = to_cstring!(key);
+            let (key_ptr, key_len) = (key_cstr.as_ptr(), key_cstr.as_bytes().len());
+            if !unsafe { LLVMGetModuleFlag(module_di, key_ptr, key_len) }.is_null() {
+                continue;
+            }
+            unsafe {
+                let value_md = LLVMValueAsMetadata(LLVMConstInt(i32_type, value as u64, 0));
+                LLVMAddModuleFlag(
+                    module_di,
+                    LLVMModuleFlagBehavior::LLVMModuleFlagBehaviorWarning,
+                    key_ptr,
+                    key_len,
+                    value_md,
+                );
+            }
+            debug!(target: "dwarf", "Added module flag {:#?} = {value}", key);
+        }
+    }
+
+    // Types and variables are described only with full debug info.
+    pub fn line_tables_only(&self) -> bool {
+        self.0.as_ref().map_or(false, |x| x.line_tables_only)
+    }
+
     pub fn global_ctx(&self) -> Option<&GlobalContext> {
         self.0.as_ref().map(|x| x.g_ctx)
     }
@@ -617,6 +1105,42 @@ impl<'up> DIBuilder<'up> {
         self.0.as_ref().map(|x| x.builder_file)
     }
 
+    // The file metadata for 'filename', as the model names it. The module source has the
+    // builder file; others, e.g. of another module in a multi-file package, are created once.
+    pub fn get_or_create_file(&self, filename: &str) -> Option<LLVMMetadataRef> {
+        let core = self.0.as_ref()?;
+        let path = if std::path::Path::new(filename).exists() {
+            relative_to_absolute(filename).unwrap_or_else(|_| filename.to_string())
+        } else {
+            filename.to_string()
+        };
+        if path == core.module_source {
+            return Some(core.builder_file);
+        }
+        let mut other_files = core.other_files.borrow_mut();
+        let file = other_files.entry(path.clone()).or_insert_with(|| {
+            let path = std::path::Path::new(&path);
+            let directory = path.parent().and_then(|p| p.to_str()).unwrap_or("");
+            let file = path
+                .file_name()
+                .and_then(|f| f.to_str())
+                .unwrap_or("unknown");
+            let dir_cstr = to_cstring!(directory);
+            let file_cstr = to_cstring!(file);
+            debug!(target: "dwarf", "Created file {:#?} in {:#?}", file, directory);
+            unsafe {
+                LLVMDIBuilderCreateFile(
+                    core.builder_ref,
+                    file_cstr.as_ptr(),
+                    file_cstr.as_bytes().len(),
+                    dir_cstr.as_ptr(),
+                    dir_cstr.as_bytes().len(),
+                )
+            }
+        });
+        Some(*file)
+    }
+
     pub fn compiled_unit(&self) -> Option<LLVMMetadataRef> {
         self.0.as_ref().map(|x| x.compiled_unit)
     }
@@ -649,13 +1173,180 @@ impl<'up> DIBuilder<'up> {
             mty::Type::Primitive(mty::PrimitiveType::U128) => core.type_u128,
             mty::Type::Primitive(mty::PrimitiveType::U256) => core.type_u256,
             mty::Type::Primitive(mty::PrimitiveType::Address) => core.type_address,
-            mty::Type::Struct(mod_id, struct_id, _v) => {
-                self.core().get_type_struct(mod_id, struct_id, name)
+            mty::Type::Primitive(mty::PrimitiveType::Signer) => core.type_signer,
+            mty::Type::Struct(mod_id, struct_id, tys) => {
+                self.core().get_type_struct(mod_id, struct_id, &tys, name)
+            }
+            mty::Type::Vector(elt_mty) => self.create_vector_header(&elt_mty, name),
+            mty::Type::Reference(is_mut, elt_mty) => {
+                self.create_reference_type(is_mut, &elt_mty, name)
             }
             _ => core.type_unspecified,
         }
     }
 
+    // Describes a vector by its runtime header { ptr, cap, len }, the layout of MoveUntypedVector
+    // in move-native. Naming 'cap' and 'len' lets a debugger show them without pointer math.
+    fn create_vector_header(&self, elt_mty: &mty::Type, name: &String) -> LLVMMetadataRef {
+        let core = self.core();
+        let di_builder = core.builder_ref;
+        let di_builder_file = core.builder_file;
+        let llcx = &core.g_ctx.llvm_cx;
+        let data_layout = unsafe { TargetData::of_module(core.module_di) };
+
+        let header_type = llcx.get_anonymous_struct_type(&[
+            llcx.ptr_type(),
+            llcx.int_type(64),
+            llcx.int_type(64),
+        ]);
+        let header_struct_type = header_type.as_struct_type();
+
+        let elt_type = self.get_type(elt_mty.clone(), name);
+        let ty_display_ctx = core.g_ctx.env.get_type_display_ctx();
+        let header_name = format!("vector<{}>", elt_mty.display(&ty_display_ctx));
+        let header_name_cstr = to_cstring!(header_name.as_str());
+        let (header_nm_ptr, header_nm_len) =
+            (header_name_cstr.as_ptr(), header_name_cstr.as_bytes().len());
+
+        let ptr_llvm_type = header_struct_type.struct_get_type_at_index(0);
+        let ptr_in_bits = ptr_llvm_type.size_of_type_in_bits(data_layout);
+        let ptr_align_in_bits = ptr_llvm_type.abi_alignment_of_type(data_layout) * 8;
+        let ptr_name_cstr = to_cstring!(format!("{}__ptr", header_name));
+        let elt_ptr_type = unsafe {
+            LLVMDIBuilderCreatePointerType(
+                di_builder,
+                elt_type,
+                ptr_in_bits,
+                ptr_align_in_bits,
+                0,
+                ptr_name_cstr.as_ptr(),
+                ptr_name_cstr.as_bytes().len(),
+            )
+        };
+
+        let members = [
+            ("ptr", elt_ptr_type),
+            ("cap", core.type_u64),
+            ("len", core.type_u64),
+        ];
+        let mut fields: Vec<LLVMMetadataRef> = enumerate(members)
+            .map(|(idx, (fld_name, fld_type))| {
+                let llvm_ty = header_struct_type.struct_get_type_at_index(idx);
+                let sz_in_bits = llvm_ty.size_of_type_in_bits(data_layout);
+                let align_in_bits = llvm_ty.abi_alignment_of_type(data_layout) * 8;
+                let offset_in_bits = header_struct_type.offset_of_element(data_layout, idx) * 8;
+                let fld_name_cstr = to_cstring!(fld_name);
+                unsafe {
+                    LLVMDIBuilderCreateMemberType(
+                        di_builder,
+                        di_builder_file,
+                        fld_name_cstr.as_ptr(),
+                        fld_name_cstr.as_bytes().len(),
+                        di_builder_file,
+                        0,
+                        sz_in_bits,
+                        align_in_bits,
+                        offset_in_bits as u64,
+                        LLVMDIFlagZero,
+                        fld_type,
+                    )
+                }
+            })
+            .collect();
+
+        let header_in_bits = header_type.size_of_type_in_bits(data_layout);
+        let header_align_in_bits = header_type.abi_alignment_of_type(data_layout) * 8;
+        let header_meta = unsafe {
+            LLVMDIBuilderCreateStructType(
+                di_builder,
+                di_builder_file,
+                header_nm_ptr,
+                header_nm_len,
+                di_builder_file,
+                0,
+                header_in_bits,
+                header_align_in_bits,
+                LLVMDIFlagZero,
+                ptr::null_mut(),
+                fields.as_mut_ptr(),
+                fields.len() as u32,
+                0,
+                ptr::null_mut(),
+                ptr::null(),
+                0,
+            )
+        };
+        debug!(target: "dwarf", "create_vector_header {header_name}: {} members", fields.len());
+        header_meta
+    }
+
+    // A reference lowers to a plain pointer, so its size is the target's pointer size taken from
+    // the data layout: 32 bits on 32-bit BPF, 64 bits on most hosts.
+    fn create_reference_type(
+        &self,
+        is_mut: bool,
+        elt_mty: &mty::Type,
+        name: &String,
+    ) -> LLVMMetadataRef {
+        let core = self.core();
+        let data_layout = unsafe { TargetData::of_module(core.module_di) };
+        let ptr_in_bits = data_layout.pointer_size_in_bits();
+        let ptr_align_in_bits = self.pointer_align_in_bits(data_layout);
+
+        let elt_type = self.get_type(elt_mty.clone(), name);
+        let ty_display_ctx = core.g_ctx.env.get_type_display_ctx();
+        let ref_name = format!(
+            "&{}{}",
+            if is_mut { "mut " } else { "" },
+            elt_mty.display(&ty_display_ctx)
+        );
+        let ref_name_cstr = to_cstring!(ref_name.as_str());
+        let ref_type = unsafe {
+            LLVMDIBuilderCreatePointerType(
+                core.builder_ref,
+                elt_type,
+                ptr_in_bits,
+                ptr_align_in_bits,
+                0,
+                ref_name_cstr.as_ptr(),
+                ref_name_cstr.as_bytes().len(),
+            )
+        };
+        debug!(target: "reference", "create_reference_type {ref_name}: {ptr_in_bits} bits");
+        ref_type
+    }
+
+    // The DWARF type of a function parameter. A struct taken by value or by reference, as entry
+    // functions take resources, is described first if it has no type yet, so that the parameter
+    // is the composite, or a pointer to it a debugger can dereference, and not the unspecified type.
+    fn create_parameter_type(&self, mty: &mty::Type, name: &String) -> LLVMMetadataRef {
+        let core = self.core();
+        let pointee = match mty {
+            mty::Type::Reference(_, pointee) => pointee.as_ref(),
+            _ => mty,
+        };
+        if let mty::Type::Struct(mod_id, struct_id, tys) = pointee {
+            let is_described = core
+                .g_ctx
+                .di_context
+                .type_struct_db
+                .borrow()
+                .contains_key(&(*struct_id, tys.clone()));
+            if !is_described && !pointee.is_open() {
+                let struct_env = core.g_ctx.env.get_module(*mod_id).into_struct(*struct_id);
+                let struct_llvm_name = struct_env.ll_struct_name_from_raw_name(tys);
+                self.create_struct_from_model(&struct_env, tys, &struct_llvm_name);
+            }
+        }
+        self.get_type(mty.clone(), name)
+    }
+
+    // The ABI alignment of a pointer in the data layout, which may be less than its size.
+    fn pointer_align_in_bits(&self, data_layout: TargetData) -> u32 {
+        let ptr_type = self.core().g_ctx.llvm_cx.ptr_type();
+        ptr_type.abi_alignment_of_type(data_layout) * 8
+    }
+
     pub fn print_module_to_file(&self, file_path: String) {
         if let Some(x) = &self.0 {
             let mut err_string = ptr::null_mut();
@@ -797,7 +1488,7 @@ impl<'up> DIBuilder<'up> {
         llmod: &Module,
         _parent: Option<LLVMMetadataRef>,
     ) {
-        if mvec.is_empty() {
+        if mvec.is_empty() || self.line_tables_only() {
             return;
         };
 
@@ -862,11 +1553,36 @@ impl<'up> DIBuilder<'up> {
         }
     }
 
+    // Native functions have no Move body to describe, but a declaration still names calls into
+    // them in backtraces instead of showing the bare native symbol.
+    pub fn create_function_declaration(&self, fn_env: &FunctionEnv, ll_fn: Function) {
+        if self.0.is_some() {
+            let di_builder = self.builder_ref().unwrap();
+            let di_builder_file = self.builder_file().unwrap();
+            let (_file, location) = fn_env
+                .module_env
+                .env
+                .get_file_and_location(&fn_env.get_loc())
+                .unwrap_or(("unknown".to_string(), Location::new(0, 0)));
+            create_declaration_subprogram(
+                di_builder,
+                di_builder_file,
+                ll_fn,
+                &fn_env.di_display_name(&[]),
+                location.line.0,
+            );
+        }
+    }
+
     pub fn create_function(
         &self,
         func_ctx: &FunctionContext<'_, '_>,
+        body_loc: Option<&Loc>,
         _parent: Option<LLVMMetadataRef>, // reserved for future usage
     ) -> Option<*mut LLVMOpaqueMetadata> {
+        if !self.describes_function(func_ctx) {
+            return None;
+        }
         if let Some(di_builder_core) = &self.0 {
             let di_builder: *mut llvm_sys::LLVMOpaqueDIBuilder = self.builder_ref().unwrap();
             let di_builder_file = self.builder_file().unwrap();
@@ -880,6 +1596,10 @@ impl<'up> DIBuilder<'up> {
                 .unwrap_or(("unknown".to_string(), Location::new(0, 0)));
             let lineno = location.line.0;
             let column = location.column.0;
+            let body_lineno = body_loc
+                .and_then(|body_loc| fn_env.module_env.env.get_file_and_location(body_loc))
+                .map(|(_file, body_location)| body_location.line.0);
+            let scope_lineno = scope_line(lineno, body_lineno);
 
             let module_cx = &func_ctx.module_cx;
             let ll_ctx = module_cx.llvm_cx.0;
@@ -931,6 +1651,20 @@ impl<'up> DIBuilder<'up> {
             let name_cstr = to_cstring!(fn_name.clone());
             let (fn_nm_ptr, fn_nm_len) = (name_cstr.as_ptr(), name_cstr.as_bytes().len());
 
+            // Backtraces show the readable Move name with its type arguments,
+            // symbols are still resolved through the mangled linkage name.
+            let display_name = fn_env.di_display_name(func_ctx.type_params);
+            let display_name_cstr = to_cstring!(display_name.clone());
+            let (display_nm_ptr, display_nm_len) = (
+                display_name_cstr.as_ptr(),
+                display_name_cstr.as_bytes().len(),
+            );
+            let linkage_name_cstr = to_cstring!(ll_fn.get_name());
+            let (linkage_nm_ptr, linkage_nm_len) = (
+                linkage_name_cstr.as_ptr(),
+                linkage_name_cstr.as_bytes().len(),
+            );
+
             // NOTE. Explanation of the numbers used below.
             // Despite some existing freedom in choosing parameter values ​​(for example, the 'scope' in different places
             // may be either file, or function, or logical scope, or basic block) the final result may vary and even be illegal,
@@ -944,13 +1678,24 @@ impl<'up> DIBuilder<'up> {
                 LLVMDIBuilderCreateNameSpace(di_builder, di_builder_file, fn_nm_ptr, fn_nm_len, 0)
             };
 
-            // -4.
-            let mut ty_params: Vec<LLVMMetadataRef> = enumerate(&parameters)
+            // -4. Parameter types are left out of line tables.
+            let described_parameters = if di_builder_core.line_tables_only {
+                &parameters[..0]
+            } else {
+                &parameters[..]
+            };
+            let mut ty_params: Vec<LLVMMetadataRef> = enumerate(described_parameters)
                 .scan(0, |_state, (_idx, (ll_param, local))| {
                     let llval = ll_param.0;
                     let param_name = module_cx.llvm_di_builder.get_name(llval);
                     let mty = local.mty();
-                    let fn_param = self.get_type(mty.clone(), &param_name);
+                    match mty {
+                        mty::Type::Reference(_, pointee) => {
+                            self.create_nested_struct(func_ctx, pointee)
+                        }
+                        _ => self.create_nested_struct(func_ctx, mty),
+                    }
+                    let fn_param = self.create_parameter_type(mty, &param_name);
 
                     let fn_param_value = unsafe { LLVMMetadataAsValue(module_ctx, fn_param) };
                     let fn_param_info = print_to_str(fn_param_value);
@@ -958,6 +1703,17 @@ impl<'up> DIBuilder<'up> {
                     Some(fn_param)
                 })
                 .collect();
+
+            // A returned tuple takes the return type slot, ahead of the parameters.
+            let return_types: Vec<mty::Type> = fn_env
+                .get_return_types()
+                .iter()
+                .map(|ty| ty.instantiate(func_ctx.type_params))
+                .collect();
+            if return_types.len() > 1 && !di_builder_core.line_tables_only {
+                let tuple = self.create_tuple_return_type(&display_name, &return_types);
+                ty_params.insert(0, tuple);
+            }
             let ty_params_mut: *mut LLVMMetadataRef = ty_params.as_mut_ptr();
 
             // -3.
@@ -976,22 +1732,21 @@ impl<'up> DIBuilder<'up> {
                 LLVMDIBuilderCreateFunction(
                     di_builder,
                     di_builder_file,
-                    fn_nm_ptr,
-                    fn_nm_len,
-                    fn_nm_ptr,
-                    fn_nm_len,
+                    display_nm_ptr,
+                    display_nm_len,
+                    linkage_nm_ptr,
+                    linkage_nm_len,
                     di_builder_file,
                     lineno,
                     subroutine_ty,
-                    1, // IsLocalToUnit: TODO: may need change
+                    subprogram_is_local_to_unit(fn_env.is_entry()),
                     1,
-                    0, // ScopeLine: TODO: unclear param
+                    scope_lineno,
                     0, // Flags: TODO: may need change
                     0, // IsOptimized: TODO: may need change
                 )
             };
-            let mut current_function = di_builder_core.current_function.borrow_mut();
-            *current_function = function;
+            *di_builder_core.current_function.borrow_mut() = function;
             unsafe {
                 dbg_meta_operand!(ll_mod, ll_ctx, function, "functions", "create_function");
             };
@@ -1053,6 +1808,41 @@ impl<'up> DIBuilder<'up> {
         None
     }
 
+    // Functions left out by --debug-function get no subprogram, and so none of their instructions
+    // may have a location, while the module keeps its compile unit and types.
+    fn describes_function(&self, func_ctx: &FunctionContext<'_, '_>) -> bool {
+        let described = func_ctx
+            .env
+            .should_emit_debug_info(func_ctx.module_cx.options);
+        if !described {
+            debug!(target: "functions", "no debug info for filtered out function {}", func_ctx.env.get_full_name_str());
+        }
+        described
+    }
+
+    // Creates a location for code inlined from subprogram 'scope' into the call site 'inlined_at'.
+    // The 'inlined_at' location may itself be inlined, the chain of such locations is what
+    // lets the debugger reconstruct frames for every inlined Move call.
+    // The codegen is responsible to supply the inline context.
+    pub fn inlined_location(
+        &self,
+        line: libc::c_uint,
+        column: libc::c_uint,
+        scope: LLVMMetadataRef,
+        inlined_at: LLVMMetadataRef,
+    ) -> Option<LLVMMetadataRef> {
+        if let Some(di_builder_core) = &self.0 {
+            assert!(!scope.is_null(), "Inlined location must have a scope");
+            let module_ctx = unsafe { LLVMGetModuleContext(di_builder_core.module_di) };
+            let location = unsafe {
+                LLVMDIBuilderCreateDebugLocation(module_ctx, line, column, scope, inlined_at)
+            };
+            debug!(target: "dwarf", "inlined_location {line}:{column} scope {:#?} inlined at {:#?}", scope, inlined_at);
+            return Some(location);
+        }
+        None
+    }
+
     pub fn finalize_function(
         &self,
         func_ctx: &FunctionContext<'_, '_>,
@@ -1088,12 +1878,14 @@ impl<'up> DIBuilder<'up> {
         di_builder: *mut llvm_sys::LLVMOpaqueDIBuilder,
         builder_file: *mut LLVMOpaqueMetadata,
         producer: String,
+        flags: &str,
+        emission_kind: LLVMDWARFEmissionKind,
     ) -> *mut LLVMOpaqueMetadata {
         let builder_ref = di_builder;
         let cstr = to_cstring!(producer);
         let (producer_ptr, producer_len) = (cstr.as_ptr(), cstr.as_bytes().len());
 
-        let flags = String::new();
+        // How the program was compiled, e.g. the compiler invocation, kept for reproducibility.
         let cstr = to_cstring!(flags);
         let (flags_ptr, flags_len) = (cstr.as_ptr(), cstr.as_bytes().len());
 
@@ -1118,7 +1910,7 @@ impl<'up> DIBuilder<'up> {
                 0,                /* runtime_version */
                 std::ptr::null(), /* *const i8 */
                 0,                /* usize */
-                LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                emission_kind,
                 0,         /* u32 */
                 0,         /* i32 */
                 0,         /* i32 */
@@ -1155,20 +1947,92 @@ impl<'up> DIBuilder<'up> {
         }
     }
 
+    // Creates the DWARF type of a struct used as a field or parameter, unless it exists already,
+    // so that the member references the composite type, which a debugger can expand, and not the unspecified
+    // type. The struct gets its type when first packed, which need not come before the outer one.
+    fn create_nested_struct(&self, func_ctx: &FunctionContext<'_, '_>, mv_ty: &mty::Type) {
+        if let mty::Type::Struct(mod_id, struct_id, tys) = mv_ty {
+            let core = self.core();
+            if core
+                .g_ctx
+                .di_context
+                .type_struct_db
+                .borrow()
+                .contains_key(&(*struct_id, tys.clone()))
+            {
+                return;
+            }
+            let struct_env = core.g_ctx.env.get_module(*mod_id).into_struct(*struct_id);
+            let struct_llvm_name = struct_env.ll_struct_name_from_raw_name(tys);
+            // The field types of a generic struct are unknown until its type arguments are.
+            // Instantiated structs without an LLVM type are described from the model.
+            if mv_ty.is_open() {
+                debug!(target: "struct", "open type arguments for nested struct {struct_llvm_name}");
+                return;
+            }
+            self.create_struct(
+                func_ctx,
+                mod_id,
+                struct_id,
+                tys,
+                &struct_llvm_name,
+                None,
+                false,
+            );
+        }
+    }
+
+    // An artificial static member '__abilities' holding the struct's abilities as a u8, with the
+    // bits of their bytecode encoding: copy 0x1, drop 0x2, store 0x4, key 0x8. It takes no space
+    // in the struct, and lets debuggers show e.g. "has copy, drop" (0x3).
+    pub fn create_abilities_member(
+        &self,
+        scope: LLVMMetadataRef,
+        file: LLVMMetadataRef,
+        line: u32,
+        abilities: AbilitySet,
+    ) -> LLVMMetadataRef {
+        let core = self.core();
+        let name_cstr = to_cstring!("__abilities");
+        unsafe {
+            let ll_ctx = core.g_ctx.llvm_cx.0;
+            let value = LLVMConstInt(LLVMInt8TypeInContext(ll_ctx), abilities.into_u8() as u64, 0);
+            LLVMDIBuilderCreateStaticMemberType(
+                core.builder_ref,
+                scope,
+                name_cstr.as_ptr(),
+                name_cstr.as_bytes().len(),
+                file,
+                line,
+                core.type_u8,
+                LLVMDIFlagArtificial,
+                value,
+                0,
+            )
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_struct(
         &self,
         func_ctx: &FunctionContext<'_, '_>,
-        mod_id: &ModuleId, // reserved for future usage and debugging
+        mod_id: &ModuleId,
         struct_id: &StructId,
+        tys: &[mty::Type],
         struct_llvm_name: &str,
         parent: Option<LLVMMetadataRef>,
+        artificial: bool,
     ) {
+        if self.line_tables_only() {
+            return;
+        }
         if let Some(_di_builder_core) = &self.0 {
             let di_builder = self.builder_ref().unwrap();
             let di_builder_file = self.builder_file().unwrap();
             let mod_cx = &func_ctx.module_cx;
-            let mod_env = &mod_cx.env;
-            let struct_env = mod_env.clone().into_struct(*struct_id);
+            // The struct may be declared in another module when it is the type of a field.
+            let struct_env = mod_cx.env.env.get_module(*mod_id).into_struct(*struct_id);
+            let mod_env = &struct_env.module_env;
             let module = mod_cx.llvm_module;
             let data_layout = module.get_module_data_layout();
 
@@ -1176,11 +2040,15 @@ impl<'up> DIBuilder<'up> {
             debug!(target: "struct", "Creating dwarf info for struct move_name {}, llvm_name {} mod_id {:#?} struct_id {:#?}",
                 name, struct_llvm_name, mod_id, struct_id);
 
-            // FIXME: not clear whether to use 'name' or 'struct_llvm_name' for DWARF
+            // The LLVM name identifies the struct in the module, debuggers show the Move name with
+            // the module's address, e.g. 0x1::coin::Coin, which the name carries as a whole.
             let struct_name = struct_llvm_name;
             let name_cstr = to_cstring!(struct_name);
             let (struct_nm_ptr, struct_nm_len) = (name_cstr.as_ptr(), name_cstr.as_bytes().len());
-            let unique_id = std::ffi::CString::new("unique_id").expect("CString conversion failed");
+            let display_name = struct_env.di_display_name(tys);
+            let display_name_cstr = to_cstring!(display_name.as_str());
+            // The identifier records the type arguments, and which are of phantom parameters.
+            let unique_id = to_cstring!(struct_env.di_instantiation_id(tys));
 
             let name_space = unsafe {
                 LLVMDIBuilderCreateNameSpace(
@@ -1192,32 +2060,57 @@ impl<'up> DIBuilder<'up> {
                 )
             };
             let loc = struct_env.get_loc();
-            let (filename, location) = struct_env
-                .module_env
-                .env
-                .get_file_and_location(&loc)
-                .unwrap_or(("unknown".to_string(), Location::new(0, 0)));
+            let file_and_location = struct_env.module_env.env.get_file_and_location(&loc);
+            // The struct may come from another file of the package than the module being built.
+            let struct_file = file_and_location
+                .as_ref()
+                .map_or(di_builder_file, |(filename, _)| {
+                    self.get_or_create_file(filename).unwrap()
+                });
+            let (filename, location) =
+                file_and_location.unwrap_or(("unknown".to_string(), Location::new(0, 0)));
             debug!(target: "struct", "{struct_name} {}:{}", filename, location.line.0);
 
-            let struct_type = self
+            let struct_type = match self
                 .global_ctx()
                 .unwrap()
                 .llvm_cx
                 .named_struct_type(struct_name)
-                .expect("no struct type");
+            {
+                Some(struct_type) => struct_type,
+                None => {
+                    warn!(target: "struct", "{struct_name} has no llvm type, computing its layout from the model");
+                    self.create_struct_from_model(&struct_env, tys, struct_name);
+                    return;
+                }
+            };
 
             let struct_info = struct_type.dump_to_string();
             debug!(target: "struct", "{struct_name} {}", struct_info);
 
-            let struct_type_in_bits = struct_type.as_any_type().size_of_type_in_bits(data_layout);
-            let struct_prefered_alignment = struct_type
-                .as_any_type()
-                .preferred_alignment_of_type(data_layout);
+            // An opaque struct (body not set yet) has no layout: LLVM reports size 0, and its
+            // field types cannot be queried. Its layout is then computed from the DWARF types
+            // of the Move fields instead.
+            let is_opaque = struct_type.is_opaque();
+            if is_opaque {
+                warn!(target: "struct", "{struct_name} is opaque, computing its size from the fields");
+            }
+            let (mut struct_type_in_bits, mut struct_prefered_alignment) = if is_opaque {
+                (0, 0)
+            } else {
+                (
+                    struct_type.as_any_type().size_of_type_in_bits(data_layout),
+                    struct_type
+                        .as_any_type()
+                        .preferred_alignment_of_type(data_layout)
+                        * 8,
+                )
+            };
 
             let struct_ptr_type = struct_type.ptr_type();
             let struct_ptr_type_in_bits = struct_ptr_type.size_of_type_in_bits(data_layout);
             // Note: ignore preferred_alignment_of_type() for ptr
-            let struct_ptr_prefered_alignment = struct_ptr_type_in_bits.try_into().unwrap();
+            let struct_ptr_prefered_alignment = self.pointer_align_in_bits(data_layout);
 
             debug!(target: "struct",
                 "{struct_name} sz {struct_type_in_bits} align {struct_prefered_alignment} ptr {struct_ptr_type_in_bits} align {struct_ptr_prefered_alignment}");
@@ -1225,35 +2118,72 @@ impl<'up> DIBuilder<'up> {
             Self::struct_fields_info(&struct_type, data_layout, "from struct_type");
 
             let struct_fields = struct_env.get_fields();
+            let mut member_layout: Vec<(u64, u64, u32)> = vec![];
+            let mut member_names: Vec<String> = vec![];
             let mut fields: Vec<LLVMMetadataRef> = enumerate(struct_fields).scan(0, |current_offset, (idx, field)| {
                 let symbol = field.get_name();
-                let fld_name = symbol.display(mod_env.symbol_pool()).to_string();
+                let fld_name = escape_di_name(&symbol.display(mod_env.symbol_pool()).to_string());
                 let fld_name_cstr = to_cstring!(fld_name.clone());
                 let (field_nm_ptr, field_nm_len) = (fld_name_cstr.as_ptr(), fld_name_cstr.as_bytes().len());
                 let offset = field.get_offset();
-                let mv_ty = field.get_type();
-                let llvm_ty = struct_type.struct_get_type_at_index(offset);
-                let store_size_of_type = llvm_ty.store_size_of_type(data_layout);
-                let abi_size_of_type = llvm_ty.abi_size_of_type(data_layout);
-                let abi_alignment_of_type = llvm_ty.abi_alignment_of_type(data_layout);
-                let size_of_type_in_bits = llvm_ty.size_of_type_in_bits(data_layout);
-                let preferred_alignment_of_type = llvm_ty.preferred_alignment_of_type(data_layout);
-                let element_offset = struct_type.offset_of_element(data_layout, idx);
-                debug!(target: "struct", "Struct at {idx} field {fld_name}: store_size_of_type {}, abi_size_of_type {}, abi_alignment_of_type {}, size_of_type_in_bits {}, preferred_alignment_of_type {}, element_offset {}",
-                    store_size_of_type, abi_size_of_type, abi_alignment_of_type, size_of_type_in_bits, preferred_alignment_of_type, element_offset);
+                // A field of a type parameter's type has the type argument of this instantiation.
+                let mv_ty = field.get_type().instantiate(tys);
 
                 let fld_loc = mod_env.find_named_constant(symbol).map_or_else(|| mod_env.env.unknown_loc(), |named_const| named_const.get_loc());
                 let fld_loc_str = fld_loc.display(mod_env.env).to_string();
                 debug!(target: "struct", "Field {}: {:#?} {}", &fld_name, &fld_loc, fld_loc_str);
 
+                // A field declared in another file than its struct refers to that file, and its line.
+                let (fld_file, fld_line) = field
+                    .get_loc()
+                    .and_then(|loc| mod_env.env.get_file_and_location(&loc))
+                    .filter(|(fld_filename, _)| *fld_filename != filename)
+                    .map_or((struct_file, location.line.0 + offset as u32), |(fld_filename, fld_location)| {
+                        (self.get_or_create_file(&fld_filename).unwrap(), fld_location.line.0)
+                    });
+
+                self.create_nested_struct(func_ctx, &mv_ty);
                 let fld_type = self.get_type(mv_ty.clone(), &fld_name);
 
+                let (sz_in_bits, align_in_bits, store_size_of_type) = if is_opaque {
+                    let sz_in_bits = unsafe { LLVMDITypeGetSizeInBits(fld_type) };
+                    let align_in_bits = self.natural_align_in_bits(fld_type);
+                    let align = align_in_bits as u64;
+                    *current_offset = (*current_offset + align - 1) / align * align;
+                    (sz_in_bits, align_in_bits, (sz_in_bits + 7) / 8)
+                } else {
+                    let (ll_index, element_offset_in_bits) =
+                        lowered_field_position(&struct_type, data_layout, &field);
+                    let llvm_ty = struct_type.struct_get_type_at_index(ll_index);
+                    let store_size_of_type = llvm_ty.store_size_of_type(data_layout);
+                    let abi_size_of_type = llvm_ty.abi_size_of_type(data_layout);
+                    let abi_alignment_of_type = llvm_ty.abi_alignment_of_type(data_layout);
+                    let size_of_type_in_bits = llvm_ty.size_of_type_in_bits(data_layout);
+                    let preferred_alignment_of_type = llvm_ty.preferred_alignment_of_type(data_layout);
+                    let element_offset = struct_type.offset_of_element(data_layout, ll_index);
+                    debug!(target: "struct", "Struct at {idx} field {fld_name}: store_size_of_type {}, abi_size_of_type {}, abi_alignment_of_type {}, size_of_type_in_bits {}, preferred_alignment_of_type {}, element_offset {}",
+                        store_size_of_type, abi_size_of_type, abi_alignment_of_type, size_of_type_in_bits, preferred_alignment_of_type, element_offset);
+
+                    if fld_type == self.core().type_unspecified {
+                        if let mty::Type::Struct(_, _, _) = mv_ty {
+                            let fld_struct_type = llvm_ty.as_struct_type();
+                            let fld_struct_info = fld_struct_type.dump_to_string();
+                            debug!(target: "struct", "fld {fld_name} {}", fld_struct_info);
+                        }
+                    }
+                    let align_in_bits = checked_member_align(
+                        &format!("{struct_name}.{fld_name}"),
+                        abi_alignment_of_type * 8,
+                        preferred_alignment_of_type * 8,
+                    );
+                    // The member is where the data layout put it, after any padding.
+                    *current_offset = element_offset_in_bits;
+                    (size_of_type_in_bits, align_in_bits, store_size_of_type)
+                };
+
                 if fld_type == self.core().type_unspecified {
                     if let mty::Type::Struct(mod_id, struct_id, _v) = mv_ty.clone() {
                         debug!(target: "struct", "fld {fld_name} mod_id {:#?} struct_id {:#?}", mod_id, struct_id);
-                        let fld_struct_type = llvm_ty.as_struct_type();
-                        let fld_struct_info = fld_struct_type.dump_to_string();
-                        debug!(target: "struct", "fld {fld_name} {}", fld_struct_info);
                         let msg = format!("Unresoled field in struct {}", struct_name);
                         self.core().add_unresolved_mty(mv_ty.clone(), fld_name.clone(), msg);
                     }
@@ -1261,16 +2191,13 @@ impl<'up> DIBuilder<'up> {
 
                 let vars = mv_ty.get_vars(); // FIXME: how vars can be used for DWARF?
                 debug!(target: "struct", "vars {:#?}", vars);
-
-                let sz_in_bits: u64 = size_of_type_in_bits;
-                let align_in_bits: u32 = abi_alignment_of_type * 8;
                 let fld = unsafe { LLVMDIBuilderCreateMemberType(
                     di_builder,
                     name_space,
                     field_nm_ptr,
                     field_nm_len,
-                    di_builder_file, //File: LLVMMetadataRef,
-                    location.line.0 + offset as u32,  // FIXME: Loc for fields is the index
+                    fld_file, //File: LLVMMetadataRef,
+                    fld_line,  // FIXME: Loc for fields in the struct's file is the index
                     sz_in_bits,
                     align_in_bits,
                     *current_offset,
@@ -1283,33 +2210,69 @@ impl<'up> DIBuilder<'up> {
                 let field_name = unsafe { std::ffi::CStr::from_ptr(name_c_str).to_string_lossy().into_owned() };
                 debug!(target: "struct", "Struct at {idx} field {fld_name}: created member type {field_name}");
 
+                member_layout.push((*current_offset, sz_in_bits, align_in_bits));
+                member_names.push(fld_name);
                 *current_offset += store_size_of_type * 8;
+                if is_opaque {
+                    struct_type_in_bits = *current_offset;
+                    struct_prefered_alignment = struct_prefered_alignment.max(align_in_bits);
+                }
                 Some(fld)
             }).collect();
+
+            if is_opaque && struct_prefered_alignment != 0 {
+                let align = struct_prefered_alignment as u64;
+                struct_type_in_bits = (struct_type_in_bits + align - 1) / align * align;
+            }
+            if struct_type_in_bits == 0 {
+                warn!(target: "struct", "{struct_name}: DWARF struct size is zero");
+            }
+            if log_enabled!(target: "struct", Level::Debug) {
+                check_struct_layout(struct_name, &member_layout, struct_type_in_bits);
+            }
+            let members: Vec<(u64, u64)> = member_layout
+                .iter()
+                .map(|(offset, size, _align)| (*offset, *size))
+                .collect();
+            self.core().add_type_layout(
+                &display_name,
+                StructLayout::from_members(
+                    &member_names,
+                    &members,
+                    struct_type_in_bits,
+                    struct_prefered_alignment,
+                ),
+            );
+            fields.push(self.create_abilities_member(
+                name_space,
+                struct_file,
+                location.line.0,
+                struct_env.get_abilities(),
+            ));
             let fields_mut: *mut LLVMMetadataRef = fields.as_mut_ptr();
 
             let struct_meta = unsafe {
                 LLVMDIBuilderCreateStructType(
                     di_builder,
-                    name_space,
-                    struct_nm_ptr,   // Name: *const ::libc::c_char,
-                    struct_nm_len,   // NameLen: ::libc::size_t,
-                    di_builder_file, //File: LLVMMetadataRef,
+                    struct_file,
+                    display_name_cstr.as_ptr(), // Name: *const ::libc::c_char,
+                    display_name_cstr.as_bytes().len(), // NameLen: ::libc::size_t,
+                    struct_file,                //File: LLVMMetadataRef,
                     location.line.0,
                     struct_type_in_bits,
                     struct_prefered_alignment,
-                    LLVMDIFlagObjcClassComplete, // FIXME! unclear how flags are used
+                    composite_type_flags(LLVMDIFlagObjcClassComplete, artificial), // FIXME! unclear how flags are used
                     parent.unwrap_or(ptr::null_mut()), // DerivedFrom: LLVMMetadataRef,
-                    fields_mut,                  // Elements: *mut LLVMMetadataRef,
-                    fields.len() as u32,         // NumElements: ::libc::c_uint,
+                    fields_mut,                        // Elements: *mut LLVMMetadataRef,
+                    fields.len() as u32,               // NumElements: ::libc::c_uint,
                     0,               // RunTimeLang: ::libc::c_uint - FIXME: unclear how it is used
                     ptr::null_mut(), // VTableHolder: LLVMMetadataRef - FIXME: likely not used in MOVE
-                    unique_id.as_ptr(), // UniqueId: *const ::libc::c_char - FIXME: not set for now, maybe useful
-                    0,                  // UniqueIdLen: ::libc::size_t
+                    unique_id.as_ptr(), // UniqueId: *const ::libc::c_char
+                    unique_id.as_bytes().len(), // UniqueIdLen: ::libc::size_t
                 )
             };
             let struct_id: move_model::model::StructId = struct_env.get_id();
-            self.core().add_type_struct(struct_id, struct_meta); // Add creted struct type to DB of struct types
+            self.core().add_type_struct(struct_id, tys, struct_meta); // Add creted struct type to DB of struct types
 
             // Check the name in DWARF
             let struct_ref = struct_meta as LLVMMetadataRef;
@@ -1317,7 +2280,7 @@ impl<'up> DIBuilder<'up> {
             debug!(target: "struct", "Added struct type {}", struct_name_new);
 
             assert!(
-                struct_name == struct_name_new,
+                display_name == struct_name_new,
                 "Must create DRARF struct with the same name"
             );
 
@@ -1360,11 +2323,273 @@ impl<'up> DIBuilder<'up> {
         }
     }
 
-    pub fn create_instruction<'a>(
-        &'a self,
-        bc: &'a Bytecode,
-        func_ctx: &'a FunctionContext<'_, '_>,
-    ) -> PublicInstruction {
+    // Creates DWARF for a struct without an LLVM type, e.g. a dependency struct only referred to
+    // by type, from the types of its Move fields laid out as the data layout would. The field
+    // types are instantiated with 'tys', the type arguments of a generic struct.
+    pub fn create_struct_from_model(
+        &self,
+        struct_env: &StructEnv,
+        tys: &[mty::Type],
+        struct_name: &str,
+    ) -> LLVMMetadataRef {
+        match self.0.as_ref().filter(|x| !x.line_tables_only) {
+            Some(di_builder_core) => {
+                let mod_env = &struct_env.module_env;
+                let fields: Vec<(String, mty::Type)> = struct_env
+                    .get_fields()
+                    .map(|field| {
+                        let symbol = field.get_name();
+                        let fld_name = symbol.display(mod_env.symbol_pool()).to_string();
+                        (escape_di_name(&fld_name), field.get_type().instantiate(tys))
+                    })
+                    .collect();
+                let fld_types: Vec<LLVMMetadataRef> = fields
+                    .iter()
+                    .map(|(fld_name, fld_mty)| self.get_type(fld_mty.clone(), fld_name))
+                    .collect();
+                let (offsets, size_in_bits, align_in_bits) = self.natural_layout(&fld_types);
+                let display_name = struct_env.di_display_name(tys);
+                let names: Vec<String> = fields.iter().map(|(name, _)| name.clone()).collect();
+                let members: Vec<(u64, u64)> = offsets
+                    .iter()
+                    .zip(&fld_types)
+                    .map(|(offset, fld_type)| {
+                        (*offset, unsafe { LLVMDITypeGetSizeInBits(*fld_type) })
+                    })
+                    .collect();
+                di_builder_core.add_type_layout(
+                    &display_name,
+                    StructLayout::from_members(&names, &members, size_in_bits, align_in_bits),
+                );
+                let fields: Vec<(String, mty::Type, u64)> = fields
+                    .into_iter()
+                    .zip(offsets)
+                    .map(|((fld_name, fld_mty), offset_in_bits)| {
+                        (fld_name, fld_mty, offset_in_bits / 8)
+                    })
+                    .collect();
+                let struct_meta = self.create_scoped_anonymous_struct(
+                    di_builder_core.builder_file,
+                    &display_name,
+                    struct_env.di_instantiation_id(tys),
+                    &fields,
+                    (size_in_bits / 8, align_in_bits / 8),
+                    false,
+                );
+                di_builder_core.add_type_struct(struct_env.get_id(), tys, struct_meta);
+
+                // Keep the struct, as create_struct does.
+                let name_cstr = to_cstring!(struct_name);
+                unsafe {
+                    let module_ctx = LLVMGetModuleContext(di_builder_core.module_di);
+                    LLVMAddNamedMetadataOperand(
+                        di_builder_core.module_di,
+                        name_cstr.as_ptr(),
+                        LLVMMetadataAsValue(module_ctx, struct_meta),
+                    );
+                }
+                struct_meta
+            }
+            None => ptr::null_mut(),
+        }
+    }
+
+    // The alignment of a DWARF type. Basic types are created without one, they take that of the
+    // integer of their size in the module's data layout.
+    fn natural_align_in_bits(&self, di_type: LLVMMetadataRef) -> u32 {
+        let align_in_bits = unsafe { LLVMDITypeGetAlignInBits(di_type) };
+        if align_in_bits != 0 {
+            return align_in_bits;
+        }
+        let core = self.core();
+        let size_in_bits = unsafe { LLVMDITypeGetSizeInBits(di_type) };
+        if size_in_bits == 0 {
+            return 8;
+        }
+        let data_layout = unsafe { TargetData::of_module(core.module_di) };
+        let int_type = core.g_ctx.llvm_cx.int_type(size_in_bits as usize);
+        int_type.abi_alignment_of_type(data_layout) * 8
+    }
+
+    // Lays out values of the DWARF types 'di_types' one after the other, each at its natural
+    // alignment, as the data layout lays out a struct. Returns their offsets, and the size,
+    // padded to the alignment, and the alignment of the whole, all in bits.
+    fn natural_layout(&self, di_types: &[LLVMMetadataRef]) -> (Vec<u64>, u64, u32) {
+        let mut offset_in_bits = 0;
+        let mut struct_align_in_bits = 8;
+        let offsets = di_types
+            .iter()
+            .map(|di_type| {
+                let size_in_bits = unsafe { LLVMDITypeGetSizeInBits(*di_type) };
+                let align_in_bits = self.natural_align_in_bits(*di_type);
+                let align = align_in_bits as u64;
+                let offset = (offset_in_bits + align - 1) / align * align;
+                offset_in_bits = offset + (size_in_bits + 7) / 8 * 8;
+                struct_align_in_bits = struct_align_in_bits.max(align_in_bits);
+                offset
+            })
+            .collect();
+        let align = struct_align_in_bits as u64;
+        let size_in_bits = (offset_in_bits + align - 1) / align * align;
+        (offsets, size_in_bits, struct_align_in_bits)
+    }
+
+    // Creates DWARF for a struct that has no StructEnv in the model, like the environment of a
+    // lowered closure. Offsets, size and alignment are in bytes, field types resolve via get_type.
+    // Such structs are usually synthesized, so pass artificial unless one mirrors a user type.
+    // A struct without fields, like the environment of a closure capturing nothing, gets an
+    // empty elements list and keeps the given size, which may be non-zero due to padding.
+    pub fn create_anonymous_struct(
+        &self,
+        name: &str,
+        fields: &[(String, mty::Type, u64)],
+        size: u64,
+        align: u32,
+        artificial: bool,
+    ) -> LLVMMetadataRef {
+        match self.0.as_ref().filter(|x| !x.line_tables_only) {
+            Some(di_builder_core) => self.create_scoped_anonymous_struct(
+                di_builder_core.builder_file,
+                name,
+                anonymous_struct_unique_id(name, fields),
+                fields,
+                (size, align),
+                artificial,
+            ),
+            None => ptr::null_mut(),
+        }
+    }
+
+    // Creates DWARF for the tuple returned by 'fn_name', as an artificial struct whose
+    // identifier names the function: functions returning the same tuple shape get distinct
+    // types. Members are named by position and laid out naturally.
+    pub fn create_tuple_return_type(&self, fn_name: &str, tys: &[mty::Type]) -> LLVMMetadataRef {
+        match self.0.as_ref().filter(|x| !x.line_tables_only) {
+            Some(di_builder_core) => {
+                let elt_types: Vec<LLVMMetadataRef> = enumerate(tys)
+                    .map(|(idx, ty)| self.get_type(ty.clone(), &format!("{idx}")))
+                    .collect();
+                let (offsets, size_in_bits, align_in_bits) = self.natural_layout(&elt_types);
+                let fields: Vec<(String, mty::Type, u64)> = enumerate(tys.iter().zip(offsets))
+                    .map(|(idx, (ty, offset_in_bits))| {
+                        (format!("{idx}"), ty.clone(), offset_in_bits / 8)
+                    })
+                    .collect();
+                let (size, align) = (size_in_bits / 8, align_in_bits / 8);
+                let unique_id = anonymous_struct_unique_id(&format!("tuple.{fn_name}"), &fields);
+                self.create_scoped_anonymous_struct(
+                    di_builder_core.builder_file,
+                    "tuple",
+                    unique_id,
+                    &fields,
+                    (size, align),
+                    true,
+                )
+            }
+            None => ptr::null_mut(),
+        }
+    }
+
+    // Offsets, size and alignment are in bytes, the struct is cached under 'unique_id'.
+    fn create_scoped_anonymous_struct(
+        &self,
+        scope: LLVMMetadataRef,
+        name: &str,
+        unique_id: String,
+        fields: &[(String, mty::Type, u64)],
+        (size, align): (u64, u32),
+        artificial: bool,
+    ) -> LLVMMetadataRef {
+        if let Some(di_builder_core) = self.0.as_ref() {
+            let cached = di_builder_core
+                .g_ctx
+                .di_context
+                .type_anonymous_struct_db
+                .borrow()
+                .get(&unique_id)
+                .copied();
+            if let Some(struct_meta) = cached {
+                debug!(target: "struct", "reuse anonymous struct {unique_id}");
+                return struct_meta;
+            }
+
+            let di_builder = di_builder_core.builder_ref;
+            let di_builder_file = di_builder_core.builder_file;
+
+            let mut members: Vec<LLVMMetadataRef> = fields
+                .iter()
+                .map(|(fld_name, fld_mty, fld_offset)| {
+                    let fld_name = escape_di_name(fld_name);
+                    let fld_type = self.get_type(fld_mty.clone(), &fld_name);
+                    if fld_type == di_builder_core.type_unspecified {
+                        let msg = format!("Unresolved field in anonymous struct {}", name);
+                        di_builder_core.add_unresolved_mty(fld_mty.clone(), fld_name.clone(), msg);
+                    }
+                    let sz_in_bits = unsafe { LLVMDITypeGetSizeInBits(fld_type) };
+                    let align_in_bits = unsafe { LLVMDITypeGetAlignInBits(fld_type) };
+                    let fld_name_cstr = to_cstring!(fld_name.as_str());
+                    unsafe {
+                        LLVMDIBuilderCreateMemberType(
+                            di_builder,
+                            scope,
+                            fld_name_cstr.as_ptr(),
+                            fld_name_cstr.as_bytes().len(),
+                            di_builder_file,
+                            0,
+                            sz_in_bits,
+                            align_in_bits,
+                            fld_offset * 8,
+                            LLVMDIFlagZero,
+                            fld_type,
+                        )
+                    }
+                })
+                .collect();
+
+            let name_cstr = to_cstring!(name);
+            let unique_id_cstr = to_cstring!(unique_id.as_str());
+            let struct_meta = unsafe {
+                LLVMDIBuilderCreateStructType(
+                    di_builder,
+                    scope,
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    di_builder_file,
+                    0,
+                    size * 8,
+                    align * 8,
+                    composite_type_flags(LLVMDIFlagZero, artificial),
+                    ptr::null_mut(),
+                    members.as_mut_ptr(),
+                    members.len() as u32,
+                    0,
+                    ptr::null_mut(),
+                    unique_id_cstr.as_ptr(),
+                    unique_id_cstr.as_bytes().len(),
+                )
+            };
+            debug!(target: "struct", "create_anonymous_struct {unique_id}: {} members", members.len());
+
+            di_builder_core
+                .g_ctx
+                .di_context
+                .type_anonymous_struct_db
+                .borrow_mut()
+                .insert(unique_id, struct_meta);
+            struct_meta
+        } else {
+            ptr::null_mut()
+        }
+    }
+
+    pub fn create_instruction<'a>(
+        &'a self,
+        bc: &'a Bytecode,
+        func_ctx: &'a FunctionContext<'_, '_>,
+    ) -> PublicInstruction {
+        if !self.describes_function(func_ctx) {
+            return PublicInstruction(None);
+        }
         if let Some(_di_builder_core) = &self.0 {
             let instr = Instruction::new(bc, func_ctx);
             instr.debug();
@@ -1420,6 +2645,72 @@ impl<'up> DIBuilder<'up> {
             unsafe { LLVMDIBuilderFinalize(x.builder_ref) };
         }
     }
+
+    // Debug info is emitted into the module the DIBuilder was created for, and must be finalized
+    // there, in the module that goes to the backend. A clone taken before finalize carries
+    // unfinalized metadata, and nothing emitted later reaches it, so clone only after finalize.
+    pub fn finalize_in(&self, live_module: &Module) {
+        self.is_emitted_into(live_module);
+        self.finalize();
+    }
+
+    // Returns whether the debug info is emitted into 'module', and warns when not.
+    pub fn is_emitted_into(&self, module: &Module) -> bool {
+        match &self.0 {
+            Some(x) if x.module_di != module.0 => {
+                warn!(target: "dwarf",
+                    "debug info is emitted into module {:#?}, not into live module {} at {:#?}, debug info is lost",
+                    x.module_di, module.get_module_id(), module.0);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    // Writes the layouts of the structs described so far as JSON, by Move name, e.g.
+    // {"0x1::coin::Coin": {"size": 8, "align": 8, "fields": [{"name": "value", "offset": 0,
+    // "size": 8}]}}, for tools that need struct layouts without parsing DWARF.
+    pub fn export_type_index(&self, path: &str) -> Result<()> {
+        let x = match &self.0 {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let content = serde_json::to_string_pretty(&*x.g_ctx.di_context.type_index.borrow())?;
+        std::fs::write(path, content).with_context(|| format!("Cannot write type index {path}"))
+    }
+
+    // Runs the LLVM verifier on the module after finalize, so that malformed debug info, e.g. a
+    // zero-sized type or a dangling scope, is reported before the backend trips over it. The
+    // error holds the verifier message, which names the broken metadata node.
+    pub fn verify(&self) -> Result<()> {
+        use llvm_sys::analysis::{LLVMVerifierFailureAction, LLVMVerifyModule};
+        let x = match &self.0 {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let mut message = ptr::null_mut();
+        let failed = unsafe {
+            LLVMVerifyModule(
+                x.module_di,
+                LLVMVerifierFailureAction::LLVMReturnStatusAction,
+                &mut message,
+            )
+        };
+        let message_str = if message.is_null() {
+            String::new()
+        } else {
+            let message_str = unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned();
+            unsafe { LLVMDisposeMessage(message) };
+            message_str
+        };
+        if failed != 0 {
+            return Err(anyhow::anyhow!(message_str))
+                .with_context(|| format!("Broken debug info in {}", x.module_source));
+        }
+        Ok(())
+    }
 }
 
 fn loc_display(loc: &move_model::model::Loc, env: &GlobalEnv) -> (String, u32, u32, u32, u32) {
@@ -1511,3 +2802,1531 @@ fn read_substring(file_path: &str, begin: usize, end: usize) -> anyhow::Result<S
 
     Ok(substring)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_di_name() {
+        assert_eq!(escape_di_name("field_u64"), "field_u64");
+        assert_eq!(escape_di_name("fld\0a"), "fld%00a");
+        assert_eq!(escape_di_name("fld%00a"), "fld%2500a");
+        assert_ne!(escape_di_name("fld\0a"), escape_di_name("fld\0b"));
+        assert!(std::ffi::CString::new(escape_di_name("fld\0a")).is_ok());
+    }
+
+    #[test]
+    fn test_anonymous_struct_unique_id() {
+        let fields = [
+            (
+                "count".to_string(),
+                mty::Type::Primitive(mty::PrimitiveType::U64),
+                0,
+            ),
+            (
+                "flag".to_string(),
+                mty::Type::Primitive(mty::PrimitiveType::Bool),
+                8,
+            ),
+        ];
+        let unique_id = anonymous_struct_unique_id("closure_env", &fields);
+        assert!(unique_id.starts_with("anon.closure_env."));
+        assert!(unique_id.contains(".count:"));
+        assert!(unique_id.contains(".flag:"));
+        assert_eq!(
+            unique_id,
+            anonymous_struct_unique_id("closure_env", &fields)
+        );
+
+        // Same name with a different layout is a different struct.
+        let mut moved = fields.clone();
+        moved[1].2 = 16;
+        assert_ne!(unique_id, anonymous_struct_unique_id("closure_env", &moved));
+    }
+
+    #[test]
+    fn test_opaque_struct_layout() {
+        // create_struct falls back to the Move field types while the LLVM body is not set yet.
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("opaque_struct_layout");
+        let struct_type = llcx.create_opaque_named_struct("opaque_struct_layout::S");
+        assert!(struct_type.is_opaque());
+
+        struct_type.set_struct_body(&[llcx.int_type(64), llcx.int_type(8)]);
+        assert!(!struct_type.is_opaque());
+        let data_layout = module.get_module_data_layout();
+        assert!(struct_type.as_any_type().size_of_type_in_bits(data_layout) > 0);
+    }
+
+    #[test]
+    fn test_composite_type_flags() {
+        // A synthesized tuple-return type is artificial, a user struct is not.
+        let tuple_return_flags = composite_type_flags(LLVMDIFlagZero, true);
+        let user_struct_flags = composite_type_flags(LLVMDIFlagObjcClassComplete, false);
+        assert_ne!(tuple_return_flags & LLVMDIFlagArtificial, 0);
+        assert_eq!(user_struct_flags & LLVMDIFlagArtificial, 0);
+        assert_eq!(user_struct_flags, LLVMDIFlagObjcClassComplete);
+    }
+
+    #[test]
+    fn test_check_struct_layout() {
+        // { u64, u8 }: 72 bits of members padded to 128 by the u64 alignment.
+        let members = [(0, 64, 64), (64, 8, 8)];
+        assert!(check_struct_layout("S", &members, 128));
+        // A layout computation that forgot the tail padding.
+        assert!(!check_struct_layout("S", &members, 72));
+        // Overlapping members.
+        assert!(!check_struct_layout("S", &[(0, 64, 64), (32, 64, 64)], 128));
+    }
+
+    #[test]
+    fn test_basic_type_encoding() {
+        // Wide integers are single unsigned base types, not pairs of 64-bit halves.
+        assert_eq!(basic_type_encoding("u128"), DW_ATE_UNSIGNED);
+        assert_eq!(basic_type_encoding("u256"), DW_ATE_UNSIGNED);
+        assert_eq!(basic_type_encoding("bool"), DW_ATE_BOOLEAN);
+        assert_eq!(basic_type_encoding("address"), 0);
+    }
+
+    #[test]
+    fn test_compile_unit_flags() {
+        // The flags string given to DIBuilder::new ends up in the emitted compile unit.
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("compile_unit_flags");
+        let flags = "-g --dwarf-flags";
+        unsafe {
+            let builder_ref = LLVMCreateDIBuilder(module.0);
+            let file_cstr = to_cstring!("compile_unit_flags.move");
+            let dir_cstr = to_cstring!("/");
+            let builder_file = LLVMDIBuilderCreateFile(
+                builder_ref,
+                file_cstr.as_ptr(),
+                file_cstr.as_bytes().len(),
+                dir_cstr.as_ptr(),
+                dir_cstr.as_bytes().len(),
+            );
+            DIBuilder::create_compiled_unit(
+                builder_ref,
+                builder_file,
+                "move-mv-llvm-compiler".to_string(),
+                flags,
+                LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+            );
+            LLVMDIBuilderFinalize(builder_ref);
+            crate::stackless::llvm::LLVMDisposeDIBuilder(builder_ref);
+        }
+        let module_info = print_module_to_str(&module.0);
+        assert!(module_info.contains(&format!("flags: \"{flags}\"")));
+    }
+
+    #[test]
+    fn test_address_type() {
+        // An address is a byte array of the target address size.
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("address_type");
+        unsafe {
+            let builder_ref = LLVMCreateDIBuilder(module.0);
+            let name_cstr = to_cstring!("u8");
+            let type_u8 = LLVMDIBuilderCreateBasicType(
+                builder_ref,
+                name_cstr.as_ptr(),
+                name_cstr.as_bytes().len(),
+                8,
+                basic_type_encoding("u8"),
+                LLVMDIFlagZero,
+            );
+            let type_address = create_address_type(builder_ref, type_u8);
+            assert!(matches!(
+                LLVMGetMetadataKind(type_address),
+                LLVMMetadataKind::LLVMDICompositeTypeMetadataKind
+            ));
+            assert_eq!(
+                LLVMDITypeGetSizeInBits(type_address),
+                8 * account_address::AccountAddress::LENGTH as u64
+            );
+            crate::stackless::llvm::LLVMDisposeDIBuilder(builder_ref);
+        }
+    }
+
+    #[test]
+    fn test_signer_type() {
+        // A signer is a struct with the account address as member 'address' at offset 0.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("signer_type");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let signer_ty = mty::Type::Primitive(mty::PrimitiveType::Signer);
+            let type_signer = di_builder.get_type(signer_ty, &"s".to_string());
+            assert!(matches!(
+                unsafe { LLVMGetMetadataKind(type_signer) },
+                LLVMMetadataKind::LLVMDICompositeTypeMetadataKind
+            ));
+            assert_eq!(
+                unsafe { LLVMDITypeGetSizeInBits(type_signer) },
+                8 * account_address::AccountAddress::LENGTH as u64
+            );
+            add_test_metadata(g_ctx, &module, type_signer);
+            di_builder.finalize();
+
+            let module_info = print_module_to_str(&module.0);
+            let member = module_info
+                .lines()
+                .find(|line| line.contains("DW_TAG_member, name: \"address\""))
+                .expect("member address");
+            // A zero offset is not printed.
+            assert!(!member.contains("offset:"));
+            let base_type = member.split("baseType: ").nth(1).expect("baseType");
+            let base_type = base_type
+                .split(|c| c == ',' || c == ')')
+                .next()
+                .expect("baseType");
+            let address = module_info
+                .lines()
+                .find(|line| line.starts_with(&format!("{base_type} = ")))
+                .expect("address type");
+            assert!(address.contains("DW_TAG_array_type"));
+        });
+    }
+
+    #[test]
+    fn test_native_function_declaration() {
+        // A native's LLVM declaration gets a subprogram that is not a definition.
+        use llvm_sys::debuginfo::LLVMGetSubprogram;
+
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("native_function_declaration");
+        let ll_fnty = crate::stackless::llvm::FunctionType::new(llcx.void_type(), &[]);
+        let ll_fn = module.add_function("move_native_vector_length", ll_fnty);
+        unsafe {
+            let builder_ref = LLVMCreateDIBuilder(module.0);
+            let file_cstr = to_cstring!("native_function_declaration.move");
+            let dir_cstr = to_cstring!("/");
+            let builder_file = LLVMDIBuilderCreateFile(
+                builder_ref,
+                file_cstr.as_ptr(),
+                file_cstr.as_bytes().len(),
+                dir_cstr.as_ptr(),
+                dir_cstr.as_bytes().len(),
+            );
+            let function = create_declaration_subprogram(
+                builder_ref,
+                builder_file,
+                ll_fn,
+                "0x1::vector::length",
+                1,
+            );
+            assert_eq!(LLVMGetSubprogram(ll_fn.0), function);
+            LLVMDIBuilderFinalize(builder_ref);
+            crate::stackless::llvm::LLVMDisposeDIBuilder(builder_ref);
+        }
+        let module_info = print_module_to_str(&module.0);
+        assert!(module_info.contains("declare !dbg !0 void @move_native_vector_length()"));
+        assert!(module_info.contains("name: \"0x1::vector::length\""));
+        assert!(module_info.contains("linkageName: \"move_native_vector_length\""));
+        assert!(!module_info.contains("DISPFlagDefinition"));
+        assert!(!module_info.contains("<temporary!>"), "{module_info}");
+    }
+
+    #[test]
+    fn test_scope_line() {
+        // A header spread over lines 3..5 with the first statement on line 6.
+        assert_eq!(scope_line(3, Some(6)), 6);
+        assert_eq!(scope_line(3, Some(3)), 3);
+        // Without a body location, or with a bogus one, the declaration line is used.
+        assert_eq!(scope_line(3, None), 3);
+        assert_eq!(scope_line(3, Some(1)), 3);
+    }
+
+    #[test]
+    fn test_entry_subprogram_is_external() {
+        // An entry function's subprogram is external, a private helper's is local to the unit.
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("entry_subprogram");
+        unsafe {
+            let builder_ref = LLVMCreateDIBuilder(module.0);
+            let file_cstr = to_cstring!("entry_subprogram.move");
+            let dir_cstr = to_cstring!("/");
+            let builder_file = LLVMDIBuilderCreateFile(
+                builder_ref,
+                file_cstr.as_ptr(),
+                file_cstr.as_bytes().len(),
+                dir_cstr.as_ptr(),
+                dir_cstr.as_bytes().len(),
+            );
+            for (name, is_entry) in [("0x101::m::transfer", true), ("0x101::m::helper", false)] {
+                let name_cstr = to_cstring!(name);
+                let subroutine_ty = LLVMDIBuilderCreateSubroutineType(
+                    builder_ref,
+                    builder_file,
+                    ptr::null_mut(),
+                    0,
+                    LLVMDIFlagZero,
+                );
+                let function = LLVMDIBuilderCreateFunction(
+                    builder_ref,
+                    builder_file,
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    builder_file,
+                    1,
+                    subroutine_ty,
+                    subprogram_is_local_to_unit(is_entry),
+                    1,
+                    1,
+                    LLVMDIFlagZero,
+                    0,
+                );
+                let named_md_cstr = to_cstring!("subprograms");
+                LLVMAddNamedMetadataOperand(
+                    module.0,
+                    named_md_cstr.as_ptr(),
+                    LLVMMetadataAsValue(llcx.0, function),
+                );
+            }
+            LLVMDIBuilderFinalize(builder_ref);
+            crate::stackless::llvm::LLVMDisposeDIBuilder(builder_ref);
+        }
+        let module_info = print_module_to_str(&module.0);
+        let sp_flags = |name: &str| {
+            let line = module_info
+                .lines()
+                .find(|line| line.contains(&format!("name: \"{name}\"")))
+                .expect("subprogram");
+            line.split("spFlags: ").nth(1).expect("spFlags").to_string()
+        };
+        assert!(sp_flags("0x101::m::transfer").starts_with("DISPFlagDefinition"));
+        assert!(
+            sp_flags("0x101::m::helper").starts_with("DISPFlagLocalToUnit | DISPFlagDefinition")
+        );
+    }
+
+    #[test]
+    fn test_compile_units_share_module() {
+        // Two Move modules compiled into one LLVM module get a compile unit each,
+        // and the module flags are not duplicated.
+        let llcx = crate::stackless::llvm::Context::new();
+        let module = llcx.create_module("package");
+        for source in ["a.move", "b.move"] {
+            unsafe {
+                let builder_ref = LLVMCreateDIBuilder(module.0);
+                let file_cstr = to_cstring!(source);
+                let dir_cstr = to_cstring!("/");
+                let builder_file = LLVMDIBuilderCreateFile(
+                    builder_ref,
+                    file_cstr.as_ptr(),
+                    file_cstr.as_bytes().len(),
+                    dir_cstr.as_ptr(),
+                    dir_cstr.as_bytes().len(),
+                );
+                DIBuilder::create_compiled_unit(
+                    builder_ref,
+                    builder_file,
+                    "move-mv-llvm-compiler".to_string(),
+                    "",
+                    LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                );
+                DIBuilder::add_accelerator_table_flags(module.0);
+                LLVMDIBuilderFinalize(builder_ref);
+                crate::stackless::llvm::LLVMDisposeDIBuilder(builder_ref);
+            }
+        }
+        module.verify();
+        let module_info = print_module_to_str(&module.0);
+        assert_eq!(module_info.matches("distinct !DICompileUnit(").count(), 2);
+        assert!(module_info.contains("!DIFile(filename: \"a.move\""));
+        assert!(module_info.contains("!DIFile(filename: \"b.move\""));
+        assert_eq!(module_info.matches("!\"Dwarf Version\"").count(), 1);
+        assert_eq!(module_info.matches("!\"Debug Info Version\"").count(), 1);
+    }
+
+    #[test]
+    fn test_module_contexts_share_module() {
+        // The second Move module translated into one LLVM module attaches to it with a further
+        // compile unit and leaves the source file name of the first.
+        let files = [
+            ("a.move", "module 0x101::a { struct A { x: u64 } }"),
+            (
+                "b.move",
+                "module 0x101::b { use 0x101::a::A; struct B { a: A } }",
+            ),
+        ];
+        with_test_global_ctx_files(&files, |g_ctx, sources| {
+            use crate::stackless::{entrypoint::EntrypointGenerator, Target, TargetPlatform};
+
+            let tgt_platform = TargetPlatform::Solana;
+            let llmachine = Target::from_triple(tgt_platform.triple())
+                .unwrap()
+                .create_target_machine(
+                    tgt_platform.triple(),
+                    tgt_platform.llvm_cpu(),
+                    tgt_platform.llvm_features(),
+                    "none",
+                );
+            let options = Options {
+                debug: true,
+                ..Options::default()
+            };
+            let entry_llmod = g_ctx.llvm_cx.create_module("solana_entrypoint");
+            let entrypoint_generator =
+                EntrypointGenerator::new(g_ctx, &entry_llmod, &llmachine, &options);
+            let llmod = g_ctx.llvm_cx.create_module("package");
+            for (m_env, source) in g_ctx.env.get_modules().zip(sources) {
+                let mod_cx = g_ctx.create_module_context(
+                    m_env.get_id(),
+                    &llmod,
+                    &entrypoint_generator,
+                    &options,
+                    source,
+                );
+                mod_cx.llvm_di_builder.finalize();
+            }
+            assert!(llmod.get_module_source().ends_with("a.move"));
+            llmod.verify();
+            let module_info = print_module_to_str(&llmod.0);
+            assert_eq!(module_info.matches("distinct !DICompileUnit(").count(), 2);
+            assert!(module_info.contains("!DIFile(filename: \"a.move\""));
+            assert!(module_info.contains("!DIFile(filename: \"b.move\""));
+        });
+    }
+
+    #[test]
+    fn test_loop_header_labels() {
+        use move_stackless_bytecode::stackless_bytecode::AttrId;
+
+        // while (cond) { body }: L0 is the header, L2 the exit.
+        let attr = AttrId::new(0);
+        let (l0, l1, l2) = (Label::new(0), Label::new(1), Label::new(2));
+        let code = [
+            Bytecode::Label(attr, l0),
+            Bytecode::Branch(attr, l1, l2, 0),
+            Bytecode::Label(attr, l1),
+            Bytecode::Jump(attr, l0),
+            Bytecode::Label(attr, l2),
+        ];
+        assert_eq!(loop_header_labels(&code), BTreeSet::from([l0]));
+
+        // Forward jumps alone, as in if-else, make no loop.
+        let code = [
+            Bytecode::Branch(attr, l0, l1, 0),
+            Bytecode::Label(attr, l0),
+            Bytecode::Jump(attr, l2),
+            Bytecode::Label(attr, l1),
+            Bytecode::Label(attr, l2),
+        ];
+        assert!(loop_header_labels(&code).is_empty());
+    }
+
+    // Runs 'f' with a global context for the Move module 'code', and the path of its source.
+    fn with_test_global_ctx(code: &str, f: impl FnOnce(&GlobalContext, &str)) {
+        with_test_global_ctx_files(&[("m.move", code)], |g_ctx, sources| f(g_ctx, &sources[0]))
+    }
+
+    // Like 'with_test_global_ctx', for a package of the named 'files'.
+    fn with_test_global_ctx_files(
+        files: &[(&str, &str)],
+        f: impl FnOnce(&GlobalContext, &[String]),
+    ) {
+        use crate::stackless::{Target, TargetPlatform};
+        use move_compiler::shared::PackagePaths;
+        use move_model::run_model_builder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sources: Vec<String> = files
+            .iter()
+            .map(|(name, code)| {
+                let source = dir.path().join(name);
+                std::fs::write(&source, code).unwrap();
+                source.to_string_lossy().to_string()
+            })
+            .collect();
+        let env = run_model_builder(
+            vec![PackagePaths {
+                name: None,
+                paths: sources.clone(),
+                named_address_map: BTreeMap::<String, _>::new(),
+            }],
+            vec![],
+        )
+        .unwrap();
+        let tgt_platform = TargetPlatform::Solana;
+        tgt_platform.initialize_llvm();
+        let llmachine = Target::from_triple(tgt_platform.triple())
+            .unwrap()
+            .create_target_machine(
+                tgt_platform.triple(),
+                tgt_platform.llvm_cpu(),
+                tgt_platform.llvm_features(),
+                "none",
+            );
+        let g_ctx = GlobalContext::new(&env, tgt_platform, &llmachine);
+        f(&g_ctx, &sources);
+    }
+
+    // Keeps 'meta' in the printed module, which drops metadata nothing refers to.
+    fn add_test_metadata(g_ctx: &GlobalContext, module: &Module, meta: LLVMMetadataRef) {
+        let name_cstr = to_cstring!("types");
+        unsafe {
+            LLVMAddNamedMetadataOperand(
+                module.0,
+                name_cstr.as_ptr(),
+                LLVMMetadataAsValue(g_ctx.llvm_cx.0, meta),
+            )
+        };
+    }
+
+    #[test]
+    fn test_line_tables_only() {
+        // A line-tables-only compile unit describes no types, unlike a full one.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let fields = [(
+                "value".to_string(),
+                mty::Type::Primitive(mty::PrimitiveType::U64),
+                0,
+            )];
+
+            let module_info = |debug_info: DebugInfo| {
+                let module = g_ctx.llvm_cx.create_module(&format!("{debug_info:?}"));
+                let di_builder =
+                    DIBuilder::new(g_ctx, &module, source, debug_info, &Options::default());
+                assert_eq!(
+                    di_builder.line_tables_only(),
+                    debug_info == DebugInfo::LineTablesOnly
+                );
+                let struct_meta = di_builder.create_anonymous_struct("env", &fields, 8, 8, true);
+                assert_eq!(
+                    struct_meta.is_null(),
+                    debug_info == DebugInfo::LineTablesOnly
+                );
+                if !struct_meta.is_null() {
+                    add_test_metadata(g_ctx, &module, struct_meta);
+                }
+                di_builder.finalize();
+                print_module_to_str(&module.0)
+            };
+
+            let line_tables = module_info(DebugInfo::LineTablesOnly);
+            assert!(line_tables.contains("emissionKind: LineTablesOnly"));
+            assert!(!line_tables.contains("DICompositeType"));
+
+            let full = module_info(DebugInfo::Full);
+            assert!(full.contains("emissionKind: FullDebug"));
+            assert!(full.contains("DICompositeType"));
+        });
+    }
+
+    #[test]
+    fn test_reference_member() {
+        // A reference member of a synthesized struct, like a closure environment holding a borrow,
+        // is a pointer to its target type, which a debugger can follow.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("reference_member");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+            let fields = [
+                (
+                    "r".to_string(),
+                    mty::Type::Reference(true, Box::new(u64_ty.clone())),
+                    0,
+                ),
+                ("n".to_string(), u64_ty, 8),
+            ];
+            let struct_meta = di_builder.create_anonymous_struct("env", &fields, 16, 8, true);
+            add_test_metadata(g_ctx, &module, struct_meta);
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+
+            // Follows the baseType of the node described by 'line'.
+            let base_type = |line: &str| {
+                let id = line
+                    .split("baseType: ")
+                    .nth(1)
+                    .expect("baseType")
+                    .split(|c| c == ',' || c == ')')
+                    .next()
+                    .unwrap()
+                    .to_string();
+                module_info
+                    .lines()
+                    .find(|line| line.starts_with(&format!("{id} = ")))
+                    .expect("base type node")
+                    .to_string()
+            };
+            let member = module_info
+                .lines()
+                .find(|line| line.contains("tag: DW_TAG_member, name: \"r\""))
+                .expect("member r");
+            let pointer = base_type(member);
+            assert!(pointer.contains("tag: DW_TAG_pointer_type, name: \"&mut u64\""));
+            assert!(pointer.contains("size: 64"));
+            assert!(base_type(&pointer).contains("!DIBasicType(name: \"u64\""));
+        });
+    }
+
+    #[test]
+    fn test_location_column() {
+        // Instruction locations carry the 1-based column of their Move source, so a debugger can
+        // tell statements on one line apart.
+        let code = "module 0x101::m {\n    fun f(): u64 { 1 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let env = g_ctx.env;
+            let fn_env = env
+                .get_modules()
+                .flat_map(|m| m.into_functions())
+                .find(|f| f.get_name_str() == "f")
+                .expect("function f");
+            let (_file, line, column, _start, _end) = loc_display(&fn_env.get_loc(), env);
+            assert_eq!((line, column), (2, 5));
+
+            let module = g_ctx.llvm_cx.create_module("location_column");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let name_cstr = to_cstring!("f");
+            let location = unsafe {
+                let builder_ref = di_builder.builder_ref().unwrap();
+                let builder_file = di_builder.builder_file().unwrap();
+                let subroutine_ty = LLVMDIBuilderCreateSubroutineType(
+                    builder_ref,
+                    builder_file,
+                    ptr::null_mut(),
+                    0,
+                    LLVMDIFlagZero,
+                );
+                let function = LLVMDIBuilderCreateFunction(
+                    builder_ref,
+                    builder_file,
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    builder_file,
+                    line,
+                    subroutine_ty,
+                    1,
+                    1,
+                    line,
+                    LLVMDIFlagZero,
+                    0,
+                );
+                LLVMDIBuilderCreateDebugLocation(
+                    g_ctx.llvm_cx.0,
+                    line,
+                    column,
+                    function,
+                    ptr::null_mut(),
+                )
+            };
+            add_test_metadata(g_ctx, &module, location);
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+            assert!(module_info.contains("!DILocation(line: 2, column: 5,"));
+        });
+    }
+
+    #[test]
+    fn test_inlined_location_chain() {
+        // inner is inlined into middle, which is inlined into outer: the location in inner points
+        // through inlinedAt to the call in middle, and from there to the call in outer.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("inlined_location");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let builder_ref = di_builder.builder_ref().unwrap();
+            let builder_file = di_builder.builder_file().unwrap();
+            let subprogram = |name: &str, line: u32| unsafe {
+                let name_cstr = to_cstring!(name);
+                let subroutine_ty = LLVMDIBuilderCreateSubroutineType(
+                    builder_ref,
+                    builder_file,
+                    ptr::null_mut(),
+                    0,
+                    LLVMDIFlagZero,
+                );
+                LLVMDIBuilderCreateFunction(
+                    builder_ref,
+                    builder_file,
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    builder_file,
+                    line,
+                    subroutine_ty,
+                    1,
+                    1,
+                    line,
+                    LLVMDIFlagZero,
+                    0,
+                )
+            };
+            let outer = subprogram("outer", 1);
+            let middle = subprogram("middle", 10);
+            let inner = subprogram("inner", 20);
+            unsafe {
+                let ll_ctx = g_ctx.llvm_cx.0;
+                let name_cstr = to_cstring!("outer");
+                let fn_ty = LLVMFunctionType(LLVMVoidTypeInContext(ll_ctx), ptr::null_mut(), 0, 0);
+                let ll_fn = LLVMAddFunction(module.0, name_cstr.as_ptr(), fn_ty);
+                let bb = LLVMAppendBasicBlockInContext(ll_ctx, ll_fn, name_cstr.as_ptr());
+                let builder = LLVMCreateBuilderInContext(ll_ctx);
+                LLVMPositionBuilderAtEnd(builder, bb);
+                let ret = LLVMBuildRetVoid(builder);
+                LLVMDisposeBuilder(builder);
+                LLVMSetSubprogram(ll_fn, outer);
+
+                let call_in_outer =
+                    LLVMDIBuilderCreateDebugLocation(ll_ctx, 2, 5, outer, ptr::null_mut());
+                let call_in_middle = di_builder
+                    .inlined_location(11, 9, middle, call_in_outer)
+                    .unwrap();
+                let location = di_builder.inlined_location(21, 13, inner, call_in_middle);
+                LLVMInstructionSetDebugLoc(ret, location.unwrap());
+            }
+            di_builder.finalize();
+            module.verify();
+
+            let module_info = print_module_to_str(&module.0);
+            let metadata = |id: &str| -> &str {
+                module_info
+                    .lines()
+                    .find_map(|line| line.strip_prefix(&format!("{id} = ")))
+                    .expect("metadata node")
+            };
+            let inlined_at = |location: &str| -> Option<String> {
+                location
+                    .split("inlinedAt: ")
+                    .nth(1)
+                    .map(|rest| rest.trim_end_matches(')').to_string())
+            };
+            let ret_line = module_info
+                .lines()
+                .find(|line| line.contains("ret void, !dbg "))
+                .expect("ret");
+            let location = metadata(ret_line.split("!dbg ").nth(1).unwrap());
+            assert!(location.starts_with("!DILocation(line: 21, column: 13,"));
+            let location = metadata(&inlined_at(location).expect("inlined into middle"));
+            assert!(location.starts_with("!DILocation(line: 11, column: 9,"));
+            let location = metadata(&inlined_at(location).expect("inlined into outer"));
+            assert!(location.starts_with("!DILocation(line: 2, column: 5,"));
+            assert_eq!(inlined_at(location), None);
+        });
+    }
+
+    #[test]
+    fn test_debug_sections_are_strippable() {
+        use crate::stackless::{Target, TargetPlatform};
+        use llvm_sys::object::*;
+
+        // DWARF lowers to the standard .debug_* sections, and their relocations, only. These are
+        // what 'strip --strip-debug' and 'llvm-strip' remove, so stripping leaves the code intact.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let tgt_platform = TargetPlatform::Solana;
+            let llmachine = Target::from_triple(tgt_platform.triple())
+                .unwrap()
+                .create_target_machine(
+                    tgt_platform.triple(),
+                    tgt_platform.llvm_cpu(),
+                    tgt_platform.llvm_features(),
+                    "none",
+                );
+            let module = g_ctx.llvm_cx.create_module("strippable");
+            module.set_target(tgt_platform.triple());
+            module.set_data_layout(&llmachine);
+            let di_builder = DIBuilder::new(
+                g_ctx,
+                &module,
+                source,
+                DebugInfo::Full,
+                &Options {
+                    debug_names: true,
+                    ..Options::default()
+                },
+            );
+            unsafe {
+                // fun f() {}, with a subprogram and a location for its return.
+                let ll_ctx = g_ctx.llvm_cx.0;
+                let name_cstr = to_cstring!("f");
+                let fn_ty = LLVMFunctionType(LLVMVoidTypeInContext(ll_ctx), ptr::null_mut(), 0, 0);
+                let ll_fn = LLVMAddFunction(module.0, name_cstr.as_ptr(), fn_ty);
+                let bb = LLVMAppendBasicBlockInContext(ll_ctx, ll_fn, name_cstr.as_ptr());
+                let builder = LLVMCreateBuilderInContext(ll_ctx);
+                LLVMPositionBuilderAtEnd(builder, bb);
+                let ret = LLVMBuildRetVoid(builder);
+                LLVMDisposeBuilder(builder);
+
+                let builder_ref = di_builder.builder_ref().unwrap();
+                let builder_file = di_builder.builder_file().unwrap();
+                let subroutine_ty = LLVMDIBuilderCreateSubroutineType(
+                    builder_ref,
+                    builder_file,
+                    ptr::null_mut(),
+                    0,
+                    LLVMDIFlagZero,
+                );
+                let function = LLVMDIBuilderCreateFunction(
+                    builder_ref,
+                    builder_file,
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    name_cstr.as_ptr(),
+                    name_cstr.as_bytes().len(),
+                    builder_file,
+                    1,
+                    subroutine_ty,
+                    0,
+                    1,
+                    1,
+                    LLVMDIFlagZero,
+                    0,
+                );
+                LLVMSetSubprogram(ll_fn, function);
+                let location =
+                    LLVMDIBuilderCreateDebugLocation(ll_ctx, 1, 1, function, ptr::null_mut());
+                LLVMInstructionSetDebugLoc(ret, location);
+            }
+            di_builder.finalize();
+            module.verify();
+
+            let dir = tempfile::tempdir().unwrap();
+            let obj_file = dir.path().join("strippable.o");
+            let obj_file = obj_file.to_str().unwrap();
+            llmachine.emit_to_obj_file(&module, obj_file).unwrap();
+
+            let mut sections = vec![];
+            unsafe {
+                let path_cstr = to_cstring!(obj_file);
+                let mut mem_buf = ptr::null_mut();
+                let mut message = ptr::null_mut();
+                let failed = LLVMCreateMemoryBufferWithContentsOfFile(
+                    path_cstr.as_ptr(),
+                    &mut mem_buf,
+                    &mut message,
+                );
+                assert_eq!(failed, 0, "cannot read the object file");
+                let binary = LLVMCreateBinary(mem_buf, g_ctx.llvm_cx.0, &mut message);
+                assert!(!binary.is_null(), "cannot parse the object file");
+                let section = LLVMObjectFileCopySectionIterator(binary);
+                while LLVMObjectFileIsSectionIteratorAtEnd(binary, section) == 0 {
+                    let name = LLVMGetSectionName(section);
+                    if !name.is_null() {
+                        sections.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+                    }
+                    LLVMMoveToNextSection(section);
+                }
+                LLVMDisposeSectionIterator(section);
+                LLVMDisposeBinary(binary);
+                LLVMDisposeMemoryBuffer(mem_buf);
+            }
+
+            assert!(sections.iter().any(|s| s == ".text"));
+            for debug_section in [".debug_info", ".debug_line", ".debug_abbrev"] {
+                assert!(sections.iter().any(|s| s == debug_section), "{sections:?}");
+            }
+            for section in sections.iter().filter(|s| s.contains("debug")) {
+                let section = section
+                    .strip_prefix(".rela")
+                    .or_else(|| section.strip_prefix(".rel"))
+                    .unwrap_or(section);
+                assert!(section.starts_with(".debug_"), "{section}");
+            }
+            assert!(!sections.iter().any(|s| s == ".debug_gdb_scripts"));
+        });
+    }
+
+    #[test]
+    fn test_field_decl_file() {
+        // Declarations from another file of the package than the module being built refer to
+        // their own file, created once, rather than to the module's source.
+        let files = [
+            ("a.move", "module 0x101::a {}"),
+            ("b.move", "module 0x101::b {\n    struct S { n: u64 }\n}\n"),
+        ];
+        with_test_global_ctx_files(&files, |g_ctx, sources| {
+            let env = g_ctx.env;
+            let struct_env = env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("b::S"))
+                .expect("struct S");
+            let field = struct_env.get_fields().next().expect("field n");
+            let (filename, location) = env
+                .get_file_and_location(&field.get_loc().expect("field location"))
+                .unwrap();
+            assert!(filename.ends_with("b.move"));
+            assert_eq!(location.line.0, 1);
+
+            let module = g_ctx.llvm_cx.create_module("field_decl_file");
+            let di_builder = DIBuilder::new(
+                g_ctx,
+                &module,
+                &sources[0],
+                DebugInfo::Full,
+                &Options::default(),
+            );
+            let builder_file = di_builder.builder_file().unwrap();
+            assert_eq!(
+                di_builder.get_or_create_file(&sources[0]),
+                Some(builder_file)
+            );
+            let fld_file = di_builder.get_or_create_file(&filename).unwrap();
+            assert_ne!(fld_file, builder_file);
+            assert_eq!(di_builder.get_or_create_file(&filename), Some(fld_file));
+
+            add_test_metadata(g_ctx, &module, fld_file);
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+            assert!(module_info.contains("!DIFile(filename: \"b.move\""));
+        });
+    }
+
+    #[test]
+    fn test_abilities_member() {
+        // Structs carry their abilities in a constant '__abilities' member: copy | drop is 0x3.
+        let code = "module 0x101::m {\n    struct S has copy, drop { n: u64 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let struct_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::S"))
+                .expect("struct S");
+            let abilities = struct_env.get_abilities();
+            assert_eq!(abilities.into_u8(), 0x3);
+
+            let module = g_ctx.llvm_cx.create_module("abilities_member");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let builder_file = di_builder.builder_file().unwrap();
+            let member =
+                di_builder.create_abilities_member(builder_file, builder_file, 2, abilities);
+            add_test_metadata(g_ctx, &module, member);
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+            let member = module_info
+                .lines()
+                .find(|line| line.contains("name: \"__abilities\""))
+                .expect("member __abilities");
+            assert!(member.contains("DIFlagArtificial"));
+            assert!(member.contains("DIFlagStaticMember"));
+            assert!(member.contains("extraData: i8 3"));
+        });
+    }
+
+    #[test]
+    fn test_integer_types_unsigned() {
+        // Move integers are unsigned, so no basic type may be signed: a debugger would show values
+        // with the high bit set as negative.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("integer_types_unsigned");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let integer_types = [
+                ("u8", mty::PrimitiveType::U8),
+                ("u16", mty::PrimitiveType::U16),
+                ("u32", mty::PrimitiveType::U32),
+                ("u64", mty::PrimitiveType::U64),
+                ("u128", mty::PrimitiveType::U128),
+                ("u256", mty::PrimitiveType::U256),
+            ];
+            for (name, ty) in &integer_types {
+                let ty = di_builder.get_type(mty::Type::Primitive(*ty), &name.to_string());
+                add_test_metadata(g_ctx, &module, ty);
+            }
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+
+            for (name, _) in integer_types {
+                let basic_type = module_info
+                    .lines()
+                    .find(|line| line.contains(&format!("!DIBasicType(name: \"{name}\",")))
+                    .expect("integer basic type");
+                assert!(
+                    basic_type.contains("encoding: DW_ATE_unsigned)"),
+                    "{basic_type}"
+                );
+            }
+            assert!(!module_info.contains("DW_ATE_signed"));
+        });
+    }
+
+    #[test]
+    fn test_producer_build_id() {
+        // A build id given to DIBuilder::new is recorded in the producer of the compile unit.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module_info = |build_id: &str| {
+                let module = g_ctx.llvm_cx.create_module("producer_build_id");
+                let di_builder = DIBuilder::new(
+                    g_ctx,
+                    &module,
+                    source,
+                    DebugInfo::Full,
+                    &Options {
+                        build_id: build_id.to_string(),
+                        ..Options::default()
+                    },
+                );
+                assert_eq!(di_builder.producer(), Some(producer_name(build_id)));
+                di_builder.finalize();
+                print_module_to_str(&module.0)
+            };
+
+            assert!(module_info("0a1b2c3").contains("producer: \"move-mv-llvm-compiler 0a1b2c3\""));
+            assert!(module_info("").contains("producer: \"move-mv-llvm-compiler\""));
+        });
+    }
+
+    #[test]
+    fn test_checked_member_align() {
+        // A computed alignment of zero, or of a non-power of two, is replaced by the natural one.
+        assert_eq!(checked_member_align("S.f", 64, 32), 64);
+        assert_eq!(checked_member_align("S.f", 0, 64), 64);
+        assert_eq!(checked_member_align("S.f", 24, 32), 32);
+        assert_eq!(checked_member_align("S.f", 0, 0), 8);
+    }
+
+    #[test]
+    fn test_verify_debug_info() {
+        // A module with correctly emitted struct debug info passes the verifier.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("verify_debug_info");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+            let fields = [
+                ("a".to_string(), u64_ty.clone(), 0),
+                ("b".to_string(), mty::Type::Vector(Box::new(u64_ty)), 8),
+            ];
+            let struct_meta = di_builder.create_anonymous_struct("env", &fields, 32, 8, true);
+            add_test_metadata(g_ctx, &module, struct_meta);
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+        });
+    }
+
+    #[test]
+    fn test_fieldless_struct() {
+        // Marker structs have no members, but still a valid composite type with their size.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("fieldless_struct");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let struct_meta = di_builder.create_anonymous_struct("marker", &[], 1, 1, false);
+            assert!(!struct_meta.is_null());
+            add_test_metadata(g_ctx, &module, struct_meta);
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            let composite = module_info
+                .lines()
+                .find(|line| {
+                    line.contains("!DICompositeType(") && line.contains("name: \"marker\"")
+                })
+                .expect("composite type marker");
+            assert!(composite.contains("size: 8"));
+            let elements = composite
+                .split("elements: ")
+                .nth(1)
+                .and_then(|rest| rest.split(|c| c == ',' || c == ')').next())
+                .expect("elements");
+            assert!(module_info.contains(&format!("{elements} = !{{}}")));
+        });
+    }
+
+    #[test]
+    fn test_tuple_return_per_function() {
+        // Two functions returning (u64, u64) get a tuple type each, identified by the function.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("tuple_return");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+            let tys = [u64_ty.clone(), u64_ty];
+            let fn_names = ["0x101::m::f", "0x101::m::g"];
+            for fn_name in fn_names {
+                let tuple = di_builder.create_tuple_return_type(fn_name, &tys);
+                assert_eq!(unsafe { LLVMDITypeGetSizeInBits(tuple) }, 128);
+                add_test_metadata(g_ctx, &module, tuple);
+            }
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            let tuples: Vec<&str> = module_info
+                .lines()
+                .filter(|line| {
+                    line.contains("!DICompositeType(") && line.contains("name: \"tuple\"")
+                })
+                .collect();
+            assert_eq!(tuples.len(), 2);
+            for (tuple, fn_name) in tuples.iter().zip(fn_names) {
+                assert!(tuple.contains("DIFlagArtificial"));
+                let identifier = tuple.split("identifier: ").nth(1).expect("identifier");
+                assert!(identifier.contains(fn_name));
+            }
+        });
+    }
+
+    #[test]
+    fn test_dwarf_version_flag() {
+        // A pinned DWARF version is recorded as the module flag, also over the one for .debug_names.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module_info = |debug_names: bool, dwarf_version: Option<u32>| {
+                let module = g_ctx.llvm_cx.create_module("dwarf_version");
+                let di_builder = DIBuilder::new(
+                    g_ctx,
+                    &module,
+                    source,
+                    DebugInfo::Full,
+                    &Options {
+                        debug_names,
+                        dwarf_version,
+                        ..Options::default()
+                    },
+                );
+                di_builder.finalize();
+                print_module_to_str(&module.0)
+            };
+            for version in [4, 5] {
+                let module_info = module_info(false, Some(version));
+                assert!(module_info.contains(&format!("!\"Dwarf Version\", i32 {version}}}")));
+                assert_eq!(module_info.matches("!\"Debug Info Version\"").count(), 1);
+            }
+            let module_info_4 = module_info(true, Some(4));
+            assert_eq!(module_info_4.matches("!\"Dwarf Version\"").count(), 1);
+            assert!(module_info_4.contains("!\"Dwarf Version\", i32 4}"));
+            assert!(!module_info(false, None).contains("!\"Dwarf Version\""));
+        });
+    }
+
+    #[test]
+    fn test_lowered_field_position() {
+        // Members are placed at the offsets of the lowered struct, padding included, not packed
+        // one after the other in declaration order.
+        let code = "module 0x101::m {\n    struct S { a: u8, b: u64, c: u8 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, _source| {
+            let struct_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::S"))
+                .expect("struct S");
+            let module = g_ctx.llvm_cx.create_module("lowered_field_position");
+            let data_layout = module.get_module_data_layout();
+            let struct_type = g_ctx.llvm_cx.create_opaque_named_struct("struct.m__S");
+            let i8_ty = g_ctx.llvm_cx.int_type(8);
+            struct_type.set_struct_body(&[i8_ty, g_ctx.llvm_cx.int_type(64), i8_ty]);
+
+            let positions: Vec<(usize, u64)> = struct_env
+                .get_fields()
+                .map(|field| lowered_field_position(&struct_type, data_layout, &field))
+                .collect();
+            let b_offset_in_bits = struct_type.offset_of_element(data_layout, 1) as u64 * 8;
+            assert!(b_offset_in_bits > 8);
+            assert_eq!(positions[0], (0, 0));
+            assert_eq!(positions[1], (1, b_offset_in_bits));
+            assert_eq!(positions[2], (2, b_offset_in_bits + 64));
+        });
+    }
+
+    #[test]
+    fn test_struct_from_model() {
+        // A struct without an LLVM type is laid out from its Move field types.
+        let code = "module 0x101::m {\n    struct S { a: u8, b: u64 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let struct_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::S"))
+                .expect("struct S");
+            assert!(g_ctx.llvm_cx.named_struct_type("struct.m__S").is_none());
+
+            let module = g_ctx.llvm_cx.create_module("struct_from_model");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let struct_meta = di_builder.create_struct_from_model(&struct_env, &[], "struct.m__S");
+            // 'b' follows 'a' at the alignment of a u64 in the data layout.
+            let data_layout = module.get_module_data_layout();
+            let b_offset_in_bits = g_ctx
+                .llvm_cx
+                .int_type(64)
+                .abi_alignment_of_type(data_layout) as u64
+                * 8;
+            assert_eq!(
+                unsafe { LLVMDITypeGetSizeInBits(struct_meta) },
+                b_offset_in_bits + 64
+            );
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            let member_b = module_info
+                .lines()
+                .find(|line| line.contains("DW_TAG_member, name: \"b\""))
+                .expect("member b");
+            assert!(member_b.contains(&format!("offset: {b_offset_in_bits}")));
+        });
+    }
+
+    #[test]
+    fn test_generic_struct_instantiations() {
+        // Each instantiation of a generic struct has its own members, of the type arguments' types.
+        let code = "module 0x101::m {\n    struct S { a: u8 }\n    struct Box<T> { v: T }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let find_struct = |name: &str| {
+                g_ctx
+                    .env
+                    .get_modules()
+                    .flat_map(|m| m.into_structs())
+                    .find(|s| s.get_full_name_str().ends_with(name))
+                    .expect("struct")
+            };
+            let s_env = find_struct("m::S");
+            let box_env = find_struct("m::Box");
+            let mod_id = box_env.module_env.get_id();
+            let s_ty = mty::Type::Struct(mod_id, s_env.get_id(), vec![]);
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+
+            let module = g_ctx.llvm_cx.create_module("generic_struct_instantiations");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let s_meta = di_builder.create_struct_from_model(&s_env, &[], "struct.m__S");
+            let box_u64_meta = di_builder.create_struct_from_model(
+                &box_env,
+                &[u64_ty.clone()],
+                "struct.m__Box_u64_",
+            );
+            let box_s_meta = di_builder.create_struct_from_model(
+                &box_env,
+                &[s_ty.clone()],
+                "struct.m__Box_m__S_",
+            );
+            assert!(!s_meta.is_null());
+            assert_ne!(box_u64_meta, box_s_meta);
+            assert_eq!(unsafe { LLVMDITypeGetSizeInBits(box_u64_meta) }, 64);
+            assert_eq!(unsafe { LLVMDITypeGetSizeInBits(box_s_meta) }, 8);
+
+            let name = "v".to_string();
+            let box_ty = |ty: mty::Type| mty::Type::Struct(mod_id, box_env.get_id(), vec![ty]);
+            assert_eq!(di_builder.get_type(box_ty(u64_ty), &name), box_u64_meta);
+            assert_eq!(di_builder.get_type(box_ty(s_ty), &name), box_s_meta);
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            let member_types: Vec<&str> = module_info
+                .lines()
+                .filter(|line| line.contains("DW_TAG_member, name: \"v\""))
+                .collect();
+            assert_eq!(member_types.len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_finalize_in_cloned_module() {
+        // A clone taken before finalize is not the module holding the debug info.
+        let code = "module 0x101::m {\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("finalize_in_cloned_module");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let early_clone = module.clone_module();
+            assert!(!di_builder.is_emitted_into(&early_clone));
+            assert!(di_builder.is_emitted_into(&module));
+            di_builder.finalize_in(&module);
+            assert!(di_builder.verify().is_ok());
+
+            // A clone taken after finalize carries the compile unit.
+            let late_clone = module.clone_module();
+            let clone_info = print_module_to_str(&late_clone.0);
+            assert!(clone_info.contains("!llvm.dbg.cu"));
+            assert!(clone_info.contains("DICompileUnit"));
+        });
+    }
+
+    #[test]
+    fn test_struct_reference_parameter() {
+        // A resource taken by '&mut' is a pointer to the struct composite, also before the struct
+        // is packed anywhere in the module.
+        let code = "module 0x101::m {\n    struct Coin has key { value: u64 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let struct_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::Coin"))
+                .expect("struct Coin");
+            let coin_ty =
+                mty::Type::Struct(struct_env.module_env.get_id(), struct_env.get_id(), vec![]);
+            let param_ty = mty::Type::Reference(true, Box::new(coin_ty));
+
+            let module = g_ctx.llvm_cx.create_module("struct_reference_parameter");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let param_meta = di_builder.create_parameter_type(&param_ty, &"coin".to_string());
+            add_test_metadata(g_ctx, &module, param_meta);
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            let pointer = module_info
+                .lines()
+                .find(|line| line.contains("tag: DW_TAG_pointer_type, name: \"&mut "))
+                .expect("pointer type");
+            let id = pointer
+                .split("baseType: ")
+                .nth(1)
+                .expect("baseType")
+                .split(|c| c == ',' || c == ')')
+                .next()
+                .unwrap();
+            let pointee = module_info
+                .lines()
+                .find(|line| line.starts_with(&format!("{id} = ")))
+                .expect("pointee node");
+            assert!(pointee.contains("tag: DW_TAG_structure_type"));
+            assert!(pointee.contains("Coin"));
+        });
+    }
+
+    #[test]
+    fn test_struct_name_has_address() {
+        // Structs are named as in Move, with the address of their module.
+        let code = "module 0x101::m {\n    struct S { a: u8 }\n    struct Box<T> { v: T }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let find_struct = |name: &str| {
+                g_ctx
+                    .env
+                    .get_modules()
+                    .flat_map(|m| m.into_structs())
+                    .find(|s| s.get_full_name_str().ends_with(name))
+                    .expect("struct")
+            };
+            let s_env = find_struct("m::S");
+            let box_env = find_struct("m::Box");
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+            assert_eq!(s_env.di_display_name(&[]), "0x101::m::S");
+            assert_eq!(
+                box_env.di_display_name(&[u64_ty.clone()]),
+                "0x101::m::Box<u64>"
+            );
+
+            let module = g_ctx.llvm_cx.create_module("struct_name_has_address");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            di_builder.create_struct_from_model(&s_env, &[], "struct.m__S");
+            di_builder.create_struct_from_model(&box_env, &[u64_ty], "struct.m__Box_u64_");
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            assert!(module_info.contains("DW_TAG_structure_type, name: \"0x101::m::S\""));
+            assert!(module_info.contains("DW_TAG_structure_type, name: \"0x101::m::Box<u64>\""));
+        });
+    }
+
+    #[test]
+    fn test_export_type_index() {
+        // The exported JSON holds the fields of each described struct with their offsets and sizes.
+        let code = "module 0x101::m {\n    struct S { a: u8, b: u64 }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let struct_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::S"))
+                .expect("struct S");
+            let module = g_ctx.llvm_cx.create_module("export_type_index");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            di_builder.create_struct_from_model(&struct_env, &[], "struct.m__S");
+            di_builder.finalize();
+
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("types.json");
+            di_builder
+                .export_type_index(path.to_str().unwrap())
+                .unwrap();
+            let index: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+            let b_offset = g_ctx
+                .llvm_cx
+                .int_type(64)
+                .abi_alignment_of_type(module.get_module_data_layout())
+                as u64;
+            let layout = &index["0x101::m::S"];
+            assert_eq!(layout["size"], b_offset + 8);
+            let fields = layout["fields"].as_array().expect("fields");
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0]["name"], "a");
+            assert_eq!(fields[0]["offset"], 0);
+            assert_eq!(fields[0]["size"], 1);
+            assert_eq!(fields[1]["name"], "b");
+            assert_eq!(fields[1]["offset"], b_offset);
+            assert_eq!(fields[1]["size"], 8);
+        });
+    }
+
+    #[test]
+    fn test_export_type_index_of_packed_struct() {
+        // A struct with an LLVM type, described when it is packed, is aligned in bytes in the
+        // index and in bits in DWARF, as any other struct.
+        let code = "module 0x101::m {\n    struct S has drop { a: u8, b: u64 }\n    public fun make(): S { S { a: 1, b: 2 } }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            use crate::stackless::{entrypoint::EntrypointGenerator, Target, TargetPlatform};
+
+            let tgt_platform = TargetPlatform::Solana;
+            let llmachine = Target::from_triple(tgt_platform.triple())
+                .unwrap()
+                .create_target_machine(
+                    tgt_platform.triple(),
+                    tgt_platform.llvm_cpu(),
+                    tgt_platform.llvm_features(),
+                    "none",
+                );
+            let options = Options {
+                debug: true,
+                ..Options::default()
+            };
+            let entry_llmod = g_ctx.llvm_cx.create_module("solana_entrypoint");
+            let entrypoint_generator =
+                EntrypointGenerator::new(g_ctx, &entry_llmod, &llmachine, &options);
+            let m_env = g_ctx.env.get_modules().next().expect("module m");
+            let llmod = g_ctx
+                .llvm_cx
+                .create_module("export_type_index_of_packed_struct");
+            let mut mod_cx = g_ctx.create_module_context(
+                m_env.get_id(),
+                &llmod,
+                &entrypoint_generator,
+                &options,
+                source,
+            );
+            mod_cx.translate();
+
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("types.json");
+            mod_cx
+                .llvm_di_builder
+                .export_type_index(path.to_str().unwrap())
+                .unwrap();
+            let index: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+            let align = g_ctx
+                .llvm_cx
+                .int_type(64)
+                .preferred_alignment_of_type(llmod.get_module_data_layout())
+                as u64;
+            let layout = &index["0x101::m::S"];
+            assert_eq!(layout["align"], align);
+            assert_eq!(layout["size"], align + 8);
+
+            let module_info = print_module_to_str(&llmod.0);
+            let composite = module_info
+                .lines()
+                .find(|line| line.contains("DW_TAG_structure_type, name: \"0x101::m::S\""))
+                .expect("composite type S");
+            assert!(
+                composite.contains(&format!("align: {}", align * 8)),
+                "{composite}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_pointer_alignment() {
+        // A pointer is aligned as the data layout says, which may be less than its size.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("pointer_alignment");
+            let layout_cstr = to_cstring!("e-p:64:32");
+            unsafe { llvm_sys::core::LLVMSetDataLayout(module.0, layout_cstr.as_ptr()) };
+            let data_layout = module.get_module_data_layout();
+            assert_eq!(data_layout.pointer_size_in_bits(), 64);
+
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            assert_eq!(di_builder.pointer_align_in_bits(data_layout), 32);
+
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+            let ref_meta = di_builder.get_type(
+                mty::Type::Reference(false, Box::new(u64_ty)),
+                &"r".to_string(),
+            );
+            add_test_metadata(g_ctx, &module, ref_meta);
+            di_builder.finalize();
+            let module_info = print_module_to_str(&module.0);
+            let pointer = module_info
+                .lines()
+                .find(|line| line.contains("tag: DW_TAG_pointer_type, name: \"&u64\""))
+                .expect("pointer type");
+            assert!(pointer.contains("size: 64, align: 32"));
+        });
+    }
+
+    #[test]
+    fn test_struct_instantiation_identifier() {
+        // Each instantiation's identifier names its type arguments, marking those of phantom parameters.
+        let code = "module 0x101::m {\n    struct Box<phantom P, T> { v: T }\n}\n";
+        with_test_global_ctx(code, |g_ctx, source| {
+            let box_env = g_ctx
+                .env
+                .get_modules()
+                .flat_map(|m| m.into_structs())
+                .find(|s| s.get_full_name_str().ends_with("m::Box"))
+                .expect("struct");
+            let u8_ty = mty::Type::Primitive(mty::PrimitiveType::U8);
+            let u16_ty = mty::Type::Primitive(mty::PrimitiveType::U16);
+            let u64_ty = mty::Type::Primitive(mty::PrimitiveType::U64);
+
+            let module = g_ctx
+                .llvm_cx
+                .create_module("struct_instantiation_identifier");
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let box_u8_meta = di_builder.create_struct_from_model(
+                &box_env,
+                &[u8_ty, u64_ty.clone()],
+                "struct.m__Box_u8_u64_",
+            );
+            let box_u16_meta = di_builder.create_struct_from_model(
+                &box_env,
+                &[u16_ty, u64_ty],
+                "struct.m__Box_u16_u64_",
+            );
+            assert_ne!(box_u8_meta, box_u16_meta);
+            di_builder.finalize();
+            assert!(di_builder.verify().is_ok());
+
+            let module_info = print_module_to_str(&module.0);
+            assert!(module_info.contains("identifier: \"0x101::m::Box<phantom u8, u64>\""));
+            assert!(module_info.contains("identifier: \"0x101::m::Box<phantom u16, u64>\""));
+        });
+    }
+
+    #[test]
+    fn test_di_module_target_matches_code_module() {
+        // Debug info sizes are computed against the layout the code is generated for.
+        with_test_global_ctx("module 0x101::m {}", |g_ctx, source| {
+            let module = g_ctx.llvm_cx.create_module("di_module_target");
+            module.set_target("sbf-solana-solana");
+            let layout_cstr = to_cstring!("e-m:e-p:64:64-i64:64-n32:64-S128");
+            unsafe { LLVMSetDataLayout(module.0, layout_cstr.as_ptr()) };
+
+            let di_builder =
+                DIBuilder::new(g_ctx, &module, source, DebugInfo::Full, &Options::default());
+            let module_di = di_builder.module_di().expect("DI module");
+            let triple = |m: LLVMModuleRef| unsafe { CStr::from_ptr(LLVMGetTarget(m)).to_owned() };
+            let layout =
+                |m: LLVMModuleRef| unsafe { CStr::from_ptr(LLVMGetDataLayoutStr(m)).to_owned() };
+            assert_eq!(triple(module_di), triple(module.0));
+            assert_eq!(layout(module_di), layout(module.0));
+            di_builder.finalize();
+        });
+    }
+}
@@ -4,7 +4,10 @@
 
 //! Extension traits for foreign types.
 
-use crate::stackless::llvm;
+use crate::{
+    options::Options,
+    stackless::{dwarf::DebugInfo, llvm},
+};
 use extension_trait::extension_trait;
 use move_binary_format::file_format::SignatureToken;
 use move_core_types::account_address;
@@ -16,6 +19,26 @@ pub impl<'a> ModuleEnvExt for mm::ModuleEnv<'a> {
     fn llvm_module_name(&self) -> String {
         self.get_full_name_str().replace(':', "_")
     }
+
+    /// Whether to create debug information for this module: with `-g` or
+    /// `--line-tables-only`, unless it is a dependency and
+    /// `--no-debug-dependencies` is given.
+    ///
+    /// Without it the module gets an empty `DIBuilder`, which emits nothing.
+    fn should_emit_debug_info(&self, options: &Options) -> bool {
+        (options.debug || options.line_tables_only)
+            && (self.is_target() || !options.no_debug_dependencies)
+    }
+
+    fn debug_info(&self, options: &Options) -> DebugInfo {
+        if !self.should_emit_debug_info(options) {
+            DebugInfo::None
+        } else if options.line_tables_only {
+            DebugInfo::LineTablesOnly
+        } else {
+            DebugInfo::Full
+        }
+    }
 }
 
 #[extension_trait]
@@ -111,6 +134,34 @@ pub impl<'a> FunctionEnvExt for mm::FunctionEnv<'a> {
         symbol
     }
 
+    /// The name debuggers show for this function instantiated with `tyvec`,
+    /// e.g. `0x101::foo::a<bool>`, as opposed to its mangled symbol name.
+    fn di_display_name(&self, tyvec: &[mty::Type]) -> String {
+        let name = format!(
+            "{}::{}",
+            self.module_env.get_full_name_str(),
+            self.get_name_str()
+        );
+        if tyvec.is_empty() {
+            return name;
+        }
+        let type_names: Vec<String> = tyvec
+            .iter()
+            .map(|ty| ty.display(&self.get_type_display_ctx()).to_string())
+            .collect();
+        format!("{name}<{}>", type_names.join(", "))
+    }
+
+    /// Whether to create debug information for this function, when its
+    /// module has it: unless `--debug-function` names other functions only.
+    fn should_emit_debug_info(&self, options: &Options) -> bool {
+        options.debug_functions.is_empty()
+            || options
+                .debug_functions
+                .iter()
+                .any(|name| *name == self.di_display_name(&[]) || *name == self.get_full_name_str())
+    }
+
     /// Entry points follow their own naming convention
     fn llvm_symbol_name_entrypoint(&self) -> String {
         self.get_full_name_str().replace(':', "_")
@@ -206,6 +257,45 @@ pub impl<'a> StructEnvExt for mm::StructEnv<'a> {
         format!("struct.{}", xs)
     }
 
+    /// The name debuggers show for this struct instantiated with `tys`,
+    /// e.g. `0x101::m::Box<u64>`, prefixed with its module's address.
+    fn di_display_name(&self, tys: &[mty::Type]) -> String {
+        let name = self.get_full_name_with_address();
+        if tys.is_empty() {
+            return name;
+        }
+        let type_display_ctx = self.module_env.env.get_type_display_ctx();
+        let type_names: Vec<String> = tys
+            .iter()
+            .map(|ty| ty.display(&type_display_ctx).to_string())
+            .collect();
+        format!("{name}<{}>", type_names.join(", "))
+    }
+
+    /// Records how a generic struct was instantiated, for auditing monomorphized
+    /// types: its display name with each type argument of a phantom parameter
+    /// marked, e.g. `0x101::m::Coin<phantom 0x101::m::USD, u64>`.
+    fn di_instantiation_id(&self, tys: &[mty::Type]) -> String {
+        let name = self.get_full_name_with_address();
+        if tys.is_empty() {
+            return name;
+        }
+        let type_display_ctx = self.module_env.env.get_type_display_ctx();
+        let type_args: Vec<String> = tys
+            .iter()
+            .enumerate()
+            .map(|(idx, ty)| {
+                let ty_name = ty.display(&type_display_ctx).to_string();
+                if self.is_phantom_parameter(idx) {
+                    format!("phantom {ty_name}")
+                } else {
+                    ty_name
+                }
+            })
+            .collect();
+        format!("{name}<{}>", type_args.join(", "))
+    }
+
     fn struct_raw_type_name(&self, tys: &[mty::Type]) -> String {
         let qid = self.get_qualified_id();
         let s = mty::Type::Struct(qid.module_id, qid.id, tys.to_vec());
@@ -280,3 +370,175 @@ fn test_symbol_name() {
 
     assert_eq!(symbol, "0000000000000101_foo_a_7JBHPr3AYTvPmP");
 }
+
+#[test]
+fn test_di_display_name() {
+    use move_compiler::shared::PackagePaths;
+    use move_core_types::{
+        account_address::AccountAddress,
+        identifier::{IdentStr, Identifier},
+        language_storage::ModuleId,
+    };
+    use move_model::run_model_builder;
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    let model = {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let manifest_dir = PathBuf::from(manifest_dir);
+        let move_path = "../../tools/move-mv-llvm-compiler/tests/rbpf-tests/call-local.move";
+        let move_path = manifest_dir.join(move_path);
+
+        let named_address_map: BTreeMap<String, _> = BTreeMap::new();
+
+        let sources = vec![PackagePaths {
+            name: None,
+            paths: vec![move_path.to_string_lossy().to_string()],
+            named_address_map,
+        }];
+
+        run_model_builder(sources, vec![]).unwrap()
+    };
+
+    let fun = model
+        .find_function_by_language_storage_id_name(
+            &ModuleId::new(
+                AccountAddress::from_hex_literal("0x101").unwrap(),
+                Identifier::new("foo").unwrap(),
+            ),
+            IdentStr::new("a").unwrap(),
+        )
+        .unwrap();
+
+    // The subprogram's display name is the readable instantiation, the linkage name the symbol.
+    let tyvec = [
+        mty::Type::Primitive(mty::PrimitiveType::Bool),
+        mty::Type::Vector(Box::new(mty::Type::Primitive(mty::PrimitiveType::U8))),
+    ];
+    let display_name = fun.di_display_name(&tyvec);
+    let symbol = fun.llvm_symbol_name_full(&tyvec);
+    assert_eq!(display_name, "0x101::foo::a<bool, vector<u8>>");
+    assert_ne!(display_name, symbol);
+    assert_eq!(fun.di_display_name(&[]), "0x101::foo::a");
+}
+
+#[test]
+fn test_should_emit_debug_info() {
+    use move_compiler::shared::PackagePaths;
+    use move_model::run_model_builder;
+    use std::collections::BTreeMap;
+
+    let model = {
+        let dir = tempfile::tempdir().unwrap();
+        let app_path = dir.path().join("app.move");
+        let lib_path = dir.path().join("lib.move");
+        std::fs::write(
+            &app_path,
+            "module 0x101::app { use 0x102::lib; public fun one(): u64 { lib::one() } }",
+        )
+        .unwrap();
+        std::fs::write(
+            &lib_path,
+            "module 0x102::lib { public fun one(): u64 { 1 } }",
+        )
+        .unwrap();
+
+        let package = |path: std::path::PathBuf| PackagePaths {
+            name: None,
+            paths: vec![path.to_string_lossy().to_string()],
+            named_address_map: BTreeMap::<String, _>::new(),
+        };
+        run_model_builder(vec![package(app_path)], vec![package(lib_path)]).unwrap()
+    };
+    let module = |name: &str| {
+        model
+            .get_modules()
+            .find(|m| m.get_full_name_str() == name)
+            .unwrap()
+    };
+    let (app, lib) = (module("0x101::app"), module("0x102::lib"));
+
+    let options = Options {
+        debug: true,
+        ..Options::default()
+    };
+    assert!(app.should_emit_debug_info(&options));
+    assert!(lib.should_emit_debug_info(&options));
+
+    // The dependency is filtered out, so it gets no DI module.
+    let options = Options {
+        debug: true,
+        no_debug_dependencies: true,
+        ..Options::default()
+    };
+    assert!(app.should_emit_debug_info(&options));
+    assert!(!lib.should_emit_debug_info(&options));
+    assert_eq!(app.debug_info(&options), DebugInfo::Full);
+    assert_eq!(lib.debug_info(&options), DebugInfo::None);
+
+    let options = Options {
+        debug: true,
+        line_tables_only: true,
+        ..Options::default()
+    };
+    assert_eq!(app.debug_info(&options), DebugInfo::LineTablesOnly);
+
+    // Line tables don't need -g.
+    let options = Options {
+        line_tables_only: true,
+        ..Options::default()
+    };
+    assert_eq!(app.debug_info(&options), DebugInfo::LineTablesOnly);
+
+    let options = Options::default();
+    assert!(!app.should_emit_debug_info(&options));
+}
+
+#[test]
+fn test_function_should_emit_debug_info() {
+    use move_compiler::shared::PackagePaths;
+    use move_model::run_model_builder;
+    use std::collections::BTreeMap;
+
+    let model = {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m.move");
+        std::fs::write(
+            &path,
+            "module 0x101::m { public fun traced(): u64 { 1 } public fun other(): u64 { 2 } }",
+        )
+        .unwrap();
+        let package = PackagePaths {
+            name: None,
+            paths: vec![path.to_string_lossy().to_string()],
+            named_address_map: BTreeMap::<String, _>::new(),
+        };
+        run_model_builder(vec![package], vec![]).unwrap()
+    };
+    let function = |name: &str| {
+        model
+            .get_modules()
+            .flat_map(|m| m.into_functions())
+            .find(|f| f.get_full_name_str() == name)
+            .unwrap()
+    };
+    let (traced, other) = (function("m::traced"), function("m::other"));
+
+    // Without --debug-function every function of a module with debug info is described.
+    let options = Options {
+        debug: true,
+        ..Options::default()
+    };
+    assert!(traced.should_emit_debug_info(&options));
+    assert!(other.should_emit_debug_info(&options));
+
+    // A function is named with or without its module's address.
+    for name in ["0x101::m::traced", "m::traced"] {
+        let options = Options {
+            debug: true,
+            debug_functions: vec![name.to_string()],
+            ..Options::default()
+        };
+        assert!(traced.should_emit_debug_info(&options));
+        assert!(!other.should_emit_debug_info(&options));
+    }
+}
@@ -38,9 +38,12 @@ pub use llvm_sys::{
     LLVMValue,
 };
 
-use crate::stackless::{
-    dwarf::{from_raw_slice_to_string, DIBuilder},
-    GlobalContext, ModuleContext,
+use crate::{
+    options::Options,
+    stackless::{
+        dwarf::{from_raw_slice_to_string, DIBuilder, DebugInfo},
+        GlobalContext, ModuleContext,
+    },
 };
 
 pub fn initialize_sbf() {
@@ -110,9 +113,21 @@ impl Context {
         g_ctx: &'up GlobalContext,
         module: &Module,
         source: &str,
-        debug: bool,
+        debug_info: DebugInfo,
+        options: &Options,
+    ) -> DIBuilder {
+        DIBuilder::new(g_ctx, module, source, debug_info, options)
+    }
+
+    pub fn create_shared_di_builder<'up>(
+        &'up self,
+        g_ctx: &'up GlobalContext,
+        module: &Module,
+        source: &str,
+        debug_info: DebugInfo,
+        options: &Options,
     ) -> DIBuilder {
-        DIBuilder::new(g_ctx, module, source, debug)
+        DIBuilder::new_shared(g_ctx, module, source, debug_info, options)
     }
 
     pub fn get_anonymous_struct_type(&self, field_tys: &[Type]) -> Type {
@@ -261,6 +276,19 @@ impl Context {
 #[derive(Copy, Clone)]
 pub struct TargetData(LLVMTargetDataRef);
 
+impl TargetData {
+    /// # Safety
+    ///
+    /// `module` must be a live LLVM module.
+    pub unsafe fn of_module(module: LLVMModuleRef) -> TargetData {
+        TargetData(LLVMGetModuleDataLayout(module))
+    }
+
+    pub fn pointer_size_in_bits(&self) -> u64 {
+        unsafe { LLVMPointerSize(self.0) as u64 * 8 }
+    }
+}
+
 #[derive(Debug)]
 pub struct Module(pub LLVMModuleRef);
 
@@ -291,12 +319,24 @@ impl Module {
         }
     }
 
+    // Metadata is copied as it is at the time of the call, so a DIBuilder attached to this module
+    // must be finalized before cloning; see DIBuilder::finalize_in.
+    pub fn clone_module(&self) -> Module {
+        unsafe { Module(LLVMCloneModule(self.0)) }
+    }
+
     pub fn get_module_id(&self) -> String {
         let mut mod_len: ::libc::size_t = 0;
         let mod_ptr = unsafe { LLVMGetModuleIdentifier(self.0, &mut mod_len) };
         from_raw_slice_to_string(mod_ptr, mod_len)
     }
 
+    // Whether a DIBuilder already created a compile unit in this module.
+    pub fn has_compile_unit(&self) -> bool {
+        let name = "llvm.dbg.cu";
+        unsafe { !LLVMGetNamedMetadata(self.0, name.cstr(), name.len()).is_null() }
+    }
+
     pub fn get_module_source(&self) -> String {
         let mut mod_len: ::libc::size_t = 0;
         let mod_ptr = unsafe { LLVMGetSourceFileName(self.0, &mut mod_len) };
@@ -774,10 +814,8 @@ impl Builder {
         }
     }
 
-    pub fn build_br(&self, bb: BasicBlock) {
-        unsafe {
-            LLVMBuildBr(self.0, bb.0);
-        }
+    pub fn build_br(&self, bb: BasicBlock) -> AnyValue {
+        unsafe { AnyValue(LLVMBuildBr(self.0, bb.0)) }
     }
 
     pub fn build_cond_br(&self, cnd_reg: AnyValue, bb0: BasicBlock, bb1: BasicBlock) {
@@ -786,10 +824,16 @@ impl Builder {
         }
     }
 
-    pub fn load_cond_br(&self, ty: Type, val: Alloca, bb0: BasicBlock, bb1: BasicBlock) {
+    pub fn load_cond_br(
+        &self,
+        ty: Type,
+        val: Alloca,
+        bb0: BasicBlock,
+        bb1: BasicBlock,
+    ) -> AnyValue {
         unsafe {
             let cnd_reg = LLVMBuildLoad2(self.0, ty.0, val.0, "cnd".cstr());
-            LLVMBuildCondBr(self.0, cnd_reg, bb0.0, bb1.0);
+            AnyValue(LLVMBuildCondBr(self.0, cnd_reg, bb0.0, bb1.0))
         }
     }
 
@@ -1189,6 +1233,10 @@ impl StructType {
         }
     }
 
+    pub fn is_opaque(&self) -> bool {
+        unsafe { LLVMIsOpaqueStruct(self.0) != 0 }
+    }
+
     pub fn count_struct_element_types(&self) -> usize {
         unsafe { LLVMCountStructElementTypes(self.0) as usize }
     }
@@ -15,7 +15,7 @@ use crate::{
     },
 };
 use codespan::Location;
-use log::debug;
+use log::{debug, error};
 use move_binary_format::file_format::SignatureToken;
 use move_core_types::u256::U256;
 use move_model::{model as mm, ty as mty};
@@ -77,7 +77,12 @@ impl<'mm: 'up, 'up> ModuleContext<'mm, 'up> {
 
         self.llvm_di_builder
             .print_log_unresoled_types(UnresolvedPrintLogLevel::Warning);
-        self.llvm_di_builder.finalize();
+        self.llvm_di_builder.finalize_in(self.llvm_module);
+        if cfg!(debug_assertions) {
+            if let Err(err) = self.llvm_di_builder.verify() {
+                error!(target: "dwarf", "{err:#}");
+            }
+        }
         self.llvm_module.verify();
     }
 
@@ -626,6 +631,8 @@ impl<'mm: 'up, 'up> ModuleContext<'mm, 'up> {
         };
 
         ll_fn.as_gv().set_linkage(linkage);
+        self.llvm_di_builder
+            .create_function_declaration(fn_env, ll_fn);
 
         self.fn_decls.insert(ll_native_sym_name, ll_fn);
     }
@@ -149,8 +149,14 @@ impl<'up> GlobalContext<'up> {
         let m_env = env.get_module(id);
         let modname = m_env.llvm_module_name();
         debug!(target: "dwarf", "Create DWARF for module {:#?} with source {:#?}", modname, source);
-        // DIBuilder does not depend on Builder and can be created first
-        let llvm_di_builder = llvm_cx.create_di_builder(self, llmod, source, options.debug);
+        // DIBuilder does not depend on Builder and can be created first. A module that already
+        // has the compile unit of another Move module is shared, as in whole-package compilation.
+        let debug_info = m_env.debug_info(options);
+        let llvm_di_builder = if llmod.has_compile_unit() {
+            llvm_cx.create_shared_di_builder(self, llmod, source, debug_info, options)
+        } else {
+            llvm_cx.create_di_builder(self, llmod, source, debug_info, options)
+        };
         let llvm_builder = llvm_cx.create_builder();
         let rtty_cx = RttyContext::new(self.env, &self.llvm_cx, llmod);
         ModuleContext {
@@ -345,13 +351,40 @@ impl<'mm, 'up> FunctionContext<'mm, 'up> {
             }
         }
 
-        let di_func = self.module_cx.llvm_di_builder.create_function(&self, None);
+        // The body starts at the first instruction attributed to a statement
+        // rather than to the function as a whole.
+        let fn_loc = self.env.get_loc();
+        let body_loc = fn_data
+            .code
+            .iter()
+            .map(|instr| {
+                self.env
+                    .get_bytecode_loc(instr.get_attr_id().as_usize() as u16)
+            })
+            .find(|loc| *loc != fn_loc);
+        let di_func =
+            self.module_cx
+                .llvm_di_builder
+                .create_function(&self, body_loc.as_ref(), None);
 
         // Translate instructions
         for instr in &fn_data.code {
             self.translate_instruction(instr);
         }
 
+        // Now that the blocks are filled in, locate the entry of each loop header at the loop.
+        let loop_headers = super::dwarf::loop_header_labels(&fn_data.code);
+        for instr in &fn_data.code {
+            if let sbc::Bytecode::Label(_, label) = instr {
+                if loop_headers.contains(label) {
+                    self.module_cx
+                        .llvm_di_builder
+                        .create_instruction(instr, &self)
+                        .create_loop_header(self.label_blocks[label]);
+                }
+            }
+        }
+
         self.module_cx
             .llvm_di_builder
             .finalize_function(&self, di_func);
@@ -534,11 +567,13 @@ impl<'mm, 'up> FunctionContext<'mm, 'up> {
                 let cnd_llty = self.locals[*cnd_idx].llty;
                 let bb0 = self.label_blocks[label0];
                 let bb1 = self.label_blocks[label1];
-                builder.load_cond_br(cnd_llty, cnd_llval, bb0, bb1);
+                let br = builder.load_cond_br(cnd_llty, cnd_llval, bb0, bb1);
+                instr_dbg.create_branch(br.get0());
             }
             sbc::Bytecode::Jump(_, label) => {
                 let llbb = self.label_blocks[label];
-                builder.build_br(llbb);
+                let br = builder.build_br(llbb);
+                instr_dbg.create_branch(br.get0());
             }
             sbc::Bytecode::Label(_, label) => {
                 let llbb = self.label_blocks[label];
@@ -1266,7 +1301,15 @@ impl<'mm, 'up> FunctionContext<'mm, 'up> {
                     .get_file_and_location(&loc)
                     .unwrap_or(("unknown".to_string(), Location::new(0, 0)));
                 debug!(target: "dwarf", "Op {:#?} {}:{:#?}", &op, filename, location.line.0);
-                di_builder.create_struct(self, mod_id, struct_id, &struct_name, None);
+                di_builder.create_struct(
+                    self,
+                    mod_id,
+                    struct_id,
+                    &types,
+                    &struct_name,
+                    None,
+                    false,
+                );
             }
             Operation::Unpack(mod_id, struct_id, types) => {
                 let types = mty::Type::instantiate_vec(types.to_vec(), self.type_params);
@@ -1887,7 +1930,7 @@ impl<'mm, 'up> FunctionContext<'mm, 'up> {
         (res_val.llvm_type(), res_ptr)
     }
 
-    fn emit_rtcall(&self, rtcall: RtCall, _instr: &sbc::Bytecode) {
+    fn emit_rtcall(&self, rtcall: RtCall, instr: &sbc::Bytecode) {
         match &rtcall {
             RtCall::Abort(local_idx) => {
                 let llfn = ModuleContext::get_runtime_function(
@@ -1898,11 +1941,17 @@ impl<'mm, 'up> FunctionContext<'mm, 'up> {
                 );
                 let local_llval = self.locals[*local_idx].llval;
                 let local_llty = self.locals[*local_idx].llty;
+                // The abort call carries the location of the Move `abort` or `assert!`,
+                // so a debugger stops at the failing source line, not in the runtime.
+                let instr_dbg = self
+                    .module_cx
+                    .llvm_di_builder
+                    .create_instruction(instr, self);
                 self.module_cx.llvm_builder.load_call_store(
                     llfn,
                     &[(local_llty, local_llval)],
                     &[],
-                    super::dwarf::PublicInstruction::none(),
+                    instr_dbg,
                 );
                 self.module_cx.llvm_builder.build_unreachable();
             }
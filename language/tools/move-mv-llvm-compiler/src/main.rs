@@ -214,6 +214,14 @@ fn main() -> anyhow::Result<()> {
             dot_file_path: args.dot_file_path.clone(),
             test_signers: args.test_signers.clone(),
             debug: args.debug,
+            debug_names: args.debug_names,
+            no_debug_dependencies: args.no_debug_dependencies,
+            line_tables_only: args.line_tables_only,
+            dwarf_flags: args.dwarf_flags.clone(),
+            build_id: args.build_id.clone(),
+            dwarf_version: args.dwarf_version,
+            debug_functions: args.debug_functions.clone(),
+            type_index: args.type_index.clone(),
             opt_level: args.opt_level.clone(),
             ..MoveToSolanaOptions::default()
         };
@@ -271,6 +279,10 @@ fn main() -> anyhow::Result<()> {
                 mod_src,
             );
             mod_cx.translate();
+            // The index holds the structs of all modules translated so far.
+            if let Some(type_index) = &options.type_index {
+                mod_cx.llvm_di_builder.export_type_index(type_index)?;
+            }
 
             if args.diagnostics {
                 println!("Module {} Solana llvm ir", modname);
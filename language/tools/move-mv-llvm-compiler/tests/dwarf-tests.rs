@@ -31,6 +31,7 @@
 //! - Create a test for every .move file in dwarf-tests/, for example for test basic-coin.move
 //! directort basic-coin-build is created.
 //! - Run `move-mv-llvm-compiler` with -g option. This will create *.dbg_info files.
+//! Extra compiler options may be given with a `// flags: ...` directive in the .move file.
 //! - Compare the dbg_info.actual files with dbg_info.expected files.
 
 use std::{env, path::Path};
@@ -78,11 +79,11 @@ fn run_test_inner(test_path: &Path) -> anyhow::Result<()> {
     tc::clean_results(src)?;
     std::fs::remove_dir_all(dst).ok();
 
-    tc::run_move_to_llvm_build(
-        &harness_paths,
-        &test_plan,
-        vec![&"-p".to_string(), &p_absolute_path, &"-g".to_string()],
-    )?;
+    let (package_flag, debug_flag) = ("-p".to_string(), "-g".to_string());
+    let flags = test_plan.flags();
+    let mut args = vec![&package_flag, &p_absolute_path, &debug_flag];
+    args.extend(flags.iter());
+    tc::run_move_to_llvm_build(&harness_paths, &test_plan, args)?;
 
     // remove .actual files; this will not remove.dbg_info files
     tc::clean_results(src)?;
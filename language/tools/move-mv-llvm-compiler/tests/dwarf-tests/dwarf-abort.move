@@ -0,0 +1,16 @@
+module 0x101::abort_location {
+
+    const EINSUFFICIENT: u64 = 1;
+
+    // The call to move_rt_abort lowered from the 'assert!' carries a '!dbg' location,
+    // so a debugger stopped in the runtime has a Move frame to return to.
+    public fun test_assert(balance: u64, amount: u64): u64 {
+        assert!(balance >= amount, EINSUFFICIENT);
+        balance - amount
+    }
+
+    // Likewise for an explicit 'abort', located at the 'abort' line.
+    public fun test_abort(code: u64) {
+        abort code
+    }
+}
@@ -0,0 +1,15 @@
+// flags: --debug-function 0x101::M::traced
+
+// Only 'traced' gets a subprogram and locations, 'untraced' none,
+// while the module still has its compile unit.
+module 0x101::M {
+    public fun traced(x: u64): u64 {
+        let y = x + 1;
+        y
+    }
+
+    public fun untraced(x: u64): u64 {
+        let y = x * 2;
+        y
+    }
+}
@@ -0,0 +1,12 @@
+// flags: --debug-names
+
+// With --debug-names the module gets the "Dwarf Version" 5 and
+// "Debug Info Version" module flags, which make the backend emit .debug_names.
+module 0x101::M {
+    struct Point has copy, drop { x: u64, y: u64 }
+
+    public fun make_point(x: u64, y: u64): Point {
+        let p = Point { x, y };
+        p
+    }
+}
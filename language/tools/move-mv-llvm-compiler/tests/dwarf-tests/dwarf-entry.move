@@ -0,0 +1,13 @@
+module 0x101::entry_marker {
+
+    // The DISubprogram of the entry function 'run' is external, without
+    // DISPFlagLocalToUnit, while the private 'double' stays local to the unit.
+    public entry fun run(x: u64) {
+        let y = double(x);
+        assert!(y == x + x, 1);
+    }
+
+    fun double(x: u64): u64 {
+        x * 2
+    }
+}
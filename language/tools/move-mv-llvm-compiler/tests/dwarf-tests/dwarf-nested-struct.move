@@ -0,0 +1,19 @@
+module 0x102::nested {
+
+    struct Inner has drop {
+        a: u64,
+        b: bool,
+    }
+
+    // 'Inner' is only ever packed as part of 'Outer', so its DWARF type is
+    // created while describing the 'inner' member, which then references the
+    // 'Inner' composite instead of an unspecified type.
+    struct Outer has drop {
+        inner: Inner,
+        c: u8,
+    }
+
+    public fun make(a: u64): Outer {
+        Outer { inner: Inner { a, b: true }, c: 1 }
+    }
+}
@@ -0,0 +1,13 @@
+module 0x101::reference {
+
+    struct Counter has drop {
+        value: u64,
+    }
+
+    // The parameters 'x' and 'c' have DWARF pointer types '&u64' and '&mut reference::Counter'
+    // whose size is the target's pointer size, 64 bits for the default data layout.
+    public fun test_reference(x: &u64, c: &mut Counter): u64 {
+        c.value = c.value + *x;
+        c.value
+    }
+}
@@ -0,0 +1,14 @@
+module 0x101::scope {
+
+    // The DISubprogram for 'long_header' is declared on the 'public fun' line,
+    // while its scopeLine points past the multi-line header at the first statement,
+    // so 'breakpoint set -n long_header' stops at 'let sum'.
+    public fun long_header(
+        a: u64,
+        b: u64,
+        c: u64,
+    ): u64 {
+        let sum = a + b;
+        sum * c
+    }
+}
@@ -0,0 +1,9 @@
+module 0x101::wide {
+
+    // The parameter 'x' has the single 128-bit DWARF base type 'u128' with DW_ATE_unsigned
+    // encoding, so a debugger prints 'frame variable x' as 340282366920938463463374607431768211455
+    // rather than as two 64-bit halves. Likewise 'y' has the 256-bit base type 'u256'.
+    public fun test_u128(x: u128, y: u256): u128 {
+        x + (y as u128) - 1
+    }
+}
@@ -0,0 +1,16 @@
+module 0x101::vector {
+
+    native public fun empty<Element>(): vector<Element>;
+
+    native public fun push_back<Element>(v: &mut vector<Element>, e: Element);
+
+    native public fun length<Element>(v: &vector<Element>): u64;
+
+    // The local 'v' has DWARF type 'vector<u64>' with exactly the members ptr, cap and len,
+    // where cap and len are u64.
+    public fun test_vector_header(): u64 {
+        let v: vector<u64> = empty();
+        push_back(&mut v, 7);
+        length(&v)
+    }
+}
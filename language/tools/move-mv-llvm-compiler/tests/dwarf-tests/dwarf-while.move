@@ -0,0 +1,14 @@
+module 0x101::looping {
+
+    // The first instruction of the loop header block and the back-edge branch
+    // closing each iteration are both located at the 'while' line, so stepping
+    // through the loop in lldb returns to line 8 on every iteration.
+    public fun sum_to(n: u64): u64 {
+        let (i, sum) = (0, 0);
+        while (i < n) {
+            i = i + 1;
+            sum = sum + i;
+        };
+        sum
+    }
+}
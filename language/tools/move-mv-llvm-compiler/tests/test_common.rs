@@ -147,7 +147,8 @@ pub enum TestDirective {
     Abort(u64),    // The test should abort.
     Log(String),   // Test should pass.
     Input(Input),
-    UseStdlib, // Build and link the move stdlib as bytecode
+    UseStdlib,          // Build and link the move stdlib as bytecode
+    Flags(Vec<String>), // Extra options to pass to compiler.
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -229,6 +230,18 @@ impl TestPlan {
     pub fn use_stdlib(&self) -> bool {
         self.directives.contains(&TestDirective::UseStdlib)
     }
+
+    #[allow(unused)] // not used by all test harnesses
+    pub fn flags(&self) -> Vec<String> {
+        self.directives
+            .iter()
+            .filter_map(|d| match d {
+                TestDirective::Flags(flags) => Some(flags.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
 }
 
 pub fn get_test_plan(test_path: &Path) -> anyhow::Result<TestPlan> {
@@ -304,6 +317,10 @@ fn load_directives(test_path: &Path) -> anyhow::Result<Vec<TestDirective>> {
         if line.starts_with("use-stdlib") {
             directives.push(TestDirective::UseStdlib);
         }
+        if line.starts_with("flags: ") {
+            let flags = line["flags:".len()..].split_whitespace();
+            directives.push(TestDirective::Flags(flags.map(String::from).collect()));
+        }
     }
 
     Ok(directives)